@@ -8,7 +8,10 @@ use tokio::runtime::Builder;
 
 // ----------
 
-use argan::{handler::HandlerSetter, http::Method, request::Request, resource::Resource};
+use argan::{
+	handler::HandlerSetter, host::Host, http::Method, request::Request, resource::Resource,
+	router::Router,
+};
 
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
@@ -288,6 +291,62 @@ pub fn request_routing(c: &mut Criterion) {
 		});
 	}
 
+	// -------------------------
+	// wide static resources
+
+	for width in [10, 100, 1000] {
+		let mut root = Resource::new("/");
+		for index in 0..width {
+			root.subresource_mut(format!("/static_{}", index));
+			root.subresource_mut(format!("/static_{}/static_{}", width - 1, index));
+		}
+
+		let last_pattern = format!("/static_{0}/static_{0}", width - 1);
+		root
+			.subresource_mut(&last_pattern)
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_service();
+
+		bench_group.bench_function(BenchmarkId::new("wide static segments", width), |b| {
+			b.to_async(&runtime).iter(|| async {
+				let request = Request::get(last_pattern.as_str())
+					.body(Empty::<Bytes>::new())
+					.unwrap();
+
+				let response = service.call(request).await.unwrap();
+				assert_eq!(response.status(), StatusCode::OK);
+			})
+		});
+	}
+
+	// -------------------------
+	// static hosts
+
+	for count in [10, 100, 1000] {
+		let mut router = Router::new();
+		for index in 0..count {
+			let mut root = Resource::new("/");
+			root.set_handler_for(Method::GET.to(|| async {}));
+
+			router.add_host(Host::new(format!("host-{}.example.com", index), root));
+		}
+
+		let service = router.into_service();
+		let uri = format!("http://host-{}.example.com/", count - 1);
+
+		bench_group.bench_function(BenchmarkId::new("static hosts", count), |b| {
+			b.to_async(&runtime).iter(|| async {
+				let request = Request::get(uri.as_str())
+					.body(Empty::<Bytes>::new())
+					.unwrap();
+
+				let response = service.call(request).await.unwrap();
+				assert_eq!(response.status(), StatusCode::OK);
+			})
+		});
+	}
+
 	bench_group.finish();
 }
 
@@ -50,14 +50,17 @@ pub(crate) fn host_header_value<B>(request: &Request<B>) -> Result<&str, HostHea
 		request.uri().host().ok_or(HostHeaderError::Missing)?
 	};
 
+	// An IPv6 address is given in brackets and has its own colons.
+	if let Some(ipv6_authority) = authority.strip_prefix('[') {
+		if let Some((host, _)) = ipv6_authority.split_once(']') {
+			return Ok(host);
+		}
+
+		return Ok(authority);
+	}
+
 	if let Some((host, _)) = authority.rsplit_once(':') {
-		// We have to check if the host is given as an IPv6 address.
-		return Ok(
-			host
-				.strip_suffix(']')
-				.and_then(|host| host.strip_prefix('['))
-				.unwrap_or(host),
-		);
+		return Ok(host);
 	}
 
 	Ok(authority)
@@ -116,7 +119,8 @@ pub(crate) fn split_header_value_with_weights(
 		.to_str()?
 		.split(',')
 		.try_fold::<_, _, Result<_, SplitHeaderValueError>>(Vec::new(), |mut values, value| {
-			let value = value.trim().split_once(';').map_or(
+			let value = value.trim();
+			let value = value.split_once(';').map_or(
 				Result::<_, SplitHeaderValueError>::Ok((value, 1f32)),
 				|segments| {
 					let value = segments.0.trim_end();
@@ -128,6 +132,11 @@ pub(crate) fn split_header_value_with_weights(
 
 					let quality = quality.parse::<f32>()?;
 
+					// Also rejects NaN and infinities.
+					if !(0.0..=1.0).contains(&quality) {
+						return Err(SplitHeaderValueError::InvalidQualitySpecifier);
+					}
+
 					Ok((value, quality))
 				},
 			)?;
@@ -28,6 +28,8 @@ pub mod node_properties;
 #[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 pub use node_properties::NodeCookieKey;
 
+pub use node_properties::{NodeStaticMatching, StaticMatching};
+
 pub(crate) mod header_utils;
 
 #[cfg(all(test, feature = "full"))]
@@ -2,6 +2,18 @@
 
 // ----------
 
+use std::sync::Arc;
+
+#[cfg(feature = "unicode-normalization")]
+use std::borrow::Cow;
+
+use percent_encoding::percent_decode_str;
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::pattern::{register_constraint, SegmentConstraint};
+
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
 
@@ -12,6 +24,8 @@ option! {
 	pub(crate) NodeProperty<Mark> {
 		#[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 		CookieKey(cookie::Key),
+		StaticMatching(StaticMatching),
+		SegmentConstraint(Arc<str>, Arc<dyn SegmentConstraint>),
 	}
 }
 
@@ -34,4 +48,163 @@ impl NodeCookieKey {
 	}
 }
 
+// --------------------------------------------------
+// SegmentConstraint
+
+/// A type that represents a *segment constraint* as a property.
+pub struct NodeSegmentConstraint;
+
+impl NodeSegmentConstraint {
+	/// Registers the `constraint` with the given `name` as a node property.
+	///
+	/// The constraint can be used with the `{capture:@name}` syntax in the patterns of the
+	/// node and its subtree. See [`SegmentConstraint`](crate::resource::SegmentConstraint).
+	///
+	/// # Panics
+	///
+	/// - if the name is empty or contains characters other than ASCII alphanumerics,
+	///   `'_'` and `'-'`
+	/// - if it's a name of a built-in constraint
+	pub fn to<C, Mark>(self, name: &str, constraint: C) -> NodeProperty<Mark>
+	where
+		C: SegmentConstraint,
+	{
+		let (name, constraint) = register_constraint(name, constraint);
+
+		NodeProperty::SegmentConstraint(name, constraint)
+	}
+}
+
+// --------------------------------------------------
+// StaticMatching
+
+/// A type that represents the matching rules of the static subresources as a property.
+pub struct NodeStaticMatching;
+
+impl NodeStaticMatching {
+	/// Passes the rules used to match the request's path segments against the patterns
+	/// of the resource's static subresources as a node property.
+	///
+	/// The rules set on a router are applied to all its root resources (hosts' and router's)
+	/// that don't have their own rules.
+	pub fn to<Mark>(self, static_matching: StaticMatching) -> NodeProperty<Mark> {
+		NodeProperty::StaticMatching(static_matching)
+	}
+}
+
+/// Rules to match the request's path segments against the static patterns.
+///
+/// By default, the static patterns are matched byte for byte. When the matching is relaxed,
+/// the percent-decoded path segment and pattern are compared after the normalization. If the
+/// request's path segment matches the pattern only after the normalization, the request can
+/// be redirected to the path with the registered pattern with a `301 Moved Permanently`, or
+/// with a `308 Permanent Redirect` if the request's method isn't GET or HEAD.
+///
+/// The rules apply to the static subresources of the resource and, unless they're local,
+/// to the static subresources of all the resources in its subtree that don't have their
+/// own rules.
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   common::node_properties::{NodeStaticMatching, StaticMatching},
+/// };
+///
+/// let mut root = Resource::new("/");
+///
+/// // "/Users/Profile" will be redirected to "/users/profile".
+/// root.set_property(NodeStaticMatching.to(
+///   StaticMatching::exact()
+///     .case_insensitive()
+///     .redirects_to_canonical_path(),
+/// ));
+///
+/// root.subresource_mut("/users/profile");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StaticMatching {
+	case_insensitive: bool,
+	nfc_normalized: bool,
+	local: bool,
+	redirects: bool,
+}
+
+impl StaticMatching {
+	/// Creates rules that match the static patterns byte for byte.
+	///
+	/// Can be used to turn off the relaxed matching inherited from the parent resource.
+	pub fn exact() -> Self {
+		Self::default()
+	}
+
+	/// Ignores the letter case when matching the static patterns.
+	pub fn case_insensitive(mut self) -> Self {
+		self.case_insensitive = true;
+
+		self
+	}
+
+	/// Converts the path segments and the static patterns to the Unicode Normalization
+	/// Form C before matching.
+	#[cfg(feature = "unicode-normalization")]
+	pub fn nfc_normalized(mut self) -> Self {
+		self.nfc_normalized = true;
+
+		self
+	}
+
+	/// Applies the rules only to the resource's own static subresources. The subtree
+	/// keeps the rules inherited from the parent resource.
+	pub fn local(mut self) -> Self {
+		self.local = true;
+
+		self
+	}
+
+	/// Redirects the requests whose path segment matches the static pattern only after
+	/// the normalization to the path with the registered pattern.
+	///
+	/// The request is redirected when it reaches the resource that handles it, and all
+	/// such segments of the path are replaced in a single redirect. Requests that don't
+	/// reach a resource that handles them aren't redirected.
+	pub fn redirects_to_canonical_path(mut self) -> Self {
+		self.redirects = true;
+
+		self
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_exact(&self) -> bool {
+		!(self.case_insensitive || self.nfc_normalized)
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_local(&self) -> bool {
+		self.local
+	}
+
+	#[inline(always)]
+	pub(crate) fn redirects(&self) -> bool {
+		self.redirects
+	}
+
+	// Returns the percent-decoded and normalized value of the path segment or static pattern.
+	pub(crate) fn normalize(&self, value: &str) -> String {
+		let decoded_value = percent_decode_str(value).decode_utf8_lossy();
+
+		#[cfg(feature = "unicode-normalization")]
+		let decoded_value: Cow<str> = if self.nfc_normalized {
+			Cow::Owned(decoded_value.nfc().collect())
+		} else {
+			decoded_value
+		};
+
+		if self.case_insensitive {
+			decoded_value.to_lowercase()
+		} else {
+			decoded_value.into_owned()
+		}
+	}
+}
+
 // --------------------------------------------------------------------------------
@@ -2,6 +2,11 @@
 
 // ----------
 
+use std::{
+	convert::Infallible,
+	future::{ready, Future},
+};
+
 use argan_core::response::ResponseHeadParts;
 use cookie::CookieJar as InnerCookieJar;
 use http::{
@@ -11,6 +16,8 @@ use http::{
 
 use crate::{
 	common::IntoArray,
+	handler::Args,
+	request::{ExtractorGuard, RequestContext},
 	response::{BoxedErrorResponse, IntoResponse, IntoResponseHeadParts, Response},
 };
 
@@ -270,6 +277,22 @@ pub(crate) fn cookies_from_request(
 
 // -------------------------
 
+impl<B, Ext> ExtractorGuard<B, Ext> for CookieJar
+where
+	Ext: Clone,
+{
+	type Error = Infallible;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		ready(Ok(request_context.cookies()))
+	}
+}
+
+// -------------------------
+
 impl IntoResponseHeadParts for CookieJar {
 	fn into_response_head(
 		self,
@@ -1,7 +1,7 @@
 use std::{
 	convert::Infallible,
 	future::Future,
-	pin::{pin, Pin},
+	pin::Pin,
 	task::{Context, Poll},
 };
 
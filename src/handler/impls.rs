@@ -8,6 +8,7 @@ use bytes::Bytes;
 
 use crate::{
 	common::marker::Private,
+	pattern::ExpectedParams,
 	request::{ExtractorGuard, FromRequest, RequestContext, RequestHead},
 	response::{BoxedErrorResponse, IntoResponseResult, Response},
 };
@@ -229,6 +230,22 @@ macro_rules! request_args_handler_fn {
 			fn into_handler(self) -> Self::Handler {
 				HandlerFn::from(self)
 			}
+
+			#[allow(private_interfaces, unused_mut)]
+			fn expected_path_params() -> Option<ExpectedParams> {
+				let mut some_expected_params: Option<ExpectedParams> = None;
+
+				$(
+					if let Some(expected_params) = <$G as ExtractorGuard<Body, Ext>>::expected_path_params() {
+						some_expected_params = Some(match some_expected_params {
+							Some(previous_expected_params) => previous_expected_params.merge(expected_params),
+							None => expected_params,
+						});
+					}
+				)*
+
+				some_expected_params
+			}
 		}
 
 		#[allow(unused)]
@@ -10,9 +10,13 @@ use http::Method;
 use crate::{
 	common::marker::Sealed,
 	http::{CustomMethod, WildcardMethod},
+	pattern::ExpectedParams,
 	request::MistargetedRequest,
 };
 
+#[cfg(feature = "openapi")]
+use crate::openapi::Operation;
+
 use super::{BoxableHandler, BoxedHandler, FinalHandler, IntoHandler};
 
 // --------------------------------------------------------------------------------
@@ -25,9 +29,79 @@ mod private {
 
 	#[allow(private_interfaces)]
 	pub enum HandlerKind {
-		Method(Method, BoxedHandler),
-		WildcardMethod(Option<BoxedHandler>),
-		MistargetedRequest(BoxedHandler),
+		Method(Method, BoxedHandler, Option<ExpectedParams>),
+		WildcardMethod(Option<BoxedHandler>, Option<ExpectedParams>),
+		MistargetedRequest(BoxedHandler, Option<ExpectedParams>),
+		#[cfg(feature = "openapi")]
+		WithOperation(Box<HandlerKind>, Operation),
+	}
+
+	impl HandlerKind {
+		/// Declares the names of the path params the handler expects to be captured by
+		/// the resource's route.
+		///
+		/// The declared names replace the ones derived from the handler's
+		/// [`PathParams`](crate::request::PathParams) guard and are checked when the
+		/// resource tree is converted into a service.
+		///
+		/// ```
+		/// use argan::{Resource, handler::HandlerSetter, http::Method, request::RequestHead};
+		///
+		/// async fn handler(head: RequestHead) {
+		///   let id = head.path_params_as::<u32>();
+		///   // ...
+		/// }
+		///
+		/// let mut resource = Resource::new("/items/{id}");
+		/// resource.set_handler_for(Method::GET.to(handler).expecting_path_params(["id"]));
+		/// ```
+		///
+		/// # Panics
+		///
+		/// - if the handler kind is a *wildcard method* without a handler
+		pub fn expecting_path_params<const N: usize>(self, names: [&str; N]) -> Self {
+			let expected_params = Some(ExpectedParams::with_required_names(names));
+
+			match self {
+				Self::Method(method, handler, _) => Self::Method(method, handler, expected_params),
+				Self::WildcardMethod(Some(handler), _) => {
+					Self::WildcardMethod(Some(handler), expected_params)
+				}
+				Self::WildcardMethod(None, _) => {
+					panic!("a wildcard method without a handler cannot expect path params")
+				}
+				Self::MistargetedRequest(handler, _) => Self::MistargetedRequest(handler, expected_params),
+				#[cfg(feature = "openapi")]
+				Self::WithOperation(handler_kind, operation) => Self::WithOperation(
+					Box::new(handler_kind.expecting_path_params(names)),
+					operation,
+				),
+			}
+		}
+
+		/// Attaches the OpenAPI operation metadata to the *method* handler.
+		///
+		/// ```
+		/// use argan::{Resource, handler::HandlerSetter, http::Method, openapi::Operation};
+		///
+		/// let mut resource = Resource::new("/users/{id}");
+		/// resource.set_handler_for(
+		///   Method::GET
+		///     .to(|| async {})
+		///     .with_operation(Operation::new().with_summary("Get a user").with_tag("users")),
+		/// );
+		/// ```
+		///
+		/// # Panics
+		///
+		/// - if the handler is not a *method* handler (when the handler is being set)
+		#[cfg(feature = "openapi")]
+		pub fn with_operation(self, operation: Operation) -> Self {
+			match self {
+				Self::WithOperation(handler_kind, _) => Self::WithOperation(handler_kind, operation),
+				handler_kind => Self::WithOperation(Box::new(handler_kind), operation),
+			}
+		}
 	}
 
 	impl IntoArray<HandlerKind, 1> for HandlerKind {
@@ -56,9 +130,10 @@ impl HandlerSetter for Method {
 		H: IntoHandler<Mark, Body>,
 		H::Handler: BoxableHandler + Clone + Send + Sync + 'static,
 	{
+		let expected_params = H::expected_path_params();
 		let final_handler = handler.into_handler();
 
-		HandlerKind::Method(self, final_handler.into_boxed_handler())
+		HandlerKind::Method(self, final_handler.into_boxed_handler(), expected_params)
 	}
 }
 
@@ -75,9 +150,10 @@ impl<M: AsRef<str>> CustomMethod<M> {
 		let method = Method::from_str(self.0.as_ref())
 			.expect("HTTP method should be a valid token [RFC 9110, 5.6.2 Tokens]");
 
+		let expected_params = H::expected_path_params();
 		let final_handler = handler.into_handler();
 
-		HandlerKind::Method(method, final_handler.into_boxed_handler())
+		HandlerKind::Method(method, final_handler.into_boxed_handler(), expected_params)
 	}
 }
 
@@ -96,13 +172,17 @@ impl WildcardMethod {
 		H: IntoHandler<Mark, Body>,
 		H::Handler: BoxableHandler + Clone + Send + Sync + 'static,
 	{
+		let expected_params = some_handler
+			.as_ref()
+			.and_then(|_| H::expected_path_params());
+
 		let some_final_handler = some_handler.map(|handler| {
 			let final_handler = handler.into_handler();
 
 			final_handler.into_boxed_handler()
 		});
 
-		HandlerKind::WildcardMethod(some_final_handler)
+		HandlerKind::WildcardMethod(some_final_handler, expected_params)
 	}
 }
 
@@ -119,9 +199,10 @@ impl MistargetedRequest {
 		H: IntoHandler<Mark, Body>,
 		H::Handler: BoxableHandler + Clone + Send + Sync + 'static,
 	{
+		let expected_params = H::expected_path_params();
 		let final_handler = handler.into_handler();
 
-		HandlerKind::MistargetedRequest(final_handler.into_boxed_handler())
+		HandlerKind::MistargetedRequest(final_handler.into_boxed_handler(), expected_params)
 	}
 }
 
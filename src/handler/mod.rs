@@ -22,6 +22,7 @@ use tower_service::Service as TowerService;
 use crate::{
 	common::{IntoArray, NodeExtension, Uncloneable},
 	middleware::Layer,
+	pattern::ExpectedParams,
 	request::{routing::RoutingState, RequestContext, RequestContextProperties},
 	response::{BoxedErrorResponse, Response},
 };
@@ -96,6 +97,13 @@ pub trait IntoHandler<Mark, B = Body, Ext: Clone = ()>: Sized {
 	fn wrapped_in<L: Layer<Self::Handler>>(self, layer: L) -> L::Handler {
 		layer.wrap(self.into_handler())
 	}
+
+	// Names of the path params the handler expects to be captured by the resource's route.
+	#[doc(hidden)]
+	#[allow(private_interfaces)]
+	fn expected_path_params() -> Option<ExpectedParams> {
+		None
+	}
 }
 
 impl<H, B, Ext> IntoHandler<(), B, Ext> for H
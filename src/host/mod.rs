@@ -4,8 +4,9 @@
 
 use crate::{
 	common::{IntoArray, SCOPE_VALIDITY},
-	pattern::{Pattern, Similarity},
-	resource::Resource,
+	pattern::{Pattern, RegisteredConstraint, Similarity},
+	resource::{NamedRoutes, Resource},
+	router::RouterReloadError,
 };
 
 // --------------------------------------------------
@@ -77,7 +78,7 @@ impl Host {
 	/// Checks whether the given `pattern` is the `Host`'s pattern.
 	#[inline(always)]
 	pub fn is<P: AsRef<str>>(&self, pattern: P) -> bool {
-		let pattern = Pattern::parse(pattern.as_ref());
+		let pattern = Pattern::parse_host(pattern.as_ref());
 
 		self.pattern.compare(&pattern) == Similarity::Same
 	}
@@ -97,6 +98,11 @@ impl Host {
 		self.pattern.compare(other_host_pattern)
 	}
 
+	#[inline(always)]
+	pub(crate) fn root_ref(&self) -> &Resource {
+		&self.root_resource
+	}
+
 	#[inline(always)]
 	pub(crate) fn root_mut(&mut self) -> &mut Resource {
 		&mut self.root_resource
@@ -142,6 +148,20 @@ impl Host {
 		(pattern, root_resource)
 	}
 
+	// Resolves the registered constraints of the host pattern and the patterns in the
+	// resource tree.
+	pub(crate) fn resolve_segment_constraints<'c>(
+		&'c self,
+		constraints: &mut Vec<&'c RegisteredConstraint>,
+	) -> Result<(), RouterReloadError> {
+		let constraints_count = constraints.len();
+		constraints.extend(self.root_resource.segment_constraints_ref());
+		self.pattern.resolve_constraint(constraints)?;
+		constraints.truncate(constraints_count);
+
+		self.root_resource.resolve_segment_constraints(constraints)
+	}
+
 	pub(crate) fn finalize(self) -> FinalHost {
 		let Host {
 			pattern,
@@ -152,8 +172,31 @@ impl Host {
 	}
 
 	/// Converts the `Host` into a service.
+	///
+	/// # Panics
+	///
+	/// - if some handler expects a path param that its resource's route doesn't capture
+	///   (see [`Resource::check_path_params()`])
+	/// - if two resources in the resource tree have the same name
+	/// - if some pattern uses a segment constraint that isn't registered on its resource
+	///   or the resource's parents
 	#[inline(always)]
-	pub fn into_service(self) -> HostService {
+	pub fn into_service(mut self) -> HostService {
+		if let Err(error) = self.resolve_segment_constraints(&mut Vec::new()) {
+			panic!("{}", error)
+		}
+
+		if let Err(error) = self.root_resource.check_path_params() {
+			error.panic_on_mismatches();
+		}
+
+		let mut named_routes = NamedRoutes::default();
+		if let Err(error) = self.root_resource.collect_named_routes(&mut named_routes) {
+			panic!("{}", error)
+		}
+
+		self.root_resource.set_named_routes(named_routes);
+
 		HostService::new(self.finalize())
 	}
 
@@ -194,7 +237,7 @@ pub(crate) fn parse_host_pattern<P: AsRef<str>>(
 
 	let host_pattern = host_pattern_str
 		.strip_suffix('/')
-		.map_or(Pattern::parse(host_pattern_str), Pattern::parse);
+		.map_or(Pattern::parse_host(host_pattern_str), Pattern::parse_host);
 
 	if host_pattern.is_wildcard() {
 		return Err(HostPatternError::Wildcard);
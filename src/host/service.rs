@@ -8,6 +8,7 @@ use hyper::service::Service;
 use crate::{
 	common::{header_utils::host_header_value, marker::Sealed, CloneWithPeerAddr},
 	handler::Args,
+	pattern::ParamsList,
 	request::{
 		routing::{RouteTraversal, RoutingState},
 		Request, RequestContext, RequestContextProperties,
@@ -16,9 +17,6 @@ use crate::{
 	response::{BoxedErrorResponse, InfallibleResponseFuture, IntoResponse, Response},
 };
 
-#[cfg(feature = "regex")]
-use crate::pattern::ParamsList;
-
 use super::*;
 
 // --------------------------------------------------------------------------------
@@ -39,14 +37,14 @@ impl FinalHost {
 	}
 
 	#[inline(always)]
-	pub(crate) fn is_static_match(&self, text: &str) -> Option<bool> {
-		self.pattern.is_static_match(text)
-	}
-
-	#[cfg(feature = "regex")]
-	#[inline(always)]
-	pub(crate) fn is_regex_match(&self, text: &str, params_list: &mut ParamsList) -> Option<bool> {
-		self.pattern.is_regex_match(text, params_list)
+	pub(crate) fn is_regex_or_constrained_match(
+		&self,
+		text: &str,
+		params_list: &mut ParamsList,
+	) -> Option<bool> {
+		self
+			.pattern
+			.is_regex_or_constrained_match(text, params_list)
 	}
 
 	#[inline(always)]
@@ -176,11 +174,10 @@ where
 			);
 		}
 
-		#[cfg(feature = "regex")]
 		if let Some(true) = self
 			.host_ref()
 			.pattern
-			.is_regex_match(host, &mut routing_state.uri_params)
+			.is_regex_or_constrained_match(host, &mut routing_state.uri_params)
 		{
 			let request_context = RequestContext::new(
 				#[cfg(feature = "peer-addr")]
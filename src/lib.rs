@@ -50,4 +50,10 @@ pub use server::Server;
 
 pub mod prelude;
 
+#[cfg(feature = "test-util")]
+pub mod test_client;
+
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
 // --------------------------------------------------------------------------------
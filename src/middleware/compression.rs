@@ -0,0 +1,1180 @@
+use std::{
+	collections::VecDeque,
+	io::{Error as IoError, ErrorKind as IoErrorKind, Write},
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use argan_core::{
+	body::{Body, Bytes, Frame, HttpBody},
+	BoxedError,
+};
+use brotli::{
+	BrotliDecompressStream, BrotliResult, BrotliState, CompressorWriter, HeapAlloc, HuffmanCode,
+};
+use flate2::{
+	write::{GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder},
+	Compression,
+};
+use http::{
+	header::{
+		GetAll, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_RANGE,
+		CONTENT_TYPE, VARY,
+	},
+	HeaderMap, HeaderValue, StatusCode,
+};
+use http_body_util::BodyExt;
+
+use crate::{
+	common::{
+		header_utils::{header_value_has_value, split_header_value_with_weights},
+		SCOPE_VALIDITY,
+	},
+	handler::Args,
+	request::RequestContext,
+};
+
+use super::*;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+const DEFAULT_MIN_SIZE_TO_COMPRESS: u64 = 1024;
+const DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+
+const BROTLI_BUFFER_SIZE: usize = 8 * 1024;
+const BROTLI_LG_WINDOW_SIZE: u32 = 22; // Recommended size (brotli crate).
+
+const SUPPORTED_CODINGS: &str = "br, gzip, deflate";
+
+// The maximum size of the decoded data produced in one step.
+const DECODER_OUTPUT_SIZE: usize = 32 * 1024;
+
+// --------------------------------------------------
+// CompressionLayer
+
+/// A layer that applies a response compression middleware to a [`Handler`].
+///
+/// The middleware negotiates the coding with the request's `Accept-Encoding` and compresses
+/// the response body with `br`, `gzip`, or `deflate` while it's being streamed. The responses
+/// that are already encoded, smaller than the minimum size, or have a content type that
+/// isn't compressible are left as is. `text/event-stream` responses are flushed after each
+/// chunk, so the events are sent to the client without delay.
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   handler::HandlerSetter,
+///   http::Method,
+///   middleware::{CompressionLayer, RequestReceiver},
+/// };
+///
+/// let mut root = Resource::new("/");
+/// root
+///   .subresource_mut("/items")
+///   .set_handler_for(Method::GET.to(|| async { "items" }));
+///
+/// root.wrap(RequestReceiver.component_in(CompressionLayer::new().with_min_size(512)));
+/// ```
+#[derive(Clone)]
+pub struct CompressionLayer {
+	min_size: u64,
+	level: u32,
+}
+
+impl CompressionLayer {
+	/// Creates a new `CompressionLayer`.
+	pub fn new() -> Self {
+		Self {
+			min_size: DEFAULT_MIN_SIZE_TO_COMPRESS,
+			level: DEFAULT_COMPRESSION_LEVEL,
+		}
+	}
+
+	/// Minimum body size to compress. Default is 1 KiB.
+	///
+	/// The body size is taken from the `Content-Length`. Bodies of unknown size are buffered
+	/// until they reach the minimum size. `text/event-stream` bodies are always compressed.
+	pub fn with_min_size(mut self, min_size: u64) -> Self {
+		self.min_size = min_size;
+
+		self
+	}
+
+	/// Compression level. Default is 6.
+	pub fn with_level(mut self, level: u32) -> Self {
+		self.level = level;
+
+		self
+	}
+}
+
+impl Default for CompressionLayer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<H> Layer<H> for CompressionLayer {
+	type Handler = Compressor<H>;
+
+	fn wrap(&self, handler: H) -> Self::Handler {
+		Compressor::new(handler, self.min_size, self.level)
+	}
+}
+
+// --------------------------------------------------
+// DecompressionLayer
+
+/// A layer that applies a request body decompression middleware to a [`Handler`].
+///
+/// The middleware decodes the `br`, `gzip`, and `deflate` request bodies while they're being
+/// read. So the extractors receive the decoded data, and their size limits apply to the
+/// decoded size. Requests with an unsupported `Content-Encoding` are rejected with
+/// [`UnsupportedContentEncodingError`].
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   data::Text,
+///   handler::HandlerSetter,
+///   http::Method,
+///   middleware::{DecompressionLayer, RequestReceiver},
+/// };
+///
+/// let mut root = Resource::new("/");
+/// root
+///   .subresource_mut("/notes")
+///   .set_handler_for(Method::POST.to(|Text(note): Text| async move { note }));
+///
+/// root.wrap(RequestReceiver.component_in(DecompressionLayer));
+/// ```
+#[derive(Clone, Default)]
+pub struct DecompressionLayer;
+
+impl<H> Layer<H> for DecompressionLayer {
+	type Handler = Decompressor<H>;
+
+	fn wrap(&self, handler: H) -> Self::Handler {
+		Decompressor::new(handler)
+	}
+}
+
+// -------------------------
+
+/// An error type that's returned by the [`DecompressionLayer`]'s middleware when the
+/// request's `Content-Encoding` isn't supported.
+///
+/// The error is converted into a "415 Unsupported Media Type" response with an
+/// `Accept-Encoding` header that lists the supported codings.
+#[derive(Debug, crate::ImplError)]
+#[error("unsupported content encoding '{0}'")]
+pub struct UnsupportedContentEncodingError(Box<str>);
+
+impl IntoResponse for UnsupportedContentEncodingError {
+	fn into_response(self) -> Response {
+		let mut response = StatusCode::UNSUPPORTED_MEDIA_TYPE.into_response();
+		response
+			.headers_mut()
+			.insert(ACCEPT_ENCODING, HeaderValue::from_static(SUPPORTED_CODINGS));
+
+		response
+	}
+}
+
+// --------------------------------------------------
+
+mod private {
+	use super::*;
+
+	// --------------------------------------------------
+	// Compressor
+
+	#[derive(Clone)]
+	pub struct Compressor<H> {
+		inner_handler: H,
+		min_size: u64,
+		level: u32,
+	}
+
+	impl<H> Compressor<H> {
+		pub(super) fn new(handler: H, min_size: u64, level: u32) -> Self {
+			Self {
+				inner_handler: handler,
+				min_size,
+				level,
+			}
+		}
+	}
+
+	impl<H, B, Ext> Handler<B, Ext> for Compressor<H>
+	where
+		H: Handler<
+			B,
+			Ext,
+			Response = Response,
+			Error = BoxedErrorResponse,
+			Future = BoxedFuture<Result<Response, BoxedErrorResponse>>,
+		>,
+		Ext: Clone,
+	{
+		type Response = Response;
+		type Error = BoxedErrorResponse;
+		type Future = BoxedFuture<Result<Self::Response, Self::Error>>;
+
+		fn handle(&self, request_context: RequestContext<B>, args: Args<'_, Ext>) -> Self::Future {
+			let some_coding = request_context
+				.headers_ref()
+				.get(ACCEPT_ENCODING)
+				.and_then(negotiate_coding);
+
+			let response_future = self.inner_handler.handle(request_context, args);
+			let min_size = self.min_size;
+			let level = self.level;
+
+			Box::pin(async move {
+				let response = response_future.await?;
+
+				Ok(compress_response(response, some_coding, min_size, level).await)
+			})
+		}
+	}
+
+	// --------------------------------------------------
+	// Decompressor
+
+	#[derive(Clone)]
+	pub struct Decompressor<H> {
+		inner_handler: H,
+	}
+
+	impl<H> Decompressor<H> {
+		pub(super) fn new(handler: H) -> Self {
+			Self {
+				inner_handler: handler,
+			}
+		}
+	}
+
+	impl<H, Ext> Handler<Body, Ext> for Decompressor<H>
+	where
+		H: Handler<
+			Body,
+			Ext,
+			Response = Response,
+			Error = BoxedErrorResponse,
+			Future = BoxedFuture<Result<Response, BoxedErrorResponse>>,
+		>,
+		Ext: Clone,
+	{
+		type Response = Response;
+		type Error = BoxedErrorResponse;
+		type Future = BoxedFuture<Result<Self::Response, Self::Error>>;
+
+		fn handle(&self, mut request_context: RequestContext, args: Args<'_, Ext>) -> Self::Future {
+			let content_encoding = request_context.headers_ref().get_all(CONTENT_ENCODING);
+			if content_encoding.iter().next().is_none() {
+				return self.inner_handler.handle(request_context, args);
+			}
+
+			let codings = match content_codings(content_encoding) {
+				Ok(codings) => codings,
+				Err(error) => return Box::pin(async move { Err(error.into()) }),
+			};
+
+			let headers = request_context.request_mut().headers_mut();
+			headers.remove(CONTENT_ENCODING);
+
+			if codings.is_empty() {
+				return self.inner_handler.handle(request_context, args);
+			}
+
+			headers.remove(CONTENT_LENGTH);
+
+			// The codings are listed in the order they were applied.
+			let request_context = request_context.map(|body| {
+				codings.into_iter().rev().fold(body, |body, coding| {
+					Body::new(DecompressedBody::new(body, coding))
+				})
+			});
+
+			self.inner_handler.handle(request_context, args)
+		}
+	}
+}
+
+pub(crate) use private::{Compressor, Decompressor};
+
+// --------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coding {
+	Brotli,
+	Gzip,
+	Deflate,
+}
+
+impl Coding {
+	fn from_name(name: &str) -> Option<Self> {
+		if name.eq_ignore_ascii_case("br") {
+			Some(Self::Brotli)
+		} else if name.eq_ignore_ascii_case("gzip") {
+			Some(Self::Gzip)
+		} else if name.eq_ignore_ascii_case("deflate") {
+			Some(Self::Deflate)
+		} else {
+			None
+		}
+	}
+
+	fn header_value(self) -> HeaderValue {
+		match self {
+			Self::Brotli => HeaderValue::from_static("br"),
+			Self::Gzip => HeaderValue::from_static("gzip"),
+			Self::Deflate => HeaderValue::from_static("deflate"),
+		}
+	}
+}
+
+// Returns the coding with the highest weight. Invalid values are treated as if there was
+// no `Accept-Encoding`.
+fn negotiate_coding(accept_encoding: &HeaderValue) -> Option<Coding> {
+	let elements = split_header_value_with_weights(accept_encoding).ok()?;
+
+	elements.iter().find_map(|&(coding, weight)| {
+		if weight <= 0.0 {
+			return None;
+		}
+
+		if coding == "*" {
+			// The wildcard matches only the codings that aren't listed explicitly.
+			[Coding::Gzip, Coding::Brotli, Coding::Deflate]
+				.into_iter()
+				.find(|wildcard_coding| {
+					!elements
+						.iter()
+						.any(|(name, _)| Coding::from_name(name) == Some(*wildcard_coding))
+				})
+		} else {
+			Coding::from_name(coding)
+		}
+	})
+}
+
+// Returns the content codings of all the header lines in the order they were applied.
+// The `identity` is omitted.
+fn content_codings(
+	content_encoding: GetAll<'_, HeaderValue>,
+) -> Result<Vec<Coding>, UnsupportedContentEncodingError> {
+	let mut codings = Vec::new();
+
+	for value in content_encoding {
+		let unsupported_content_encoding =
+			|| UnsupportedContentEncodingError(String::from_utf8_lossy(value.as_bytes()).into());
+
+		let value = value.to_str().map_err(|_| unsupported_content_encoding())?;
+
+		for name in value.split(',').map(str::trim) {
+			if name.is_empty() || name.eq_ignore_ascii_case("identity") {
+				continue;
+			}
+
+			codings.push(Coding::from_name(name).ok_or_else(unsupported_content_encoding)?);
+		}
+	}
+
+	Ok(codings)
+}
+
+async fn compress_response(
+	mut response: Response,
+	some_coding: Option<Coding>,
+	min_size: u64,
+	level: u32,
+) -> Response {
+	let headers = response.headers();
+
+	if headers.contains_key(CONTENT_ENCODING)
+		|| headers.contains_key(CONTENT_RANGE)
+		|| !has_compressible_content_type(headers)
+	{
+		return response;
+	}
+
+	response
+		.headers_mut()
+		.append(VARY, HeaderValue::from_static("accept-encoding"));
+
+	let Some(coding) = some_coding else {
+		return response;
+	};
+
+	let headers = response.headers();
+
+	if response.status() == StatusCode::NO_CONTENT
+		|| response.status() == StatusCode::NOT_MODIFIED
+		|| headers
+			.get(CACHE_CONTROL)
+			.is_some_and(|value| header_value_has_value(value, "no-transform"))
+	{
+		return response;
+	}
+
+	let some_content_length = headers
+		.get(CONTENT_LENGTH)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.parse::<u64>().ok());
+
+	if some_content_length.is_some_and(|content_length| content_length < min_size) {
+		return response;
+	}
+
+	let flushes_each_chunk = headers
+		.get(CONTENT_TYPE)
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|value| mime_essence(value).eq_ignore_ascii_case("text/event-stream"));
+
+	let (mut head, mut body) = response.into_parts();
+
+	if some_content_length.is_none() && !flushes_each_chunk && min_size > 0 {
+		let mut buffered_body = BufferedBody::new(body);
+		let reached_min_size = buffered_body.buffer(min_size).await;
+		body = Body::new(buffered_body);
+
+		if !reached_min_size {
+			return Response::from_parts(head, body);
+		}
+	}
+
+	head.headers.remove(CONTENT_LENGTH);
+	head.headers.insert(CONTENT_ENCODING, coding.header_value());
+
+	let body = Body::new(CompressedBody {
+		inner: body,
+		some_encoder: Some(Encoder::new(coding, level)),
+		flushes_each_chunk,
+		some_trailers: None,
+	});
+
+	Response::from_parts(head, body)
+}
+
+fn has_compressible_content_type(headers: &HeaderMap) -> bool {
+	let Some(content_type) = headers
+		.get(CONTENT_TYPE)
+		.and_then(|value| value.to_str().ok())
+	else {
+		return false;
+	};
+
+	let essence = mime_essence(content_type).to_ascii_lowercase();
+
+	let Some((type_, subtype)) = essence.split_once('/') else {
+		return false;
+	};
+
+	type_ == "text"
+		|| subtype == "json"
+		|| subtype == "xml"
+		|| subtype == "javascript"
+		|| subtype.ends_with("+json")
+		|| subtype.ends_with("+xml")
+}
+
+#[inline(always)]
+fn mime_essence(content_type: &str) -> &str {
+	content_type
+		.split_once(';')
+		.map_or(content_type, |(essence, _)| essence)
+		.trim()
+}
+
+// --------------------------------------------------
+// Encoder
+
+enum Encoder {
+	Brotli(Box<CompressorWriter<Vec<u8>>>),
+	Gzip(GzEncoder<Vec<u8>>),
+	Deflate(ZlibEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+	fn new(coding: Coding, level: u32) -> Self {
+		match coding {
+			Coding::Brotli => Self::Brotli(Box::new(CompressorWriter::new(
+				Vec::new(),
+				BROTLI_BUFFER_SIZE,
+				level,
+				BROTLI_LG_WINDOW_SIZE,
+			))),
+			Coding::Gzip => Self::Gzip(GzEncoder::new(Vec::new(), Compression::new(level))),
+			Coding::Deflate => Self::Deflate(ZlibEncoder::new(Vec::new(), Compression::new(level))),
+		}
+	}
+
+	// Returns the data that has been compressed so far.
+	fn encode(&mut self, data: &[u8], flush: bool) -> Result<Bytes, IoError> {
+		let writer: &mut dyn Write = match self {
+			Self::Brotli(encoder) => encoder.as_mut(),
+			Self::Gzip(encoder) => encoder,
+			Self::Deflate(encoder) => encoder,
+		};
+
+		writer.write_all(data)?;
+
+		if flush {
+			writer.flush()?;
+		}
+
+		let output = match self {
+			Self::Brotli(encoder) => encoder.get_mut(),
+			Self::Gzip(encoder) => encoder.get_mut(),
+			Self::Deflate(encoder) => encoder.get_mut(),
+		};
+
+		Ok(std::mem::take(output).into())
+	}
+
+	// Returns the rest of the compressed data.
+	fn finish(self) -> Result<Bytes, IoError> {
+		let output = match self {
+			Self::Brotli(encoder) => encoder.into_inner(),
+			Self::Gzip(encoder) => encoder.finish()?,
+			Self::Deflate(encoder) => encoder.finish()?,
+		};
+
+		Ok(output.into())
+	}
+}
+
+// --------------------------------------------------
+// BufferedBody
+
+// A body that yields the buffered frames before the rest of the inner body's frames.
+struct BufferedBody {
+	frames: VecDeque<Result<Frame<Bytes>, BoxedError>>,
+	some_inner: Option<Body>,
+}
+
+impl BufferedBody {
+	fn new(inner: Body) -> Self {
+		Self {
+			frames: VecDeque::new(),
+			some_inner: Some(inner),
+		}
+	}
+
+	// Buffers the inner body's frames until their data reaches the given size. Returns
+	// `false` if the body has ended or failed before that.
+	async fn buffer(&mut self, size: u64) -> bool {
+		let mut buffered_size = 0;
+
+		while buffered_size < size {
+			let Some(inner) = self.some_inner.as_mut() else {
+				return false;
+			};
+
+			match inner.frame().await {
+				Some(Ok(frame)) => {
+					if let Some(data) = frame.data_ref() {
+						buffered_size += data.len() as u64;
+					}
+
+					self.frames.push_back(Ok(frame));
+				}
+				Some(Err(error)) => {
+					self.frames.push_back(Err(error));
+					self.some_inner = None;
+				}
+				None => self.some_inner = None,
+			}
+		}
+
+		true
+	}
+}
+
+impl HttpBody for BufferedBody {
+	type Data = Bytes;
+	type Error = BoxedError;
+
+	fn poll_frame(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		if let Some(frame_result) = self.frames.pop_front() {
+			return Poll::Ready(Some(frame_result));
+		}
+
+		match self.some_inner.as_mut() {
+			Some(inner) => Pin::new(inner).poll_frame(cx),
+			None => Poll::Ready(None),
+		}
+	}
+}
+
+// --------------------------------------------------
+// CompressedBody
+
+struct CompressedBody {
+	inner: Body,
+	some_encoder: Option<Encoder>,
+	flushes_each_chunk: bool,
+	some_trailers: Option<Frame<Bytes>>,
+}
+
+impl HttpBody for CompressedBody {
+	type Data = Bytes;
+	type Error = BoxedError;
+
+	fn poll_frame(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		loop {
+			if self.some_encoder.is_none() {
+				return Poll::Ready(self.some_trailers.take().map(Ok));
+			}
+
+			match Pin::new(&mut self.inner).poll_frame(cx) {
+				Poll::Ready(Some(Ok(frame))) => match frame.into_data() {
+					Ok(data) => {
+						let flush = self.flushes_each_chunk;
+						let encoder = self.some_encoder.as_mut().expect(SCOPE_VALIDITY);
+
+						let compressed_data = encoder.encode(&data, flush)?;
+						if !compressed_data.is_empty() {
+							return Poll::Ready(Some(Ok(Frame::data(compressed_data))));
+						}
+					}
+					Err(frame) => {
+						// Trailers must come after the end of the compressed data.
+						self.some_trailers = Some(frame);
+
+						let encoder = self.some_encoder.take().expect(SCOPE_VALIDITY);
+
+						return Poll::Ready(Some(Ok(Frame::data(encoder.finish()?))));
+					}
+				},
+				Poll::Ready(Some(Err(error))) => return Poll::Ready(Some(Err(error))),
+				Poll::Ready(None) => {
+					let encoder = self.some_encoder.take().expect(SCOPE_VALIDITY);
+
+					return Poll::Ready(Some(Ok(Frame::data(encoder.finish()?))));
+				}
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+}
+
+// --------------------------------------------------
+// Decoder
+
+enum Decoder {
+	Brotli(Box<BrotliDecoder>),
+	Gzip(GzDecoder<Vec<u8>>),
+	Deflate(ZlibDecoder<Vec<u8>>),
+}
+
+impl Decoder {
+	fn new(coding: Coding) -> Self {
+		match coding {
+			Coding::Brotli => Self::Brotli(Box::new(BrotliDecoder::new())),
+			Coding::Gzip => Self::Gzip(GzDecoder::new(Vec::new())),
+			Coding::Deflate => Self::Deflate(ZlibDecoder::new(Vec::new())),
+		}
+	}
+
+	// Decodes some of the data and returns the number of consumed bytes with the decoded data.
+	// The decoded data doesn't exceed the `DECODER_OUTPUT_SIZE`. An empty `data` makes the
+	// decoder produce the rest of the decoded data of the previously consumed bytes.
+	fn decode(&mut self, data: &[u8]) -> Result<(usize, Bytes), IoError> {
+		let (consumed_size, output) = match self {
+			Self::Brotli(decoder) => return decoder.decode(data),
+			Self::Gzip(decoder) => (decoder.write(data)?, decoder.get_mut()),
+			Self::Deflate(decoder) => (decoder.write(data)?, decoder.get_mut()),
+		};
+
+		Ok((consumed_size, std::mem::take(output).into()))
+	}
+
+	// Returns the rest of the decoded data. Fails if the encoded data is incomplete.
+	fn finish(self) -> Result<Bytes, IoError> {
+		let output = match self {
+			Self::Brotli(decoder) => return decoder.finish(),
+			Self::Gzip(decoder) => decoder.finish()?,
+			Self::Deflate(decoder) => decoder.finish()?,
+		};
+
+		Ok(output.into())
+	}
+}
+
+// -------------------------
+
+struct BrotliDecoder {
+	state: BrotliState<HeapAlloc<u8>, HeapAlloc<u32>, HeapAlloc<HuffmanCode>>,
+	output: Box<[u8]>,
+	total_output_size: usize,
+	has_finished: bool,
+}
+
+impl BrotliDecoder {
+	fn new() -> Self {
+		Self {
+			state: BrotliState::new(
+				HeapAlloc::new(0),
+				HeapAlloc::new(0),
+				HeapAlloc::new(HuffmanCode::default()),
+			),
+			output: vec![0; DECODER_OUTPUT_SIZE].into_boxed_slice(),
+			total_output_size: 0,
+			has_finished: false,
+		}
+	}
+
+	fn decode(&mut self, data: &[u8]) -> Result<(usize, Bytes), IoError> {
+		if self.has_finished {
+			// The data after the end of the stream is ignored.
+			return Ok((data.len(), Bytes::new()));
+		}
+
+		let mut available_input_size = data.len();
+		let mut input_offset = 0;
+		let mut available_output_size = self.output.len();
+		let mut output_offset = 0;
+
+		let result = BrotliDecompressStream(
+			&mut available_input_size,
+			&mut input_offset,
+			data,
+			&mut available_output_size,
+			&mut output_offset,
+			&mut self.output,
+			&mut self.total_output_size,
+			&mut self.state,
+		);
+
+		match result {
+			BrotliResult::ResultSuccess => self.has_finished = true,
+			BrotliResult::NeedsMoreInput | BrotliResult::NeedsMoreOutput => {}
+			BrotliResult::ResultFailure => {
+				return Err(IoError::new(
+					IoErrorKind::InvalidData,
+					"invalid brotli stream",
+				))
+			}
+		}
+
+		Ok((
+			input_offset,
+			Bytes::copy_from_slice(&self.output[..output_offset]),
+		))
+	}
+
+	fn finish(self) -> Result<Bytes, IoError> {
+		if self.has_finished {
+			Ok(Bytes::new())
+		} else {
+			Err(IoError::new(
+				IoErrorKind::UnexpectedEof,
+				"incomplete brotli stream",
+			))
+		}
+	}
+}
+
+// --------------------------------------------------
+// DecompressedBody
+
+struct DecompressedBody {
+	inner: Body,
+	some_decoder: Option<Decoder>,
+	input: Bytes,
+	some_trailers: Option<Frame<Bytes>>,
+}
+
+impl DecompressedBody {
+	fn new(inner: Body, coding: Coding) -> Self {
+		Self {
+			inner,
+			some_decoder: Some(Decoder::new(coding)),
+			input: Bytes::new(),
+			some_trailers: None,
+		}
+	}
+}
+
+impl HttpBody for DecompressedBody {
+	type Data = Bytes;
+	type Error = BoxedError;
+
+	fn poll_frame(
+		mut self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		let this = &mut *self;
+
+		loop {
+			let Some(decoder) = this.some_decoder.as_mut() else {
+				return Poll::Ready(this.some_trailers.take().map(Ok));
+			};
+
+			// The input is decoded step by step to keep the size of each data frame bounded.
+			let input = this.input.clone();
+			let (consumed_size, decoded_data) = decoder.decode(&input)?;
+			this.input = input.slice(consumed_size..);
+
+			if !decoded_data.is_empty() {
+				return Poll::Ready(Some(Ok(Frame::data(decoded_data))));
+			}
+
+			if !this.input.is_empty() {
+				continue;
+			}
+
+			match Pin::new(&mut this.inner).poll_frame(cx) {
+				Poll::Ready(Some(Ok(frame))) => match frame.into_data() {
+					Ok(data) => this.input = data,
+					Err(frame) => {
+						this.some_trailers = Some(frame);
+
+						let decoder = this.some_decoder.take().expect(SCOPE_VALIDITY);
+
+						return Poll::Ready(Some(Ok(Frame::data(decoder.finish()?))));
+					}
+				},
+				Poll::Ready(Some(Err(error))) => return Poll::Ready(Some(Err(error))),
+				Poll::Ready(None) => {
+					let decoder = this.some_decoder.take().expect(SCOPE_VALIDITY);
+
+					return Poll::Ready(Some(Ok(Frame::data(decoder.finish()?))));
+				}
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use std::io::Read;
+
+	use futures_util::{stream, StreamExt};
+	use http::Method;
+	use http_body_util::StreamBody;
+	use hyper::service::Service;
+
+	use crate::{
+		data::Text, handler::HandlerSetter, middleware::RequestReceiver, resource::Resource,
+		test_client::TestClient,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[tokio::test]
+	async fn compression() {
+		let text = "Hello, World! ".repeat(256);
+
+		let mut root = Resource::new("/");
+
+		let text_clone = text.clone();
+		root
+			.subresource_mut("/text")
+			.set_handler_for(Method::GET.to(move || {
+				let text = text_clone.clone();
+
+				async move { text }
+			}));
+
+		root
+			.subresource_mut("/small")
+			.set_handler_for(Method::GET.to(|| async { "Hello, World!" }));
+
+		let text_clone = text.clone();
+		root
+			.subresource_mut("/binary")
+			.set_handler_for(Method::GET.to(move || {
+				let text = text_clone.clone();
+
+				async move {
+					(
+						[(CONTENT_TYPE, HeaderValue::from_static("image/png"))],
+						text,
+					)
+				}
+			}));
+
+		let text_clone = text.clone();
+		root
+			.subresource_mut("/encoded")
+			.set_handler_for(Method::GET.to(move || {
+				let text = text_clone.clone();
+
+				async move { ([(CONTENT_ENCODING, HeaderValue::from_static("gzip"))], text) }
+			}));
+
+		root.wrap(RequestReceiver.component_in(CompressionLayer::new()));
+
+		let client = TestClient::new(root.into_service());
+
+		// -------------------------
+
+		let response = client
+			.get("/text")
+			.header(ACCEPT_ENCODING, "deflate;q=0.5, gzip")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.header(CONTENT_ENCODING.as_str()), Some("gzip"));
+		assert_eq!(response.header(VARY.as_str()), Some("accept-encoding"));
+		assert!(response.bytes().len() < text.len());
+
+		let mut decoded_text = String::new();
+		flate2::read::GzDecoder::new(response.bytes().as_ref())
+			.read_to_string(&mut decoded_text)
+			.unwrap();
+
+		assert_eq!(decoded_text, text);
+
+		// -------------------------
+
+		let response = client
+			.get("/text")
+			.header(ACCEPT_ENCODING, "gzip;q=0.5, br")
+			.send()
+			.await;
+
+		assert_eq!(response.header(CONTENT_ENCODING.as_str()), Some("br"));
+
+		let mut decoded_text = String::new();
+		brotli::Decompressor::new(response.bytes().as_ref(), 4096)
+			.read_to_string(&mut decoded_text)
+			.unwrap();
+
+		assert_eq!(decoded_text, text);
+
+		// -------------------------
+
+		let response = client.get("/text").send().await;
+		assert!(response.header(CONTENT_ENCODING.as_str()).is_none());
+		assert_eq!(response.header(VARY.as_str()), Some("accept-encoding"));
+		assert_eq!(response.text(), text);
+
+		let response = client
+			.get("/text")
+			.header(ACCEPT_ENCODING, "gzip;q=0, identity")
+			.send()
+			.await;
+
+		assert!(response.header(CONTENT_ENCODING.as_str()).is_none());
+		assert_eq!(response.text(), text);
+
+		let response = client
+			.get("/text")
+			.header(ACCEPT_ENCODING, "gzip;q=0, *")
+			.send()
+			.await;
+
+		assert_eq!(response.header(CONTENT_ENCODING.as_str()), Some("br"));
+
+		let response = client
+			.get("/text")
+			.header(ACCEPT_ENCODING, "gzip;q=0, br;q=0, deflate;q=0, *")
+			.send()
+			.await;
+
+		assert!(response.header(CONTENT_ENCODING.as_str()).is_none());
+		assert_eq!(response.text(), text);
+
+		// Invalid quality values are treated as if there was no Accept-Encoding.
+		for accept_encoding in ["br, gzip;q=NaN", "gzip;q=inf", "gzip;q=2", "gzip;q=-1"] {
+			let response = client
+				.get("/text")
+				.header(ACCEPT_ENCODING, accept_encoding)
+				.send()
+				.await;
+
+			assert_eq!(response.status(), StatusCode::OK);
+			assert!(
+				response.header(CONTENT_ENCODING.as_str()).is_none(),
+				"{}",
+				accept_encoding,
+			);
+		}
+
+		// -------------------------
+
+		for path in ["/small", "/binary"] {
+			let response = client
+				.get(path)
+				.header(ACCEPT_ENCODING, "gzip")
+				.send()
+				.await;
+			assert_eq!(response.status(), StatusCode::OK);
+			assert!(
+				response.header(CONTENT_ENCODING.as_str()).is_none(),
+				"{}",
+				path
+			);
+		}
+
+		let response = client
+			.get("/encoded")
+			.header(ACCEPT_ENCODING, "br")
+			.send()
+			.await;
+
+		assert_eq!(response.header(CONTENT_ENCODING.as_str()), Some("gzip"));
+		assert_eq!(response.text(), text);
+	}
+
+	#[tokio::test]
+	async fn compression_event_stream() {
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/events")
+			.set_handler_for(Method::GET.to(|| async {
+				// The stream never ends. So the event must be flushed to be received.
+				let events = stream::iter([Ok::<_, BoxedError>(Frame::data(Bytes::from_static(
+					b"data: event\n\n",
+				)))])
+				.chain(stream::pending());
+
+				let mut response = Body::new(StreamBody::new(events)).into_response();
+				response
+					.headers_mut()
+					.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+
+				response
+			}));
+
+		root.wrap(RequestReceiver.component_in(CompressionLayer::new()));
+
+		let service = root.into_service();
+
+		let request = http::Request::get("/events")
+			.header(ACCEPT_ENCODING, "gzip")
+			.body(Body::default())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+
+		let mut body = response.into_body();
+		let data = body.frame().await.unwrap().unwrap().into_data().unwrap();
+
+		let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+		decoder.write_all(&data).unwrap();
+		decoder.flush().unwrap();
+
+		assert_eq!(decoder.get_ref().as_slice(), b"data: event\n\n");
+	}
+
+	#[tokio::test]
+	async fn decompression() {
+		let text = "Hello, World! ".repeat(256);
+
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/text")
+			.set_handler_for(Method::POST.to(|Text(text): Text| async move { text }));
+
+		root
+			.subresource_mut("/limited")
+			.set_handler_for(Method::POST.to(|Text(text): Text<1024>| async move { text }));
+
+		root.wrap(RequestReceiver.component_in(DecompressionLayer));
+
+		let client = TestClient::new(root.into_service());
+
+		// -------------------------
+
+		let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
+		gzip_encoder.write_all(text.as_bytes()).unwrap();
+		let gzip_data = gzip_encoder.finish().unwrap();
+
+		let mut deflate_encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+		deflate_encoder.write_all(text.as_bytes()).unwrap();
+		let deflate_data = deflate_encoder.finish().unwrap();
+
+		let mut brotli_encoder = CompressorWriter::new(Vec::new(), 4096, 6, 22);
+		brotli_encoder.write_all(text.as_bytes()).unwrap();
+		let brotli_data = brotli_encoder.into_inner();
+
+		let mut brotli_encoder = CompressorWriter::new(Vec::new(), 4096, 6, 22);
+		brotli_encoder.write_all(&gzip_data).unwrap();
+		let gzip_brotli_data = brotli_encoder.into_inner();
+
+		for (content_encoding, data) in [
+			("gzip", gzip_data.clone()),
+			("deflate", deflate_data),
+			("br", brotli_data),
+			("gzip, br", gzip_brotli_data.clone()),
+			("identity", text.clone().into_bytes()),
+		] {
+			let response = client
+				.post("/text")
+				.header(CONTENT_TYPE, "text/plain")
+				.header(CONTENT_ENCODING, content_encoding)
+				.body(data)
+				.send()
+				.await;
+
+			assert_eq!(response.status(), StatusCode::OK, "{}", content_encoding);
+			assert_eq!(response.text(), text, "{}", content_encoding);
+		}
+
+		// -------------------------
+		// The codings listed in separate header lines are applied in the order of the lines.
+
+		let response = client
+			.post("/text")
+			.header(CONTENT_TYPE, "text/plain")
+			.header(CONTENT_ENCODING, "gzip")
+			.header(CONTENT_ENCODING, "br")
+			.body(gzip_brotli_data)
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), text);
+
+		// -------------------------
+		// The size limit applies to the decoded data.
+
+		let response = client
+			.post("/limited")
+			.header(CONTENT_TYPE, "text/plain")
+			.header(CONTENT_ENCODING, "gzip")
+			.body(gzip_data)
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+		// -------------------------
+
+		let response = client
+			.post("/text")
+			.header(CONTENT_TYPE, "text/plain")
+			.header(CONTENT_ENCODING, "zstd")
+			.body("data")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+		assert_eq!(
+			response.header(ACCEPT_ENCODING.as_str()),
+			Some(SUPPORTED_CODINGS)
+		);
+	}
+}
@@ -0,0 +1,837 @@
+use std::{
+	error::Error as StdError,
+	fmt::{Debug, Display, Formatter, Write},
+	sync::Arc,
+	time::Duration,
+};
+
+use http::{
+	header::{
+		ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+		ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+		ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ALLOW, ORIGIN, VARY,
+	},
+	HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+};
+
+use crate::{handler::Args, request::routing::NotAllowedMethodError, request::RequestContext};
+
+use super::*;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// CorsLayer
+
+/// A layer that applies a CORS (Cross-Origin Resource Sharing) middleware to a [`Handler`].
+///
+/// The middleware answers the preflight requests with the methods supported by the target
+/// resource, and adds the `Access-Control-*` headers to the responses of the requests with
+/// an allowed `Origin`. The errors are passed on wrapped in a [`CorsError`], whose response
+/// gets the headers too, so the error handlers applied above the `CorsLayer` still see them.
+/// The requests without an `Origin` and with an origin that isn't allowed are passed to
+/// the handler as is. Unless any origin is allowed without credentials, the responses to
+/// such requests get the `Vary: origin` header too, so the caches don't serve them to the
+/// allowed origins. Their errors are passed on unwrapped.
+///
+/// Usually, the layer is applied to the router's or root resource's *request passer* or
+/// *request receiver*, so the preflight requests can reach their target resources.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use argan::{
+///   Router,
+///   handler::HandlerSetter,
+///   http::{header::CONTENT_TYPE, Method},
+///   middleware::{CorsLayer, RequestPasser},
+/// };
+///
+/// let mut router = Router::new();
+/// router
+///   .resource_mut("/items")
+///   .set_handler_for([Method::GET.to(|| async {}), Method::POST.to(|| async {})]);
+///
+/// router.wrap(RequestPasser.component_in(
+///   CorsLayer::new()
+///     .allow_origin("https://example.com")
+///     .allow_origin("https://*.example.com")
+///     .allow_origin_if(|origin: &str| origin.ends_with(".example.org"))
+///     .allow_headers([CONTENT_TYPE])
+///     .max_age(Duration::from_secs(600)),
+/// ));
+/// ```
+#[derive(Clone)]
+pub struct CorsLayer(Arc<CorsConfig>);
+
+impl CorsLayer {
+	/// Creates a new `CorsLayer` that doesn't allow any origin.
+	pub fn new() -> Self {
+		Self(Arc::new(CorsConfig {
+			allowed_origins: Vec::new(),
+			some_allowed_headers: None,
+			some_exposed_headers: None,
+			some_max_age: None,
+			credentials_allowed: false,
+		}))
+	}
+
+	/// Allows the requests from the given origin.
+	///
+	/// The origin can be an exact origin like `"https://example.com"`, a wildcard origin
+	/// that has an asterisk in place of a single subdomain label like `"https://*.example.com"`,
+	/// or an asterisk `"*"` that allows any origin.
+	///
+	/// # Panics
+	///
+	/// - if the origin is empty or has more than one asterisk
+	pub fn allow_origin<O: AsRef<str>>(mut self, origin: O) -> Self {
+		let origin = origin.as_ref();
+
+		let allowed_origin = match origin.split_once('*') {
+			_ if origin.is_empty() => panic!("empty origin"),
+			None => AllowedOrigin::Exact(origin.into()),
+			Some(("", "")) => AllowedOrigin::Any,
+			Some((_, suffix)) if suffix.contains('*') => {
+				panic!("origin '{}' has more than one asterisk", origin)
+			}
+			Some((prefix, suffix)) => AllowedOrigin::Wildcard(prefix.into(), suffix.into()),
+		};
+
+		self.config_mut().allowed_origins.push(allowed_origin);
+
+		self
+	}
+
+	/// Allows the requests from the origins for which the `predicate` returns `true`.
+	pub fn allow_origin_if<P>(mut self, predicate: P) -> Self
+	where
+		P: Fn(&str) -> bool + Send + Sync + 'static,
+	{
+		self
+			.config_mut()
+			.allowed_origins
+			.push(AllowedOrigin::Predicate(Arc::new(predicate)));
+
+		self
+	}
+
+	/// Sets the headers that are allowed in the requests. By default, the headers listed in
+	/// the preflight request's `Access-Control-Request-Headers` are allowed.
+	pub fn allow_headers<I>(mut self, headers: I) -> Self
+	where
+		I: IntoIterator<Item = HeaderName>,
+	{
+		self.config_mut().some_allowed_headers = Some(header_list_value(headers));
+
+		self
+	}
+
+	/// Sets the response headers that are exposed to the client.
+	pub fn expose_headers<I>(mut self, headers: I) -> Self
+	where
+		I: IntoIterator<Item = HeaderName>,
+	{
+		self.config_mut().some_exposed_headers = Some(header_list_value(headers));
+
+		self
+	}
+
+	/// Sets how long the results of the preflight request can be cached.
+	pub fn max_age(mut self, max_age: Duration) -> Self {
+		self.config_mut().some_max_age = Some(HeaderValue::from(max_age.as_secs()));
+
+		self
+	}
+
+	/// Allows the requests with credentials.
+	///
+	/// When credentials are allowed, the request's origin is sent back instead of `"*"`
+	/// even if any origin is allowed.
+	pub fn allow_credentials(mut self) -> Self {
+		self.config_mut().credentials_allowed = true;
+
+		self
+	}
+
+	#[inline(always)]
+	fn config_mut(&mut self) -> &mut CorsConfig {
+		Arc::make_mut(&mut self.0)
+	}
+}
+
+impl Default for CorsLayer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<H> Layer<H> for CorsLayer {
+	type Handler = Cors<H>;
+
+	fn wrap(&self, handler: H) -> Self::Handler {
+		Cors::new(handler, self.0.clone())
+	}
+}
+
+// --------------------------------------------------
+// CorsConfig
+
+#[derive(Clone)]
+struct CorsConfig {
+	allowed_origins: Vec<AllowedOrigin>,
+	some_allowed_headers: Option<HeaderValue>,
+	some_exposed_headers: Option<HeaderValue>,
+	some_max_age: Option<HeaderValue>,
+	credentials_allowed: bool,
+}
+
+impl CorsConfig {
+	// Returns the value of the 'Access-Control-Allow-Origin' header if the origin is allowed.
+	fn allowed_origin_value(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+		let origin_str = origin.to_str().ok()?;
+
+		let mut any_origin_is_allowed = false;
+
+		if !self.allowed_origins.iter().any(|allowed_origin| {
+			any_origin_is_allowed = matches!(allowed_origin, AllowedOrigin::Any);
+
+			allowed_origin.matches(origin_str)
+		}) {
+			return None;
+		}
+
+		if any_origin_is_allowed && !self.credentials_allowed {
+			return Some(HeaderValue::from_static("*"));
+		}
+
+		Some(origin.clone())
+	}
+
+	// Returns true if the CORS headers of the responses depend on the request's origin.
+	fn varies_by_origin(&self) -> bool {
+		let any_origin_gets_asterisk =
+			!self.credentials_allowed && matches!(self.allowed_origins.first(), Some(AllowedOrigin::Any));
+
+		!(self.allowed_origins.is_empty() || any_origin_gets_asterisk)
+	}
+
+	fn add_headers(&self, headers: &mut HeaderMap, allowed_origin: HeaderValue) {
+		if self.varies_by_origin() {
+			headers.append(VARY, HeaderValue::from_static("origin"));
+		}
+
+		headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin);
+
+		if self.credentials_allowed {
+			headers.insert(
+				ACCESS_CONTROL_ALLOW_CREDENTIALS,
+				HeaderValue::from_static("true"),
+			);
+		}
+
+		if let Some(exposed_headers) = self.some_exposed_headers.as_ref() {
+			headers.insert(ACCESS_CONTROL_EXPOSE_HEADERS, exposed_headers.clone());
+		}
+	}
+
+	fn add_preflight_headers(
+		&self,
+		headers: &mut HeaderMap,
+		some_allowed_methods: Option<HeaderValue>,
+		some_requested_headers: Option<HeaderValue>,
+	) {
+		headers.append(
+			VARY,
+			HeaderValue::from_static("access-control-request-method, access-control-request-headers"),
+		);
+
+		if let Some(allowed_methods) = some_allowed_methods {
+			headers.insert(ACCESS_CONTROL_ALLOW_METHODS, allowed_methods);
+		}
+
+		if let Some(allowed_headers) = self.some_allowed_headers.clone().or(some_requested_headers) {
+			headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, allowed_headers);
+		}
+
+		if let Some(max_age) = self.some_max_age.as_ref() {
+			headers.insert(ACCESS_CONTROL_MAX_AGE, max_age.clone());
+		}
+	}
+}
+
+// --------------------------------------------------
+// AllowedOrigin
+
+#[derive(Clone)]
+enum AllowedOrigin {
+	Any,
+	Exact(Box<str>),
+	Wildcard(Box<str>, Box<str>),
+	Predicate(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+}
+
+impl AllowedOrigin {
+	fn matches(&self, origin: &str) -> bool {
+		match self {
+			Self::Any => true,
+			Self::Exact(allowed_origin) => allowed_origin.as_ref() == origin,
+			Self::Wildcard(prefix, suffix) => origin
+				.strip_prefix(prefix.as_ref())
+				.and_then(|origin| origin.strip_suffix(suffix.as_ref()))
+				.is_some_and(|label| !label.is_empty() && !label.contains(['.', '/', ':'])),
+			Self::Predicate(predicate) => predicate(origin),
+		}
+	}
+}
+
+// --------------------------------------------------
+
+fn header_list_value<I>(headers: I) -> HeaderValue
+where
+	I: IntoIterator<Item = HeaderName>,
+{
+	let mut value = String::new();
+	for header in headers {
+		if !value.is_empty() {
+			value.push_str(", ");
+		}
+
+		let _ = write!(value, "{}", header);
+	}
+
+	HeaderValue::try_from(value).expect("header names should make a valid header value")
+}
+
+// --------------------------------------------------------------------------------
+
+mod private {
+	use super::*;
+
+	// --------------------------------------------------
+	// Cors
+
+	#[derive(Clone)]
+	pub struct Cors<H> {
+		inner_handler: H,
+		config: Arc<CorsConfig>,
+	}
+
+	impl<H> Cors<H> {
+		pub(super) fn new(handler: H, config: Arc<CorsConfig>) -> Self {
+			Self {
+				inner_handler: handler,
+				config,
+			}
+		}
+	}
+
+	impl<H, B, Ext> Handler<B, Ext> for Cors<H>
+	where
+		H: Handler<
+			B,
+			Ext,
+			Response = Response,
+			Error = BoxedErrorResponse,
+			Future = BoxedFuture<Result<Response, BoxedErrorResponse>>,
+		>,
+		Ext: Clone,
+	{
+		type Response = Response;
+		type Error = BoxedErrorResponse;
+		type Future = BoxedFuture<Result<Self::Response, Self::Error>>;
+
+		fn handle(&self, request_context: RequestContext<B>, args: Args<'_, Ext>) -> Self::Future {
+			let headers = request_context.headers_ref();
+
+			let Some(allowed_origin) = headers
+				.get(ORIGIN)
+				.and_then(|origin| self.config.allowed_origin_value(origin))
+			else {
+				let response_future = self.inner_handler.handle(request_context, args);
+
+				if !self.config.varies_by_origin() {
+					return response_future;
+				}
+
+				return Box::pin(async move {
+					let mut response = response_future.await?;
+					response
+						.headers_mut()
+						.append(VARY, HeaderValue::from_static("origin"));
+
+					Ok(response)
+				});
+			};
+
+			let is_preflight = request_context.method_ref() == Method::OPTIONS
+				&& headers.contains_key(ACCESS_CONTROL_REQUEST_METHOD);
+
+			let cors_headers = CorsHeaders {
+				config: self.config.clone(),
+				allowed_origin,
+				is_preflight,
+				some_requested_headers: headers.get(ACCESS_CONTROL_REQUEST_HEADERS).cloned(),
+			};
+
+			let response_future = self.inner_handler.handle(request_context, args);
+
+			Box::pin(async move {
+				let mut response = match response_future.await {
+					// An error handler below the CORS layer may have already converted the
+					// NotAllowedMethodError into a response.
+					Ok(response)
+						if is_preflight
+							&& response.status() == StatusCode::METHOD_NOT_ALLOWED
+							&& response.headers().contains_key(ALLOW) =>
+					{
+						let allowed_methods = response.headers().get(ALLOW).cloned();
+
+						preflight_response(allowed_methods)
+					}
+					Ok(response) => response,
+					Err(error) if is_preflight => match error.downcast_to::<NotAllowedMethodError>() {
+						// The target resource doesn't handle the OPTIONS method. So we answer
+						// the preflight request with the methods the resource supports.
+						Ok(not_allowed_method_error) => preflight_response(
+							HeaderValue::from_str(&not_allowed_method_error.supported_methods).ok(),
+						),
+						Err(error) => return Err(CorsError::new(error, cors_headers).into()),
+					},
+					Err(error) => return Err(CorsError::new(error, cors_headers).into()),
+				};
+
+				cors_headers.add_to(&mut response);
+
+				Ok(response)
+			})
+		}
+	}
+}
+
+pub(crate) use private::Cors;
+
+// ----------
+
+fn preflight_response(some_allowed_methods: Option<HeaderValue>) -> Response {
+	let mut response = StatusCode::NO_CONTENT.into_response();
+
+	if let Some(allowed_methods) = some_allowed_methods {
+		response.headers_mut().insert(ALLOW, allowed_methods);
+	}
+
+	response
+}
+
+// The CORS headers of the response to the request with an allowed origin.
+struct CorsHeaders {
+	config: Arc<CorsConfig>,
+	allowed_origin: HeaderValue,
+	is_preflight: bool,
+	some_requested_headers: Option<HeaderValue>,
+}
+
+impl CorsHeaders {
+	fn add_to(self, response: &mut Response) {
+		let headers = response.headers_mut();
+
+		if self.is_preflight {
+			let some_allowed_methods = headers.get(ALLOW).cloned();
+			self
+				.config
+				.add_preflight_headers(headers, some_allowed_methods, self.some_requested_headers);
+		}
+
+		self.config.add_headers(headers, self.allowed_origin);
+	}
+}
+
+// --------------------------------------------------
+// CorsError
+
+/// An error type that wraps the error returned by the handler of the request with
+/// an allowed origin.
+///
+/// The error passes through the error handlers applied above the [`CorsLayer`] like any
+/// other error, and its response has the CORS headers. The wrapped error can be retrieved
+/// with [`Self::into_inner()`].
+pub struct CorsError {
+	error: BoxedErrorResponse,
+	cors_headers: CorsHeaders,
+}
+
+impl CorsError {
+	fn new(error: BoxedErrorResponse, cors_headers: CorsHeaders) -> Self {
+		Self {
+			error,
+			cors_headers,
+		}
+	}
+
+	/// Returns a reference to the wrapped error.
+	#[inline(always)]
+	pub fn inner_ref(&self) -> &BoxedErrorResponse {
+		&self.error
+	}
+
+	/// Returns the wrapped error without the CORS headers.
+	#[inline(always)]
+	pub fn into_inner(self) -> BoxedErrorResponse {
+		self.error
+	}
+}
+
+impl Debug for CorsError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("CorsError").field(&self.error).finish()
+	}
+}
+
+impl Display for CorsError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&self.error, f)
+	}
+}
+
+impl StdError for CorsError {
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		Some(self.error.as_ref())
+	}
+}
+
+impl IntoResponse for CorsError {
+	fn into_response(self) -> Response {
+		let mut response = self.error.into_response();
+		self.cors_headers.add_to(&mut response);
+
+		response
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use http::header::CONTENT_TYPE;
+
+	use crate::{
+		handler::HandlerSetter,
+		middleware::{ErrorHandlerLayer, RequestPasser, RequestReceiver},
+		test_client::TestClient,
+		Router,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Debug, crate::ImplError)]
+	#[error("teapot")]
+	struct Teapot;
+
+	impl IntoResponse for Teapot {
+		fn into_response(self) -> Response {
+			StatusCode::IM_A_TEAPOT.into_response()
+		}
+	}
+
+	#[tokio::test]
+	async fn cors() {
+		let mut router = Router::new();
+		router.resource_mut("/items").set_handler_for([
+			Method::GET.to(|| async { "items" }),
+			Method::POST.to(|| async {}),
+		]);
+
+		let failing = router.resource_mut("/failing");
+		failing.set_handler_for(Method::GET.to(|| async { Err::<(), _>(Teapot) }));
+		failing.wrap(RequestReceiver.component_in(ErrorHandlerLayer::new(
+			|error: BoxedErrorResponse| async move { Err::<Response, _>(error) },
+		)));
+
+		router.wrap(
+			RequestPasser.component_in(
+				CorsLayer::new()
+					.allow_origin("https://example.com")
+					.allow_origin("https://*.example.com")
+					.allow_origin_if(|origin: &str| origin.ends_with(".example.org"))
+					.expose_headers([CONTENT_TYPE])
+					.max_age(Duration::from_secs(600)),
+			),
+		);
+
+		let client = TestClient::new(router.into_service());
+
+		// -------------------------
+		// Preflight
+
+		let response = client
+			.options("/items")
+			.header(ORIGIN, "https://api.example.com")
+			.header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+			.header(ACCESS_CONTROL_REQUEST_HEADERS, "x-token")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::NO_CONTENT);
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://api.example.com"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_METHODS.as_str()),
+			Some("GET, POST"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_HEADERS.as_str()),
+			Some("x-token"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_MAX_AGE.as_str()),
+			Some("600")
+		);
+
+		// -------------------------
+		// Actual request
+
+		let response = client
+			.get("/items")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "items");
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_EXPOSE_HEADERS.as_str()),
+			Some("content-type"),
+		);
+
+		assert_eq!(response.header(VARY.as_str()), Some("origin"));
+
+		let response = client
+			.get("/items")
+			.header(ORIGIN, "https://shop.example.org")
+			.send()
+			.await;
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://shop.example.org"),
+		);
+
+		// -------------------------
+		// Errors
+
+		let response = client
+			.get("/failing")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+
+		let response = client
+			.delete("/items")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+
+		// -------------------------
+		// Disallowed origins
+
+		for origin in [
+			"https://example.net",
+			"https://a.b.example.com",
+			"http://example.com",
+		] {
+			let response = client.get("/items").header(ORIGIN, origin).send().await;
+			assert_eq!(response.status(), StatusCode::OK);
+			assert!(response
+				.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str())
+				.is_none());
+
+			assert_eq!(response.header(VARY.as_str()), Some("origin"));
+		}
+
+		let response = client.get("/items").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert!(response
+			.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str())
+			.is_none());
+
+		assert_eq!(response.header(VARY.as_str()), Some("origin"));
+
+		let response = client
+			.options("/items")
+			.header(ORIGIN, "https://example.net")
+			.header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+	}
+
+	#[tokio::test]
+	async fn cors_any_origin() {
+		let mut router = Router::new();
+		router
+			.resource_mut("/items")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		router.wrap(RequestPasser.component_in(CorsLayer::new().allow_origin("*")));
+
+		let client = TestClient::new(router.into_service());
+
+		let response = client
+			.get("/items")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("*")
+		);
+		assert!(response.header(VARY.as_str()).is_none());
+
+		let response = client.get("/items").send().await;
+		assert!(response.header(VARY.as_str()).is_none());
+
+		let mut router = Router::new();
+		router
+			.resource_mut("/items")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		router.wrap(RequestPasser.component_in(CorsLayer::new().allow_origin("*").allow_credentials()));
+
+		let client = TestClient::new(router.into_service());
+
+		let response = client
+			.get("/items")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_CREDENTIALS.as_str()),
+			Some("true"),
+		);
+
+		assert_eq!(response.header(VARY.as_str()), Some("origin"));
+
+		let response = client.get("/items").send().await;
+		assert_eq!(response.header(VARY.as_str()), Some("origin"));
+	}
+
+	#[tokio::test]
+	async fn cors_errors() {
+		use std::sync::atomic::{AtomicBool, Ordering};
+
+		let mut router = Router::new();
+		router
+			.resource_mut("/items")
+			.set_handler_for(Method::GET.to(|| async { Err::<(), _>(Teapot) }));
+
+		let error_is_wrapped = Arc::new(AtomicBool::new(false));
+		let error_is_wrapped_clone = error_is_wrapped.clone();
+
+		// The error handler is applied above the CorsLayer.
+		router.wrap(RequestPasser.component_in((
+			ErrorHandlerLayer::new(move |error: BoxedErrorResponse| {
+				let error_is_wrapped = error_is_wrapped_clone.clone();
+
+				async move {
+					if let Some(cors_error) = error.downcast_to_ref::<CorsError>() {
+						if cors_error.inner_ref().is::<Teapot>() {
+							error_is_wrapped.store(true, Ordering::Relaxed);
+						}
+					}
+
+					Err::<Response, _>(error)
+				}
+			}),
+			CorsLayer::new().allow_origin("https://example.com"),
+		)));
+
+		let client = TestClient::new(router.into_service());
+
+		let response = client
+			.get("/items")
+			.header(ORIGIN, "https://example.com")
+			.send()
+			.await;
+
+		assert!(error_is_wrapped.load(Ordering::Relaxed));
+		assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+
+		// -------------------------
+		// Preflight with an error handler below the CorsLayer that converts the errors
+		// into responses.
+
+		let mut router = Router::new();
+		let items = router.resource_mut("/items");
+		items.set_handler_for([Method::GET.to(|| async {}), Method::POST.to(|| async {})]);
+		items.wrap(RequestReceiver.component_in(ErrorHandlerLayer::new(
+			|error: BoxedErrorResponse| async move { Ok::<_, BoxedErrorResponse>(error.into_response()) },
+		)));
+
+		router.wrap(RequestPasser.component_in(CorsLayer::new().allow_origin("https://example.com")));
+
+		let client = TestClient::new(router.into_service());
+
+		let response = client
+			.options("/items")
+			.header(ORIGIN, "https://example.com")
+			.header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::NO_CONTENT);
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_METHODS.as_str()),
+			Some("GET, POST"),
+		);
+
+		assert_eq!(
+			response.header(ACCESS_CONTROL_ALLOW_ORIGIN.as_str()),
+			Some("https://example.com"),
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "has more than one asterisk")]
+	fn cors_origin_with_asterisks() {
+		let _ = CorsLayer::new().allow_origin("https://*.*.example.com");
+	}
+}
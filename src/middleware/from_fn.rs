@@ -0,0 +1,130 @@
+use std::future::Future;
+
+use crate::{
+	handler::Args,
+	request::RequestContext,
+	response::{IntoResponseResult, ResponseResult},
+};
+
+use super::*;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// from_fn
+
+/// Creates a layer from an async function that receives the request context, the handler
+/// arguments, and the [`Next`] handler.
+///
+/// The function can run some code before and after calling the next handler, or respond
+/// without calling it at all. The layer can be applied with any of the layer targets.
+///
+/// ```
+/// use argan::{
+///   prelude::*,
+///   middleware::{from_fn, Next},
+/// };
+///
+/// async fn authorize(
+///   request_context: RequestContext,
+///   args: Args<'static>,
+///   next: Next,
+/// ) -> Result<Response, BoxedErrorResponse> {
+///   if request_context.headers_ref().get("authorization").is_none() {
+///     return Ok(StatusCode::UNAUTHORIZED.into_response());
+///   }
+///
+///   let mut response = next.run(request_context, args).await?;
+///   response
+///     .headers_mut()
+///     .insert("x-authorized", HeaderValue::from_static("true"));
+///
+///   Ok(response)
+/// }
+///
+/// let mut resource = Resource::new("/resource");
+/// resource.set_handler_for(Method::GET.to(|| async { "resource" }));
+/// resource.wrap(RequestReceiver.component_in(from_fn(authorize)));
+/// ```
+pub fn from_fn<Func, Fut>(func: Func) -> FromFnLayer<Func>
+where
+	Func: Fn(RequestContext, Args<'static>, Next) -> Fut + Clone + Send + Sync + 'static,
+	Fut: Future + Send + 'static,
+	Fut::Output: IntoResponseResult,
+{
+	FromFnLayer(func)
+}
+
+// --------------------------------------------------
+// FromFnLayer
+
+/// A layer that applies a middleware created by [`from_fn()`].
+#[derive(Clone)]
+pub struct FromFnLayer<Func>(Func);
+
+impl<Func> Layer<AdaptiveHandler> for FromFnLayer<Func>
+where
+	Func: Clone,
+{
+	type Handler = FromFnHandler<Func>;
+
+	fn wrap(&self, handler: AdaptiveHandler) -> Self::Handler {
+		FromFnHandler::new(self.0.clone(), handler)
+	}
+}
+
+// --------------------------------------------------
+// Next
+
+/// The handler wrapped by the middleware created with [`from_fn()`].
+#[derive(Clone)]
+pub struct Next(AdaptiveHandler);
+
+impl Next {
+	/// Passes the request to the wrapped handler.
+	pub fn run(self, request_context: RequestContext, args: Args<'_>) -> BoxedFuture<ResponseResult> {
+		self.0.handle(request_context, args)
+	}
+}
+
+// --------------------------------------------------
+
+mod private {
+	use super::*;
+
+	// --------------------------------------------------
+	// FromFnHandler
+
+	#[derive(Clone)]
+	pub struct FromFnHandler<Func> {
+		func: Func,
+		next: AdaptiveHandler,
+	}
+
+	impl<Func> FromFnHandler<Func> {
+		pub(super) fn new(func: Func, next: AdaptiveHandler) -> Self {
+			Self { func, next }
+		}
+	}
+
+	impl<Func, Fut> Handler for FromFnHandler<Func>
+	where
+		Func: Fn(RequestContext, Args<'static>, Next) -> Fut,
+		Fut: Future + Send + 'static,
+		Fut::Output: IntoResponseResult,
+	{
+		type Response = Response;
+		type Error = BoxedErrorResponse;
+		type Future = BoxedFuture<Result<Self::Response, Self::Error>>;
+
+		#[inline]
+		fn handle(&self, request_context: RequestContext, args: Args<'_>) -> Self::Future {
+			let future = (self.func)(request_context, args.into_owned(), Next(self.next.clone()));
+
+			Box::pin(async move { future.await.into_response_result() })
+		}
+	}
+}
+
+pub(crate) use private::FromFnHandler;
@@ -15,6 +15,23 @@ use crate::{
 mod impls;
 pub use impls::*;
 
+mod cors;
+pub use cors::{CorsError, CorsLayer};
+
+#[cfg(feature = "compression")]
+mod compression;
+#[cfg(feature = "compression")]
+pub use compression::{CompressionLayer, DecompressionLayer, UnsupportedContentEncodingError};
+
+mod from_fn;
+pub use from_fn::{from_fn, FromFnLayer, Next};
+
+mod rate_limit;
+pub use rate_limit::{
+	MemoryRateLimitStore, RateLimitAlgorithm, RateLimitError, RateLimitKey, RateLimitLayer,
+	RateLimitPolicy, RateLimitStatus, RateLimitStore, TooManyRequestsError,
+};
+
 pub(crate) mod layer_stack;
 
 pub(crate) mod targets;
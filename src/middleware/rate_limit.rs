@@ -0,0 +1,1003 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	error::Error as StdError,
+	fmt::{Debug, Display, Formatter},
+	future::ready,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use argan_core::body::Body;
+use http::{
+	header::{HeaderName, RETRY_AFTER},
+	HeaderMap, HeaderValue, StatusCode,
+};
+
+use crate::{handler::Args, request::RequestContext};
+
+use super::*;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+const DEFAULT_MAX_ENTRIES: usize = 65536;
+
+// --------------------------------------------------
+// RateLimitLayer
+
+/// A layer that applies a rate limiting middleware to a [`Handler`].
+///
+/// The requests are counted per key. By default, the key is the peer's IP address when the
+/// "peer-addr" feature is enabled. Otherwise, all the requests share the same limit. Requests
+/// without a key, e.g., without the header that's used as a key, aren't limited.
+///
+/// When the limit is exceeded, the middleware returns [`TooManyRequestsError`]. Otherwise,
+/// the `RateLimit-Limit`, `RateLimit-Remaining`, and `RateLimit-Reset` headers are added
+/// to the response. The errors of the handler are passed on wrapped in a [`RateLimitError`],
+/// whose response gets the headers too.
+///
+/// The counts are kept in a [`MemoryRateLimitStore`] unless another [`RateLimitStore`] is
+/// set. Clones of the layer share the same store.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use argan::{
+///   Resource,
+///   handler::HandlerSetter,
+///   http::{header::HeaderName, Method},
+///   middleware::{RateLimitKey, RateLimitLayer, RequestReceiver},
+/// };
+///
+/// let mut root = Resource::new("/");
+/// root
+///   .subresource_mut("/items")
+///   .set_handler_for(Method::GET.to(|| async { "items" }));
+///
+/// // 100 requests per minute per API key.
+/// let rate_limit_layer = RateLimitLayer::sliding_window(100, Duration::from_secs(60))
+///   .key_by(RateLimitKey::header(HeaderName::from_static("x-api-key")));
+///
+/// root.wrap(RequestReceiver.component_in(rate_limit_layer));
+/// ```
+#[derive(Clone)]
+pub struct RateLimitLayer {
+	policy: RateLimitPolicy,
+	key: RateLimitKey,
+	store: Arc<dyn RateLimitStore>,
+}
+
+impl RateLimitLayer {
+	/// Creates a new `RateLimitLayer` with a token bucket of `limit` tokens. Each request takes
+	/// a token, and the bucket is refilled at the rate of `limit` tokens per `period`.
+	///
+	/// # Panics
+	///
+	/// - if the `limit` or the `period` is zero
+	pub fn token_bucket(limit: u32, period: Duration) -> Self {
+		Self::new(RateLimitPolicy::new(
+			RateLimitAlgorithm::TokenBucket,
+			limit,
+			period,
+		))
+	}
+
+	/// Creates a new `RateLimitLayer` that allows `limit` requests in any sliding `window`.
+	///
+	/// The count of the requests in the sliding window is estimated from the counts of the
+	/// current and the previous fixed windows.
+	///
+	/// # Panics
+	///
+	/// - if the `limit` or the `window` is zero
+	pub fn sliding_window(limit: u32, window: Duration) -> Self {
+		Self::new(RateLimitPolicy::new(
+			RateLimitAlgorithm::SlidingWindow,
+			limit,
+			window,
+		))
+	}
+
+	fn new(policy: RateLimitPolicy) -> Self {
+		Self {
+			policy,
+			key: RateLimitKey::default(),
+			store: Arc::new(MemoryRateLimitStore::new()),
+		}
+	}
+
+	/// Sets the key the requests are counted by.
+	pub fn key_by(mut self, key: RateLimitKey) -> Self {
+		self.key = key;
+
+		self
+	}
+
+	/// Sets the store to keep the counts in.
+	pub fn with_store<S: RateLimitStore>(mut self, store: S) -> Self {
+		self.store = Arc::new(store);
+
+		self
+	}
+}
+
+impl<H> Layer<H> for RateLimitLayer {
+	type Handler = RateLimiter<H>;
+
+	fn wrap(&self, handler: H) -> Self::Handler {
+		RateLimiter::new(handler, self.clone())
+	}
+}
+
+// --------------------------------------------------
+// RateLimitKey
+
+/// A key the [`RateLimitLayer`] counts the requests by.
+#[derive(Clone)]
+pub struct RateLimitKey(InnerRateLimitKey);
+
+#[derive(Clone)]
+enum InnerRateLimitKey {
+	Global,
+	#[cfg(feature = "peer-addr")]
+	PeerIp,
+	Header(HeaderName),
+	PathParam(Box<str>),
+	Func(KeyFn),
+}
+
+type KeyFn = Arc<dyn Fn(&RequestContext) -> Option<String> + Send + Sync>;
+
+impl RateLimitKey {
+	/// The peer's IP address.
+	#[cfg(feature = "peer-addr")]
+	pub fn peer_ip() -> Self {
+		Self(InnerRateLimitKey::PeerIp)
+	}
+
+	/// The value of the header with the given `name`.
+	///
+	/// The header's value is controlled by the client, so the number of keys is bounded only
+	/// by the store. See [`MemoryRateLimitStore::with_max_entries()`].
+	pub fn header(name: HeaderName) -> Self {
+		Self(InnerRateLimitKey::Header(name))
+	}
+
+	/// The value of the path param with the given `name`.
+	///
+	/// The param must be captured by the resource that's wrapped with the layer or by one
+	/// of its parents. Like a header value, the param's value is controlled by the client.
+	pub fn path_param(name: &str) -> Self {
+		Self(InnerRateLimitKey::PathParam(name.into()))
+	}
+
+	/// The key returned by the given function. The requests that the function returns `None`
+	/// for aren't limited.
+	pub fn from_fn<Func>(func: Func) -> Self
+	where
+		Func: Fn(&RequestContext) -> Option<String> + Send + Sync + 'static,
+	{
+		Self(InnerRateLimitKey::Func(Arc::new(func)))
+	}
+
+	fn key_of(&self, request_context: &RequestContext) -> Option<String> {
+		match &self.0 {
+			InnerRateLimitKey::Global => Some(String::new()),
+			#[cfg(feature = "peer-addr")]
+			InnerRateLimitKey::PeerIp => Some(request_context.peer_addr().ip().to_string()),
+			InnerRateLimitKey::Header(name) => request_context
+				.headers_ref()
+				.get(name)
+				.map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned()),
+			InnerRateLimitKey::PathParam(name) => request_context
+				.path_param_value(name)
+				.map(ToOwned::to_owned),
+			InnerRateLimitKey::Func(func) => func(request_context),
+		}
+	}
+}
+
+impl Default for RateLimitKey {
+	fn default() -> Self {
+		#[cfg(feature = "peer-addr")]
+		return Self(InnerRateLimitKey::PeerIp);
+
+		#[cfg(not(feature = "peer-addr"))]
+		return Self(InnerRateLimitKey::Global);
+	}
+}
+
+// --------------------------------------------------
+// RateLimitPolicy
+
+/// The algorithm and the limit of the [`RateLimitLayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitPolicy {
+	algorithm: RateLimitAlgorithm,
+	limit: u32,
+	period: Duration,
+}
+
+impl RateLimitPolicy {
+	fn new(algorithm: RateLimitAlgorithm, limit: u32, period: Duration) -> Self {
+		if limit == 0 {
+			panic!("rate limit must be greater than zero")
+		}
+
+		if period.is_zero() {
+			panic!("rate limit period must be greater than zero")
+		}
+
+		Self {
+			algorithm,
+			limit,
+			period,
+		}
+	}
+
+	/// Returns the algorithm of the policy.
+	#[inline(always)]
+	pub fn algorithm(&self) -> RateLimitAlgorithm {
+		self.algorithm
+	}
+
+	/// Returns the number of requests allowed in the period.
+	#[inline(always)]
+	pub fn limit(&self) -> u32 {
+		self.limit
+	}
+
+	/// Returns the token bucket's refill period or the sliding window's length.
+	#[inline(always)]
+	pub fn period(&self) -> Duration {
+		self.period
+	}
+}
+
+// -------------------------
+
+/// The rate limiting algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitAlgorithm {
+	TokenBucket,
+	SlidingWindow,
+}
+
+// --------------------------------------------------
+// RateLimitStatus
+
+/// The status of a key that's returned by the [`RateLimitStore`] when a request is counted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitStatus {
+	/// Whether the request is allowed.
+	pub is_allowed: bool,
+	/// The number of requests that are still allowed.
+	pub remaining: u32,
+	/// The time until the limit is fully restored.
+	pub reset_after: Duration,
+	/// The time until the next request can be allowed. Zero if the request is allowed.
+	pub retry_after: Duration,
+}
+
+// --------------------------------------------------
+// RateLimitStore
+
+/// A trait for the stores of the [`RateLimitLayer`]'s counts.
+///
+/// The trait can be implemented to keep the counts in a store shared between the instances
+/// of the server.
+pub trait RateLimitStore: Send + Sync + 'static {
+	/// Counts a request with the `key` under the `policy` and returns the key's status.
+	fn hit(
+		&self,
+		key: String,
+		policy: RateLimitPolicy,
+	) -> BoxedFuture<Result<RateLimitStatus, BoxedErrorResponse>>;
+}
+
+// -------------------------
+
+/// An in-memory [`RateLimitStore`].
+///
+/// The keys that have their limits fully restored are removed as the requests are counted.
+/// The number of keys is limited to 65536 by default. When the limit is reached, the key
+/// that's closest to having its limit fully restored is evicted. An evicted key starts over
+/// with a full limit, so the keys that can be chosen by the client, e.g., header values and
+/// path params, should have a bounded set of values or be limited by a store with enough room.
+pub struct MemoryRateLimitStore(Mutex<MemoryStoreState>);
+
+struct MemoryStoreState {
+	entries: HashMap<String, (Entry, RestorationKey)>,
+	// The keys ordered by the time their limits are fully restored.
+	restoration_order: BTreeMap<RestorationKey, String>,
+	next_sequence_number: u64,
+	max_entries: usize,
+}
+
+// The time the key's limit is fully restored and a sequence number that makes it unique.
+type RestorationKey = (Instant, u64);
+
+impl Default for MemoryRateLimitStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MemoryRateLimitStore {
+	/// Creates a new `MemoryRateLimitStore`.
+	pub fn new() -> Self {
+		Self(Mutex::new(MemoryStoreState {
+			entries: HashMap::new(),
+			restoration_order: BTreeMap::new(),
+			next_sequence_number: 0,
+			max_entries: DEFAULT_MAX_ENTRIES,
+		}))
+	}
+
+	/// Sets the maximum number of keys kept in the store.
+	///
+	/// # Panics
+	///
+	/// - if the `max_entries` is zero
+	pub fn with_max_entries(self, max_entries: usize) -> Self {
+		if max_entries == 0 {
+			panic!("max entries must be greater than zero")
+		}
+
+		let mut state = self
+			.0
+			.into_inner()
+			.unwrap_or_else(|error| error.into_inner());
+		state.max_entries = max_entries;
+
+		Self(Mutex::new(state))
+	}
+
+	fn hit_at(&self, key: String, policy: RateLimitPolicy, now: Instant) -> RateLimitStatus {
+		let mut state = self.0.lock().unwrap_or_else(|error| error.into_inner());
+		let state = &mut *state;
+
+		while let Some(first_entry) = state.restoration_order.first_entry() {
+			let (restored_at, _) = *first_entry.key();
+			if restored_at > now {
+				break;
+			}
+
+			let restored_key = first_entry.remove();
+			state.entries.remove(&restored_key);
+		}
+
+		let mut entry = match state.entries.remove(&key) {
+			Some((entry, restoration_key)) => {
+				state.restoration_order.remove(&restoration_key);
+
+				entry
+			}
+			None => {
+				if state.entries.len() >= state.max_entries {
+					if let Some((_, evicted_key)) = state.restoration_order.pop_first() {
+						state.entries.remove(&evicted_key);
+					}
+				}
+
+				Entry::new(&policy, now)
+			}
+		};
+
+		let status = entry.hit(&policy, now);
+
+		let restoration_key = (entry.restored_at(&policy), state.next_sequence_number);
+		state.next_sequence_number += 1;
+
+		state.restoration_order.insert(restoration_key, key.clone());
+		state.entries.insert(key, (entry, restoration_key));
+
+		status
+	}
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+	fn hit(
+		&self,
+		key: String,
+		policy: RateLimitPolicy,
+	) -> BoxedFuture<Result<RateLimitStatus, BoxedErrorResponse>> {
+		let status = self.hit_at(key, policy, Instant::now());
+
+		Box::pin(ready(Ok(status)))
+	}
+}
+
+// -------------------------
+
+enum Entry {
+	TokenBucket {
+		tokens: f64,
+		updated_at: Instant,
+	},
+	SlidingWindow {
+		window_start: Instant,
+		previous_count: u32,
+		current_count: u32,
+		updated_at: Instant,
+	},
+}
+
+impl Entry {
+	fn new(policy: &RateLimitPolicy, now: Instant) -> Self {
+		match policy.algorithm {
+			RateLimitAlgorithm::TokenBucket => Self::TokenBucket {
+				tokens: policy.limit as f64,
+				updated_at: now,
+			},
+			RateLimitAlgorithm::SlidingWindow => Self::SlidingWindow {
+				window_start: now,
+				previous_count: 0,
+				current_count: 0,
+				updated_at: now,
+			},
+		}
+	}
+
+	fn hit(&mut self, policy: &RateLimitPolicy, now: Instant) -> RateLimitStatus {
+		self.advance(policy, now);
+
+		let limit = policy.limit as f64;
+		let period = policy.period.as_secs_f64();
+
+		match self {
+			Self::TokenBucket { tokens, .. } => {
+				let refill_rate = limit / period;
+
+				let is_allowed = *tokens >= 1.0;
+				let retry_after = if is_allowed {
+					*tokens -= 1.0;
+
+					0.0
+				} else {
+					(1.0 - *tokens) / refill_rate
+				};
+
+				RateLimitStatus {
+					is_allowed,
+					remaining: tokens.floor() as u32,
+					reset_after: Duration::from_secs_f64((limit - *tokens) / refill_rate),
+					retry_after: Duration::from_secs_f64(retry_after),
+				}
+			}
+			Self::SlidingWindow {
+				window_start,
+				previous_count,
+				current_count,
+				..
+			} => {
+				let elapsed_part = now.duration_since(*window_start).as_secs_f64() / period;
+				let previous_count = *previous_count as f64;
+
+				let estimated_count = previous_count * (1.0 - elapsed_part) + *current_count as f64;
+
+				let is_allowed = estimated_count + 1.0 <= limit;
+				let retry_after = if is_allowed {
+					*current_count += 1;
+
+					0.0
+				} else if (*current_count as f64) + 1.0 <= limit {
+					// The previous window's requests are still counted.
+					1.0 - (limit - 1.0 - *current_count as f64) / previous_count - elapsed_part
+				} else {
+					// The current window's requests will be counted in the next window.
+					(1.0 - elapsed_part) + (1.0 - (limit - 1.0) / *current_count as f64)
+				};
+
+				let remaining = if is_allowed {
+					(limit - estimated_count - 1.0).floor() as u32
+				} else {
+					0
+				};
+
+				let reset_after = if *current_count > 0 {
+					2.0 - elapsed_part
+				} else {
+					1.0 - elapsed_part
+				};
+
+				RateLimitStatus {
+					is_allowed,
+					remaining,
+					reset_after: Duration::from_secs_f64(reset_after * period),
+					retry_after: Duration::from_secs_f64(retry_after.max(0.0) * period),
+				}
+			}
+		}
+	}
+
+	// Refills the tokens or moves the window to the current time.
+	fn advance(&mut self, policy: &RateLimitPolicy, now: Instant) {
+		match self {
+			Self::TokenBucket { tokens, updated_at } => {
+				let refill_rate = policy.limit as f64 / policy.period.as_secs_f64();
+				let elapsed = now.duration_since(*updated_at).as_secs_f64();
+
+				*tokens = (*tokens + elapsed * refill_rate).min(policy.limit as f64);
+				*updated_at = now;
+			}
+			Self::SlidingWindow {
+				window_start,
+				previous_count,
+				current_count,
+				updated_at,
+			} => {
+				*updated_at = now;
+
+				let elapsed = now.duration_since(*window_start).as_nanos();
+				let period = policy.period.as_nanos();
+
+				if elapsed < period {
+					return;
+				}
+
+				*previous_count = if elapsed < 2 * period {
+					*current_count
+				} else {
+					0
+				};
+
+				*current_count = 0;
+				*window_start = now - Duration::from_nanos((elapsed % period) as u64);
+			}
+		}
+	}
+
+	// Returns the time the entry's limit is fully restored.
+	fn restored_at(&self, policy: &RateLimitPolicy) -> Instant {
+		match self {
+			Self::TokenBucket { tokens, updated_at } => {
+				let refill_rate = policy.limit as f64 / policy.period.as_secs_f64();
+
+				*updated_at + Duration::from_secs_f64((policy.limit as f64 - *tokens) / refill_rate)
+			}
+			Self::SlidingWindow { window_start, .. } => *window_start + 2 * policy.period,
+		}
+	}
+}
+
+// --------------------------------------------------
+// TooManyRequestsError
+
+/// An error type that's returned by the [`RateLimitLayer`]'s middleware when the request's
+/// key has exceeded its limit.
+///
+/// The error is converted into a "429 Too Many Requests" response with the `Retry-After`
+/// and `RateLimit-*` headers.
+#[derive(Debug, crate::ImplError)]
+#[error("too many requests")]
+pub struct TooManyRequestsError {
+	limit: u32,
+	status: RateLimitStatus,
+}
+
+impl TooManyRequestsError {
+	/// Returns the time until the next request can be allowed.
+	pub fn retry_after(&self) -> Duration {
+		self.status.retry_after
+	}
+}
+
+impl IntoResponse for TooManyRequestsError {
+	fn into_response(self) -> Response {
+		let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+
+		let headers = response.headers_mut();
+		headers.insert(RETRY_AFTER, seconds_value(self.status.retry_after));
+		insert_rate_limit_headers(headers, self.limit, &self.status);
+
+		response
+	}
+}
+
+// --------------------------------------------------
+// RateLimitError
+
+/// An error type that wraps the error returned by the handler of the request that's
+/// counted by the [`RateLimitLayer`].
+///
+/// The error passes through the error handlers applied above the [`RateLimitLayer`] like
+/// any other error, and its response has the `RateLimit-*` headers. The wrapped error can
+/// be retrieved with [`Self::into_inner()`].
+pub struct RateLimitError {
+	error: BoxedErrorResponse,
+	limit: u32,
+	status: RateLimitStatus,
+}
+
+impl RateLimitError {
+	/// Returns a reference to the wrapped error.
+	#[inline(always)]
+	pub fn inner_ref(&self) -> &BoxedErrorResponse {
+		&self.error
+	}
+
+	/// Returns the wrapped error without the `RateLimit-*` headers.
+	#[inline(always)]
+	pub fn into_inner(self) -> BoxedErrorResponse {
+		self.error
+	}
+}
+
+impl Debug for RateLimitError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("RateLimitError").field(&self.error).finish()
+	}
+}
+
+impl Display for RateLimitError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		Display::fmt(&self.error, f)
+	}
+}
+
+impl StdError for RateLimitError {
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		Some(self.error.as_ref())
+	}
+}
+
+impl IntoResponse for RateLimitError {
+	fn into_response(self) -> Response {
+		let mut response = self.error.into_response();
+		insert_rate_limit_headers(response.headers_mut(), self.limit, &self.status);
+
+		response
+	}
+}
+
+// --------------------------------------------------
+
+mod private {
+	use super::*;
+
+	// --------------------------------------------------
+	// RateLimiter
+
+	#[derive(Clone)]
+	pub struct RateLimiter<H> {
+		inner_handler: H,
+		layer: RateLimitLayer,
+	}
+
+	impl<H> RateLimiter<H> {
+		pub(super) fn new(handler: H, layer: RateLimitLayer) -> Self {
+			Self {
+				inner_handler: handler,
+				layer,
+			}
+		}
+	}
+
+	impl<H, Ext> Handler<Body, Ext> for RateLimiter<H>
+	where
+		H: Handler<
+				Body,
+				Ext,
+				Response = Response,
+				Error = BoxedErrorResponse,
+				Future = BoxedFuture<Result<Response, BoxedErrorResponse>>,
+			> + Clone
+			+ Send
+			+ 'static,
+		Ext: Clone + Send + Sync + 'static,
+	{
+		type Response = Response;
+		type Error = BoxedErrorResponse;
+		type Future = BoxedFuture<Result<Self::Response, Self::Error>>;
+
+		fn handle(&self, request_context: RequestContext, args: Args<'_, Ext>) -> Self::Future {
+			let Some(key) = self.layer.key.key_of(&request_context) else {
+				return self.inner_handler.handle(request_context, args);
+			};
+
+			let limit = self.layer.policy.limit;
+			let status_future = self.layer.store.hit(key, self.layer.policy);
+
+			let inner_handler = self.inner_handler.clone();
+			let args = args.into_owned();
+
+			Box::pin(async move {
+				let status = status_future.await?;
+
+				if !status.is_allowed {
+					return Err(TooManyRequestsError { limit, status }.into());
+				}
+
+				match inner_handler.handle(request_context, args).await {
+					Ok(mut response) => {
+						insert_rate_limit_headers(response.headers_mut(), limit, &status);
+
+						Ok(response)
+					}
+					Err(error) => Err(
+						RateLimitError {
+							error,
+							limit,
+							status,
+						}
+						.into(),
+					),
+				}
+			})
+		}
+	}
+}
+
+pub(crate) use private::RateLimiter;
+
+// --------------------------------------------------
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, limit: u32, status: &RateLimitStatus) {
+	headers.insert(RATELIMIT_LIMIT, HeaderValue::from(limit));
+	headers.insert(RATELIMIT_REMAINING, HeaderValue::from(status.remaining));
+	headers.insert(RATELIMIT_RESET, seconds_value(status.reset_after));
+}
+
+// Returns the duration in seconds, rounded up.
+#[inline(always)]
+fn seconds_value(duration: Duration) -> HeaderValue {
+	let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+
+	HeaderValue::from(seconds)
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use std::net::SocketAddr;
+
+	use http::Method;
+
+	use crate::{
+		handler::HandlerSetter, middleware::RequestReceiver, resource::Resource,
+		test_client::TestClient,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[tokio::test]
+	async fn rate_limit() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async { "Hello, World!" }));
+
+		root.wrap(
+			RequestReceiver.component_in(
+				RateLimitLayer::token_bucket(2, Duration::from_secs(60))
+					.key_by(RateLimitKey::header(HeaderName::from_static("x-api-key"))),
+			),
+		);
+
+		let client = TestClient::new(root.into_service());
+
+		// -------------------------
+
+		for remaining in ["1", "0"] {
+			let response = client.get("/").header("x-api-key", "a").send().await;
+			assert_eq!(response.status(), StatusCode::OK);
+			assert_eq!(response.header("ratelimit-limit"), Some("2"));
+			assert_eq!(response.header("ratelimit-remaining"), Some(remaining));
+		}
+
+		let response = client.get("/").header("x-api-key", "a").send().await;
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+		assert_eq!(response.header(RETRY_AFTER.as_str()), Some("30"));
+		assert_eq!(response.header("ratelimit-limit"), Some("2"));
+		assert_eq!(response.header("ratelimit-remaining"), Some("0"));
+		assert_eq!(response.header("ratelimit-reset"), Some("60"));
+
+		// -------------------------
+
+		let response = client.get("/").header("x-api-key", "b").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.header("ratelimit-remaining"), Some("1"));
+
+		// Requests without the key aren't limited.
+		let response = client.get("/").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert!(response.header("ratelimit-limit").is_none());
+	}
+
+	#[tokio::test]
+	async fn rate_limit_path_param_and_peer_ip() {
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/users/{id}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		root.subresource_mut("/users/{id}").wrap(
+			RequestReceiver.component_in(
+				RateLimitLayer::sliding_window(1, Duration::from_secs(60))
+					.key_by(RateLimitKey::path_param("id")),
+			),
+		);
+
+		root
+			.subresource_mut("/teams/{team:[a-z]+}-{number:\\d+}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		root
+			.subresource_mut("/teams/{team:[a-z]+}-{number:\\d+}")
+			.wrap(
+				RequestReceiver.component_in(
+					RateLimitLayer::sliding_window(1, Duration::from_secs(60))
+						.key_by(RateLimitKey::path_param("team")),
+				),
+			);
+
+		root
+			.subresource_mut("/items")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		root.subresource_mut("/items").wrap(
+			RequestReceiver.component_in(RateLimitLayer::sliding_window(1, Duration::from_secs(60))),
+		);
+
+		let service = root.into_arc_service();
+
+		let client = TestClient::new(service.clone());
+		let other_client =
+			TestClient::new(service).with_peer_addr(SocketAddr::from(([10, 0, 0, 1], 4321)));
+
+		// -------------------------
+
+		let response = client.get("/users/1").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = other_client.get("/users/1").send().await;
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+		let response = other_client.get("/users/2").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = client.get("/teams/red-1").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = client.get("/teams/red-2").send().await;
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+		let response = client.get("/teams/blue-1").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		// -------------------------
+
+		let response = client.get("/items").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let response = client.get("/items").send().await;
+		assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+		let response = other_client.get("/items").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+	}
+
+	#[test]
+	fn memory_store_sliding_window() {
+		let store = MemoryRateLimitStore::new();
+		let policy = RateLimitPolicy::new(
+			RateLimitAlgorithm::SlidingWindow,
+			4,
+			Duration::from_secs(10),
+		);
+
+		let start = Instant::now();
+		let key = || "key".to_string();
+
+		for remaining in (0..4).rev() {
+			let status = store.hit_at(key(), policy, start);
+			assert!(status.is_allowed);
+			assert_eq!(status.remaining, remaining);
+		}
+
+		let status = store.hit_at(key(), policy, start + Duration::from_secs(5));
+		assert!(!status.is_allowed);
+		assert_eq!(status.remaining, 0);
+		// In the next window, the 4 previous requests are estimated as 3 after 2.5 seconds.
+		assert_eq!(status.retry_after, Duration::from_millis(7500));
+
+		// 4 * 0.75 = 3 requests are estimated in the sliding window.
+		let status = store.hit_at(key(), policy, start + Duration::from_millis(12500));
+		assert!(status.is_allowed);
+		assert_eq!(status.remaining, 0);
+
+		let status = store.hit_at(key(), policy, start + Duration::from_millis(12500));
+		assert!(!status.is_allowed);
+
+		// The entries that are fully restored are removed.
+		store.hit_at("other".to_string(), policy, start + Duration::from_secs(40));
+
+		let state = store.0.lock().unwrap();
+		assert_eq!(state.entries.len(), 1);
+		assert!(state.entries.contains_key("other"));
+	}
+
+	#[test]
+	fn memory_store_max_entries() {
+		let store = MemoryRateLimitStore::new().with_max_entries(2);
+		let policy = RateLimitPolicy::new(RateLimitAlgorithm::TokenBucket, 1, Duration::from_secs(10));
+
+		let start = Instant::now();
+
+		store.hit_at("a".to_string(), policy, start);
+		store.hit_at("b".to_string(), policy, start + Duration::from_secs(1));
+
+		// The key that is closest to having its limit restored is evicted.
+		store.hit_at("c".to_string(), policy, start + Duration::from_secs(2));
+
+		{
+			let state = store.0.lock().unwrap();
+			assert_eq!(state.entries.len(), 2);
+			assert!(state.entries.contains_key("b"));
+			assert!(state.entries.contains_key("c"));
+		}
+
+		// The existing keys are counted without an eviction.
+		let status = store.hit_at("b".to_string(), policy, start + Duration::from_secs(3));
+		assert!(!status.is_allowed);
+
+		// The restored keys are removed without an eviction.
+		store.hit_at(
+			"d".to_string(),
+			policy,
+			start + Duration::from_millis(11500),
+		);
+
+		let state = store.0.lock().unwrap();
+		assert_eq!(state.entries.len(), 2);
+		assert!(state.entries.contains_key("c"));
+		assert!(state.entries.contains_key("d"));
+	}
+
+	#[tokio::test]
+	async fn rate_limit_errors() {
+		use crate::middleware::ErrorHandlerLayer;
+
+		#[derive(Debug, crate::ImplError)]
+		#[error("teapot")]
+		struct Teapot;
+
+		impl IntoResponse for Teapot {
+			fn into_response(self) -> Response {
+				StatusCode::IM_A_TEAPOT.into_response()
+			}
+		}
+
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async { Err::<(), _>(Teapot) }));
+
+		root.wrap(
+			RequestReceiver.component_in((
+				// The error handler is applied above the RateLimitLayer.
+				ErrorHandlerLayer::new(|error: BoxedErrorResponse| async move {
+					match error.downcast_to_ref::<RateLimitError>() {
+						Some(rate_limit_error) if rate_limit_error.inner_ref().is::<Teapot>() => Err(error),
+						_ => Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response()),
+					}
+				}),
+				RateLimitLayer::token_bucket(2, Duration::from_secs(60))
+					.key_by(RateLimitKey::header(HeaderName::from_static("x-api-key"))),
+			)),
+		);
+
+		let client = TestClient::new(root.into_service());
+
+		let response = client.get("/").header("x-api-key", "a").send().await;
+		assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+		assert_eq!(response.header("ratelimit-limit"), Some("2"));
+		assert_eq!(response.header("ratelimit-remaining"), Some("1"));
+	}
+}
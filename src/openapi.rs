@@ -0,0 +1,654 @@
+//! OpenAPI document generation.
+
+// ----------
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use http::{Method, StatusCode};
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use crate::resource::Route;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+pub(crate) const APPLICATION_YAML: &str = "application/yaml";
+
+// --------------------------------------------------
+// OpenApi
+
+/// An OpenAPI 3.1 document of the routes.
+///
+/// The paths of the document come from the routes' patterns. Wildcard `{name}`, regex
+/// `{name:pattern}` and constrained `{name:@constraint}` captures become path parameters.
+/// The regex subpatterns become their `pattern` constraints, and the built-in segment
+/// constraints their types and formats, e.g., `@u64` becomes a non-negative `integer` and
+/// `@uuid` a `string` with the `uuid` format. Operation metadata can be attached to the
+/// *method* handlers with the `with_operation()` method of the handler kind.
+///
+/// ```
+/// use argan::{
+///   Router,
+///   handler::HandlerSetter,
+///   http::Method,
+///   openapi::{OpenApi, Operation},
+/// };
+///
+/// let mut router = Router::new();
+/// router.resource_mut("/users/{id:\\d+}").set_handler_for(
+///   Method::GET
+///     .to(|| async {})
+///     .with_operation(Operation::new().with_summary("Get a user")),
+/// );
+///
+/// let document = OpenApi::new("Users", "1.0.0").with_routes(router.routes());
+/// let json = document.to_json_pretty();
+/// let yaml = document.to_yaml();
+/// ```
+///
+/// The document implements `Serialize`, so it can also be serialized with other formats.
+///
+/// OpenAPI paths don't include a host, so a document can't describe the same path under
+/// different hosts. The routes of such hosts can be put into separate documents by
+/// filtering them with [`Route::host()`].
+///
+/// ```
+/// # use argan::{Router, handler::HandlerSetter, http::Method, openapi::OpenApi};
+/// let mut router = Router::new();
+/// router
+///   .resource_mut("http://api.example.com/users")
+///   .set_handler_for(Method::GET.to(|| async {}));
+///
+/// router
+///   .resource_mut("http://admin.example.com/users")
+///   .set_handler_for(Method::GET.to(|| async {}));
+///
+/// let document = OpenApi::new("Users", "1.0.0")
+///   .with_server("https://api.example.com")
+///   .with_routes(router.routes().filter(|route| route.host() == Some("api.example.com")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+	title: String,
+	version: String,
+	some_description: Option<String>,
+	servers: Vec<String>,
+	routes: Vec<Route>,
+}
+
+impl OpenApi {
+	/// Creates a new document with the API's `title` and `version`.
+	pub fn new<T, V>(title: T, version: V) -> Self
+	where
+		T: Into<String>,
+		V: Into<String>,
+	{
+		Self {
+			title: title.into(),
+			version: version.into(),
+			some_description: None,
+			servers: Vec::new(),
+			routes: Vec::new(),
+		}
+	}
+
+	/// Sets the description of the API.
+	pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+		self.some_description = Some(description.into());
+
+		self
+	}
+
+	/// Adds the URL of a server that serves the API.
+	pub fn with_server<U: Into<String>>(mut self, url: U) -> Self {
+		self.servers.push(url.into());
+
+		self
+	}
+
+	/// Adds the routes to the document.
+	///
+	/// The routes can be listed with [`Router::routes()`](crate::Router::routes) or
+	/// [`Resource::routes()`](crate::Resource::routes).
+	pub fn with_routes<R>(mut self, routes: R) -> Self
+	where
+		R: IntoIterator<Item = Route>,
+	{
+		self.routes.extend(routes);
+
+		self
+	}
+
+	/// Returns the document as a JSON value.
+	///
+	/// # Panics
+	///
+	/// - if routes of different hosts or of a host and no host have the same path
+	pub fn to_value(&self) -> Value {
+		let mut info = Map::new();
+		info.insert("title".to_owned(), Value::from(self.title.as_str()));
+		info.insert("version".to_owned(), Value::from(self.version.as_str()));
+
+		if let Some(description) = self.some_description.as_deref() {
+			info.insert("description".to_owned(), Value::from(description));
+		}
+
+		let mut document = Map::new();
+		document.insert("openapi".to_owned(), Value::from(OPENAPI_VERSION));
+		document.insert("info".to_owned(), Value::Object(info));
+
+		if !self.servers.is_empty() {
+			let servers = self
+				.servers
+				.iter()
+				.map(|url| json!({ "url": url }))
+				.collect();
+
+			document.insert("servers".to_owned(), Value::Array(servers));
+		}
+
+		let mut paths = Map::new();
+		let mut path_hosts = HashMap::<&str, Option<&str>>::new();
+
+		for route in self.routes.iter() {
+			let some_host = route.host();
+
+			match path_hosts.entry(route.path_template()) {
+				Entry::Occupied(entry) if *entry.get() != some_host => panic!(
+					"the path '{}' of the host '{}' collides with the path of the host '{}'",
+					route.path_template(),
+					some_host.unwrap_or("<none>"),
+					entry.get().unwrap_or("<none>"),
+				),
+				Entry::Occupied(_) => {}
+				Entry::Vacant(entry) => {
+					entry.insert(some_host);
+				}
+			}
+
+			let parameters = route
+				.path_params()
+				.iter()
+				.map(|(name, some_subpattern)| {
+					json!({
+						"name": name,
+						"in": "path",
+						"required": true,
+						"schema": param_schema(some_subpattern.as_deref()),
+					})
+				})
+				.collect::<Vec<_>>();
+
+			let path_item = paths
+				.entry(route.path_template())
+				.or_insert_with(|| Value::Object(Map::new()));
+
+			for method in route.methods() {
+				let Some(method_name) = openapi_method_name(method) else {
+					continue;
+				};
+
+				let some_operation = route
+					.operations()
+					.iter()
+					.find_map(|(operation_method, operation)| {
+						(operation_method == method).then_some(operation)
+					});
+
+				let operation = match some_operation {
+					Some(operation) => operation.to_value(&parameters),
+					None => Operation::new().to_value(&parameters),
+				};
+
+				path_item[method_name] = operation;
+			}
+		}
+
+		document.insert("paths".to_owned(), Value::Object(paths));
+
+		Value::Object(document)
+	}
+
+	/// Returns the document as a JSON string.
+	pub fn to_json(&self) -> String {
+		self.to_value().to_string()
+	}
+
+	/// Returns the document as a pretty-printed JSON string.
+	pub fn to_json_pretty(&self) -> String {
+		serde_json::to_string_pretty(&self.to_value()).expect("JSON value should be serializable")
+	}
+
+	/// Returns the document as a YAML string.
+	pub fn to_yaml(&self) -> String {
+		serde_yaml_ng::to_string(&self.to_value()).expect("JSON value should be serializable")
+	}
+}
+
+impl Serialize for OpenApi {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.to_value().serialize(serializer)
+	}
+}
+
+// Returns the JSON schema of a path param with the regex subpattern or the constraint
+// of its capture.
+fn param_schema(some_subpattern: Option<&str>) -> Value {
+	match some_subpattern {
+		None => json!({ "type": "string" }),
+		Some("@u64") => json!({ "type": "integer", "minimum": 0 }),
+		Some("@alnum") => json!({ "type": "string", "pattern": "^[A-Za-z0-9]+$" }),
+		Some("@uuid") => json!({ "type": "string", "format": "uuid" }),
+		Some("@date") => json!({ "type": "string", "format": "date" }),
+		// Custom constraints have no schema of their own.
+		Some(constraint) if constraint.starts_with('@') => json!({ "type": "string" }),
+		Some(subpattern) => json!({ "type": "string", "pattern": format!("^(?:{})$", subpattern) }),
+	}
+}
+
+fn openapi_method_name(method: &Method) -> Option<&'static str> {
+	let method_name = match *method {
+		Method::GET => "get",
+		Method::PUT => "put",
+		Method::POST => "post",
+		Method::DELETE => "delete",
+		Method::OPTIONS => "options",
+		Method::HEAD => "head",
+		Method::PATCH => "patch",
+		Method::TRACE => "trace",
+		_ => return None,
+	};
+
+	Some(method_name)
+}
+
+// --------------------------------------------------
+// Operation
+
+/// OpenAPI metadata of the operation that a *method* handler performs.
+///
+/// ```
+/// use argan::{http::StatusCode, openapi::Operation};
+/// use serde_json::json;
+///
+/// let operation = Operation::new()
+///   .with_operation_id("create_user")
+///   .with_summary("Create a user")
+///   .with_tag("users")
+///   .with_request_body("application/json", json!({ "$ref": "#/components/schemas/User" }))
+///   .with_response(StatusCode::CREATED, "The user was created")
+///   .with_response(StatusCode::BAD_REQUEST, "Invalid user data");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Operation {
+	some_operation_id: Option<String>,
+	some_summary: Option<String>,
+	some_description: Option<String>,
+	tags: Vec<String>,
+	some_request_body: Option<Content>,
+	responses: Vec<(StatusCode, String, Option<Content>)>,
+	deprecated: bool,
+}
+
+// A content type and the JSON schema of the content.
+type Content = (String, Value);
+
+// JSON values can't contain NaN numbers, so the equality is reflexive.
+impl Eq for Operation {}
+
+impl Operation {
+	/// Creates a new empty operation.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the unique ID of the operation.
+	pub fn with_operation_id<I: Into<String>>(mut self, operation_id: I) -> Self {
+		self.some_operation_id = Some(operation_id.into());
+
+		self
+	}
+
+	/// Sets the short summary of the operation.
+	pub fn with_summary<S: Into<String>>(mut self, summary: S) -> Self {
+		self.some_summary = Some(summary.into());
+
+		self
+	}
+
+	/// Sets the description of the operation.
+	pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+		self.some_description = Some(description.into());
+
+		self
+	}
+
+	/// Adds a tag to the operation.
+	pub fn with_tag<T: Into<String>>(mut self, tag: T) -> Self {
+		self.tags.push(tag.into());
+
+		self
+	}
+
+	/// Sets the content type and the JSON schema of the request body.
+	pub fn with_request_body<C: Into<String>>(mut self, content_type: C, schema: Value) -> Self {
+		self.some_request_body = Some((content_type.into(), schema));
+
+		self
+	}
+
+	/// Adds a response with the status code and description.
+	pub fn with_response<D: Into<String>>(mut self, status_code: StatusCode, description: D) -> Self {
+		self.responses.push((status_code, description.into(), None));
+
+		self
+	}
+
+	/// Adds a response with the status code and description, and the content type and
+	/// the JSON schema of its body.
+	pub fn with_response_body<D, C>(
+		mut self,
+		status_code: StatusCode,
+		description: D,
+		content_type: C,
+		schema: Value,
+	) -> Self
+	where
+		D: Into<String>,
+		C: Into<String>,
+	{
+		self.responses.push((
+			status_code,
+			description.into(),
+			Some((content_type.into(), schema)),
+		));
+
+		self
+	}
+
+	/// Marks the operation as deprecated.
+	pub fn deprecated(mut self) -> Self {
+		self.deprecated = true;
+
+		self
+	}
+
+	fn to_value(&self, parameters: &[Value]) -> Value {
+		let mut operation = Map::new();
+
+		if let Some(operation_id) = self.some_operation_id.as_deref() {
+			operation.insert("operationId".to_owned(), Value::from(operation_id));
+		}
+
+		if let Some(summary) = self.some_summary.as_deref() {
+			operation.insert("summary".to_owned(), Value::from(summary));
+		}
+
+		if let Some(description) = self.some_description.as_deref() {
+			operation.insert("description".to_owned(), Value::from(description));
+		}
+
+		if !self.tags.is_empty() {
+			operation.insert("tags".to_owned(), Value::from(self.tags.clone()));
+		}
+
+		if !parameters.is_empty() {
+			operation.insert("parameters".to_owned(), Value::from(parameters.to_vec()));
+		}
+
+		if let Some((content_type, schema)) = self.some_request_body.as_ref() {
+			operation.insert(
+				"requestBody".to_owned(),
+				json!({
+					"required": true,
+					"content": { content_type: { "schema": schema } },
+				}),
+			);
+		}
+
+		let mut responses = Map::new();
+
+		for (status_code, description, some_content) in self.responses.iter() {
+			let mut response = json!({ "description": description });
+			if let Some((content_type, schema)) = some_content {
+				response["content"] = json!({ content_type: { "schema": schema } });
+			}
+
+			responses.insert(status_code.as_str().to_owned(), response);
+		}
+
+		if responses.is_empty() {
+			responses.insert(
+				"default".to_owned(),
+				json!({ "description": "Default response" }),
+			);
+		}
+
+		operation.insert("responses".to_owned(), Value::Object(responses));
+
+		if self.deprecated {
+			operation.insert("deprecated".to_owned(), Value::Bool(true));
+		}
+
+		Value::Object(operation)
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use crate::{handler::HandlerSetter, test_client::TestClient, Resource, Router};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[test]
+	fn openapi_document() {
+		let mut root = Resource::new("/");
+		root.subresource_mut("/users").set_handler_for([
+			Method::GET.to(|| async {}),
+			Method::POST.to(|| async {}).with_operation(
+				Operation::new()
+					.with_operation_id("create_user")
+					.with_tag("users")
+					.with_request_body("application/json", json!({ "type": "object" }))
+					.with_response(StatusCode::CREATED, "Created"),
+			),
+		]);
+
+		root
+			.subresource_mut("/users/{id:\\d+}/posts/{post}/")
+			.set_handler_for(
+				Method::GET
+					.to(|| async {})
+					.with_operation(Operation::new().with_summary("Get a post").deprecated()),
+			);
+
+		root
+			.subresource_mut("/orders/{id:@u64}/{token:@uuid}/{day:@date}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		root
+			.subresource_mut("/files")
+			.set_handler_for(Method::from_bytes(b"CUSTOM").unwrap().to(|| async {}));
+
+		let document = OpenApi::new("Test", "1.0.0")
+			.with_description("Test API")
+			.with_server("https://example.com")
+			.with_routes(root.routes())
+			.to_value();
+
+		assert_eq!(
+			document,
+			json!({
+				"openapi": "3.1.0",
+				"info": { "title": "Test", "version": "1.0.0", "description": "Test API" },
+				"servers": [{ "url": "https://example.com" }],
+				"paths": {
+					"/users": {
+						"get": {
+							"responses": { "default": { "description": "Default response" } },
+						},
+						"post": {
+							"operationId": "create_user",
+							"tags": ["users"],
+							"requestBody": {
+								"required": true,
+								"content": { "application/json": { "schema": { "type": "object" } } },
+							},
+							"responses": { "201": { "description": "Created" } },
+						},
+					},
+					"/users/{id}/posts/{post}/": {
+						"get": {
+							"summary": "Get a post",
+							"parameters": [
+								{
+									"name": "id",
+									"in": "path",
+									"required": true,
+									"schema": { "type": "string", "pattern": "^(?:\\d+)$" },
+								},
+								{
+									"name": "post",
+									"in": "path",
+									"required": true,
+									"schema": { "type": "string" },
+								},
+							],
+							"responses": { "default": { "description": "Default response" } },
+							"deprecated": true,
+						},
+					},
+					"/orders/{id}/{token}/{day}": {
+						"get": {
+							"parameters": [
+								{
+									"name": "id",
+									"in": "path",
+									"required": true,
+									"schema": { "type": "integer", "minimum": 0 },
+								},
+								{
+									"name": "token",
+									"in": "path",
+									"required": true,
+									"schema": { "type": "string", "format": "uuid" },
+								},
+								{
+									"name": "day",
+									"in": "path",
+									"required": true,
+									"schema": { "type": "string", "format": "date" },
+								},
+							],
+							"responses": { "default": { "description": "Default response" } },
+						},
+					},
+					"/files": {},
+				},
+			}),
+		);
+	}
+
+	#[test]
+	fn openapi_document_yaml() {
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/users/{id:@u64}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		let document = OpenApi::new("Test", "1.0.0").with_routes(root.routes());
+
+		let yaml = document.to_yaml();
+		let value = serde_yaml_ng::from_str::<Value>(&yaml).unwrap();
+
+		assert_eq!(value, document.to_value());
+		assert!(yaml.lines().any(|line| line == "openapi: 3.1.0"));
+	}
+
+	#[test]
+	#[should_panic(expected = "the path '/users' of the host 'admin.example.com' collides")]
+	fn openapi_document_host_path_collision() {
+		let mut router = Router::new();
+		router
+			.resource_mut("http://api.example.com/users")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		router
+			.resource_mut("http://admin.example.com/users")
+			.set_handler_for(Method::DELETE.to(|| async {}));
+
+		// The routes of a single host make a valid document.
+		let document = OpenApi::new("Test", "1.0.0")
+			.with_routes(
+				router
+					.routes()
+					.filter(|route| route.host() == Some("api.example.com")),
+			)
+			.to_value();
+
+		assert!(document["paths"]["/users"]["get"].is_object());
+		assert!(document["paths"]["/users"]["delete"].is_null());
+
+		OpenApi::new("Test", "1.0.0")
+			.with_routes(router.routes())
+			.to_value();
+	}
+
+	#[tokio::test]
+	async fn openapi_document_service() {
+		let mut router = Router::new();
+		router
+			.resource_mut("/users/{id}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		router.set_openapi_document("/openapi.json", OpenApi::new("Test", "1.0.0"));
+
+		let client = TestClient::new(router.into_arc_service());
+
+		let response = client.get("/openapi.json").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.header("content-type").unwrap(), "application/json");
+
+		let document = response.json::<Value>();
+		assert!(document["paths"]["/users/{id}"]["get"].is_object());
+		assert!(document["paths"]["/openapi.json"].is_null());
+
+		// ----------
+
+		let mut router = Router::new();
+		router
+			.resource_mut("/users/{id}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		router.set_openapi_document("/openapi.yaml", OpenApi::new("Test", "1.0.0"));
+
+		let client = TestClient::new(router.into_arc_service());
+
+		let response = client.get("/openapi.yaml").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.header("content-type").unwrap(), "application/yaml");
+
+		let document = serde_yaml_ng::from_str::<Value>(response.text()).unwrap();
+		assert!(document["paths"]["/users/{id}"]["get"].is_object());
+	}
+
+	#[test]
+	#[should_panic(expected = "an operation can only be attached to a method handler")]
+	fn openapi_operation_on_non_method_handler() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(
+			crate::request::MistargetedRequest
+				.to(|| async {})
+				.with_operation(Operation::new()),
+		);
+	}
+}
@@ -0,0 +1,367 @@
+use std::{
+	fmt::{self, Debug},
+	sync::{Arc, OnceLock},
+};
+
+use crate::{common::SCOPE_VALIDITY, router::RouterReloadError};
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// A constraint registered on a node with the `NodeSegmentConstraint` property.
+pub(crate) type RegisteredConstraint = (Arc<str>, Arc<dyn SegmentConstraint>);
+
+// --------------------------------------------------
+// SegmentConstraint
+
+/// A trait for the constraints of the captures in the path segment and host patterns.
+///
+/// A constraint is applied to a capture with the `{name:@constraint}` syntax, e.g.,
+/// `/{id:@u64}`. The capture must be the only part of the path segment or host. A value
+/// that doesn't satisfy the constraint doesn't match the pattern, so the request is passed
+/// to the next sibling resource or handled as a mistargeted request.
+///
+/// The `@` sign distinguishes a constraint from a regex subpattern. With the `regex`
+/// feature flag, `{id:u64}` is a regex that matches only the text "u64". The sign keeps
+/// the meaning of a pattern independent of the enabled features.
+///
+/// The built-in constraints are:
+///
+/// | constraint | matches                                   |
+/// |------------|-------------------------------------------|
+/// | `u64`      | an unsigned 64-bit integer in decimal     |
+/// | `alnum`    | one or more ASCII alphanumeric characters |
+/// | `uuid`     | a hyphenated UUID                         |
+/// | `date`     | a valid calendar date in `YYYY-MM-DD`     |
+///
+/// Custom constraints are registered on a router or resource with the
+/// [`NodeSegmentConstraint`](crate::common::node_properties::NodeSegmentConstraint)
+/// property and can be used by the patterns of that node and its subtree. The patterns
+/// are checked when the node is converted into a service.
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   common::node_properties::NodeSegmentConstraint,
+///   resource::SegmentConstraint,
+/// };
+///
+/// struct Lowercase;
+///
+/// impl SegmentConstraint for Lowercase {
+///   fn matches(&self, value: &str) -> bool {
+///     !value.is_empty() && value.chars().all(char::is_lowercase)
+///   }
+/// }
+///
+/// let mut root = Resource::new("/");
+/// root.set_property(NodeSegmentConstraint.to("lowercase", Lowercase));
+/// root.subresource_mut("/users/{name:@lowercase}");
+/// ```
+pub trait SegmentConstraint: Send + Sync + 'static {
+	/// Returns `true` if the percent-decoded value satisfies the constraint.
+	fn matches(&self, value: &str) -> bool;
+}
+
+// Returns the registered constraint after validating its name.
+pub(crate) fn register<C: SegmentConstraint>(name: &str, constraint: C) -> RegisteredConstraint {
+	if name.is_empty()
+		|| !name
+			.chars()
+			.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+	{
+		panic!("invalid segment constraint name '{}'", name)
+	}
+
+	if built_in(name).is_some() {
+		panic!("segment constraint '{}' is a built-in constraint", name)
+	}
+
+	(name.into(), Arc::new(constraint))
+}
+
+// Adds the constraint to the node's constraints.
+pub(crate) fn add_registered(
+	constraints: &mut Vec<RegisteredConstraint>,
+	constraint: RegisteredConstraint,
+) {
+	if constraints.iter().any(|(name, _)| name == &constraint.0) {
+		panic!(
+			"segment constraint '{}' is already registered",
+			constraint.0
+		)
+	}
+
+	constraints.push(constraint);
+}
+
+fn built_in(name: &str) -> Option<Arc<dyn SegmentConstraint>> {
+	match name {
+		"u64" => Some(Arc::new(U64)),
+		"alnum" => Some(Arc::new(Alnum)),
+		"uuid" => Some(Arc::new(Uuid)),
+		"date" => Some(Arc::new(Date)),
+		_ => None,
+	}
+}
+
+// --------------------------------------------------
+// Constraint
+
+// A constraint of a capture. A named constraint is written as `@name` in a pattern.
+// Built-in constraints are available immediately, while registered constraints are
+// resolved against the nodes' registrations before the node is converted into a service.
+// The matcher is shared between the clones of the pattern.
+#[derive(Clone)]
+pub(crate) struct Constraint {
+	name: Arc<str>,
+	matcher: Arc<OnceLock<Arc<dyn SegmentConstraint>>>,
+}
+
+impl Constraint {
+	// Returns a named constraint if the subpattern starts with '@'.
+	pub(crate) fn named(subpattern: &str) -> Option<Self> {
+		let name = subpattern.strip_prefix('@')?;
+		if name.is_empty() {
+			panic!("empty segment constraint name")
+		}
+
+		let matcher = OnceLock::new();
+		if let Some(built_in_matcher) = built_in(name) {
+			let _ = matcher.set(built_in_matcher);
+		}
+
+		Some(Self {
+			name: subpattern.into(),
+			matcher: Arc::new(matcher),
+		})
+	}
+
+	// Returns a constraint that matches the whole value against the regex subpattern.
+	#[cfg(feature = "regex")]
+	pub(crate) fn regex(subpattern: &str) -> Self {
+		match Regex::new(&format!(r"\A(?:{})\z", subpattern)) {
+			Ok(regex) => Self {
+				name: subpattern.into(),
+				matcher: Arc::new(OnceLock::from(
+					Arc::new(RegexConstraint(regex)) as Arc<dyn SegmentConstraint>
+				)),
+			},
+			Err(error) => panic!("{}", error),
+		}
+	}
+
+	// Returns the constraint as it's written in the pattern.
+	#[inline(always)]
+	pub(crate) fn name(&self) -> &str {
+		&self.name
+	}
+
+	// Resolves the registered constraint. The closest registration takes precedence,
+	// so the constraints must be ordered from the root to the node.
+	pub(crate) fn resolve(
+		&self,
+		constraints: &[&RegisteredConstraint],
+	) -> Result<(), RouterReloadError> {
+		if self.matcher.get().is_some() {
+			return Ok(());
+		}
+
+		let name = self.name.strip_prefix('@').expect(SCOPE_VALIDITY);
+
+		let Some((_, matcher)) = constraints
+			.iter()
+			.rev()
+			.find(|(registered_name, _)| registered_name.as_ref() == name)
+		else {
+			return Err(RouterReloadError::UnknownSegmentConstraint(name.into()));
+		};
+
+		let _ = self.matcher.set(matcher.clone());
+
+		Ok(())
+	}
+
+	#[inline(always)]
+	pub(crate) fn matches(&self, value: &str) -> bool {
+		self
+			.matcher
+			.get()
+			.expect("segment constraint must be resolved before the pattern is used")
+			.matches(value)
+	}
+}
+
+impl Debug for Constraint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_tuple("Constraint").field(&self.name).finish()
+	}
+}
+
+// --------------------------------------------------
+// Built-in constraints
+
+struct U64;
+
+impl SegmentConstraint for U64 {
+	fn matches(&self, value: &str) -> bool {
+		is_ascii_digits(value) && value.parse::<u64>().is_ok()
+	}
+}
+
+struct Alnum;
+
+impl SegmentConstraint for Alnum {
+	fn matches(&self, value: &str) -> bool {
+		!value.is_empty() && value.bytes().all(|byte| byte.is_ascii_alphanumeric())
+	}
+}
+
+struct Uuid;
+
+impl SegmentConstraint for Uuid {
+	fn matches(&self, value: &str) -> bool {
+		let mut groups = value.split('-');
+
+		[8, 4, 4, 4, 12].into_iter().all(|length| {
+			groups.next().is_some_and(|group| {
+				group.len() == length && group.bytes().all(|byte| byte.is_ascii_hexdigit())
+			})
+		}) && groups.next().is_none()
+	}
+}
+
+struct Date;
+
+impl SegmentConstraint for Date {
+	fn matches(&self, value: &str) -> bool {
+		let mut parts = value.split('-');
+
+		let (Some(year), Some(month), Some(day), None) =
+			(parts.next(), parts.next(), parts.next(), parts.next())
+		else {
+			return false;
+		};
+
+		if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+			return false;
+		}
+
+		if !(is_ascii_digits(year) && is_ascii_digits(month) && is_ascii_digits(day)) {
+			return false;
+		}
+
+		let year = year.parse::<u32>().expect("year must be a valid number");
+		let month = month.parse::<u32>().expect("month must be a valid number");
+		let day = day.parse::<u32>().expect("day must be a valid number");
+
+		let days_in_month = match month {
+			1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+			4 | 6 | 9 | 11 => 30,
+			2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+			2 => 28,
+			_ => return false,
+		};
+
+		(1..=days_in_month).contains(&day)
+	}
+}
+
+#[cfg(feature = "regex")]
+struct RegexConstraint(Regex);
+
+#[cfg(feature = "regex")]
+impl SegmentConstraint for RegexConstraint {
+	fn matches(&self, value: &str) -> bool {
+		self.0.is_match(value)
+	}
+}
+
+#[inline(always)]
+fn is_ascii_digits(value: &str) -> bool {
+	!value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[test]
+	fn built_in_constraints() {
+		let cases = [
+			("u64", "0", true),
+			("u64", "18446744073709551615", true),
+			("u64", "18446744073709551616", false),
+			("u64", "+1", false),
+			("u64", "", false),
+			("alnum", "abc123", true),
+			("alnum", "abc-123", false),
+			("alnum", "", false),
+			("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8", true),
+			("uuid", "67E55044-10B1-426F-9247-BB680E5FE0C8", true),
+			("uuid", "67e5504410b1426f9247bb680e5fe0c8", false),
+			("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8-0", false),
+			("uuid", "g7e55044-10b1-426f-9247-bb680e5fe0c8", false),
+			("date", "2024-02-29", true),
+			("date", "2023-02-29", false),
+			("date", "2000-02-29", true),
+			("date", "1900-02-29", false),
+			("date", "2024-04-31", false),
+			("date", "2024-13-01", false),
+			("date", "2024-1-01", false),
+			("date", "2024-01-01-01", false),
+		];
+
+		for (name, value, expected) in cases {
+			let constraint = Constraint::named(&format!("@{}", name)).unwrap();
+			assert_eq!(constraint.matches(value), expected, "{}: {}", name, value);
+		}
+	}
+
+	#[test]
+	fn registered_constraint() {
+		struct Even;
+
+		impl SegmentConstraint for Even {
+			fn matches(&self, value: &str) -> bool {
+				value.parse::<u64>().is_ok_and(|number| number % 2 == 0)
+			}
+		}
+
+		assert!(Constraint::named("even").is_none());
+
+		let constraint = Constraint::named("@even").unwrap();
+		let cloned_constraint = constraint.clone();
+
+		let registered_constraint = register("even", Even);
+		constraint.resolve(&[&registered_constraint]).unwrap();
+
+		assert_eq!(cloned_constraint.name(), "@even");
+		assert!(cloned_constraint.matches("42"));
+		assert!(!cloned_constraint.matches("43"));
+	}
+
+	#[test]
+	fn unknown_constraint() {
+		let error = Constraint::named("@even")
+			.unwrap()
+			.resolve(&[])
+			.unwrap_err();
+		assert_eq!(error.to_string(), "unknown segment constraint 'even'");
+	}
+
+	#[test]
+	#[should_panic(expected = "segment constraint 'u64' is a built-in constraint")]
+	fn registered_constraint_built_in_name() {
+		register("u64", Alnum);
+	}
+}
@@ -164,7 +164,7 @@ macro_rules! declare_deserialize_for_simple_types {
 	};
 }
 
-impl<'de, 'a> Deserializer<'de> for &'a mut FromParams<'de> {
+impl<'de> Deserializer<'de> for &mut FromParams<'de> {
 	type Error = DeserializerError;
 
 	declare_deserialize_for_simple_types!(
@@ -70,7 +70,7 @@ macro_rules! declare_deserialize_for_simple_types {
 	};
 }
 
-impl<'a, 'de> Deserializer<'de> for &'a mut FromParamsList<'de> {
+impl<'de> Deserializer<'de> for &mut FromParamsList<'de> {
 	type Error = DeserializerError;
 
 	declare_deserialize_for_simple_types!(
@@ -348,12 +348,21 @@ mod test {
 			let pattern = patterns_iter.next().unwrap();
 			match pattern {
 				Pattern::Static(_) => assert!(pattern.is_static_match(match_segment).is_some_and(|r| r)),
-				Pattern::Regex(_, _) => assert!(pattern
+				Pattern::Regex(..) => assert!(pattern
 					.is_regex_match(match_segment, &mut params_list)
 					.is_some_and(|r| r),),
 				Pattern::Wildcard(_) => assert!(pattern
 					.is_wildcard_match(match_segment.into(), &mut params_list)
 					.is_some_and(|r| r),),
+				Pattern::Constrained(..) => assert!(pattern
+					.is_constrained_match(match_segment, &mut params_list)
+					.is_some_and(|r| r),),
+				Pattern::CatchAll(..) => assert!(pattern
+					.is_catch_all_match(match_segment.into(), &mut params_list)
+					.is_some_and(|r| r),),
+				Pattern::Subdomain(..) => assert!(pattern
+					.is_subdomain_match(match_segment, &mut params_list)
+					.is_some_and(|r| r),),
 			}
 		}
 
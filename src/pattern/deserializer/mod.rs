@@ -10,8 +10,10 @@ use serde::{
 
 mod from_params;
 mod from_params_list;
+mod probe;
 
 pub(crate) use from_params_list::FromParamsList;
+pub(crate) use probe::ExpectedParams;
 
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
@@ -0,0 +1,339 @@
+use std::fmt::Display;
+
+use serde::{
+	de::{DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor},
+	forward_to_deserialize_any, Deserializer,
+};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// ExpectedParams
+
+// Names of the params a handler expects to find among the captured path params.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ExpectedParams(Vec<(Box<str>, bool)>); // (name, is_required)
+
+impl ExpectedParams {
+	// Probes the type's `Deserialize` implementation for the names of the struct fields.
+	// Returns `None` when `T` is not a struct or when its fields cannot be determined.
+	//
+	// A field is considered required only if the type reports it missing. Option fields
+	// and fields with default values are never reported.
+	pub(crate) fn of<T: DeserializeOwned>() -> Option<Self> {
+		let mut state = ProbeState::default();
+
+		loop {
+			match T::deserialize(StructProbe(&mut state)) {
+				Ok(_) => break,
+				Err(ProbeError::MissingField(name)) => {
+					if state.required.contains(&name) {
+						break;
+					}
+
+					state.required.push(name);
+				}
+				Err(_) => break,
+			}
+		}
+
+		let fields = state.some_fields?;
+
+		Some(Self(
+			fields
+				.iter()
+				.map(|field| (Box::from(*field), state.required.contains(field)))
+				.collect(),
+		))
+	}
+
+	pub(crate) fn with_required_names<'n, N>(names: N) -> Self
+	where
+		N: IntoIterator<Item = &'n str>,
+	{
+		Self(
+			names
+				.into_iter()
+				.map(|name| (Box::from(name), true))
+				.collect(),
+		)
+	}
+
+	pub(crate) fn merge(mut self, other: Self) -> Self {
+		for (name, is_required) in other.0 {
+			if let Some(position) = self.0.iter().position(|(self_name, _)| *self_name == name) {
+				self.0[position].1 |= is_required;
+			} else {
+				self.0.push((name, is_required));
+			}
+		}
+
+		self
+	}
+
+	#[inline]
+	pub(crate) fn has(&self, name: &str) -> bool {
+		self
+			.0
+			.iter()
+			.any(|(self_name, _)| self_name.as_ref() == name)
+	}
+
+	#[inline]
+	pub(crate) fn required_names(&self) -> impl Iterator<Item = &str> {
+		self
+			.0
+			.iter()
+			.filter_map(|(name, is_required)| is_required.then_some(name.as_ref()))
+	}
+}
+
+// --------------------------------------------------
+// StructProbe
+
+#[derive(Default)]
+struct ProbeState {
+	some_fields: Option<&'static [&'static str]>,
+	required: Vec<&'static str>,
+}
+
+// The probe feeds the struct visitor only the fields that are already known to be
+// required. The visitor then reports the next required field as missing.
+struct StructProbe<'s>(&'s mut ProbeState);
+
+impl<'de> Deserializer<'de> for StructProbe<'_> {
+	type Error = ProbeError;
+
+	fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		Err(ProbeError::UnsupportedType)
+	}
+
+	fn deserialize_newtype_struct<V>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_struct<V>(
+		self,
+		_name: &'static str,
+		fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self.0.some_fields = Some(fields);
+
+		visitor.visit_map(FieldsAccess {
+			fields: self.0.required.clone().into_iter(),
+		})
+	}
+
+	forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+		option unit unit_struct seq tuple tuple_struct map enum identifier ignored_any
+	}
+}
+
+// -------------------------
+
+struct FieldsAccess {
+	fields: std::vec::IntoIter<&'static str>,
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess {
+	type Error = ProbeError;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+	where
+		K: DeserializeSeed<'de>,
+	{
+		self
+			.fields
+			.as_slice()
+			.first()
+			.map(|field| seed.deserialize(field.into_deserializer()))
+			.transpose()
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+	where
+		V: DeserializeSeed<'de>,
+	{
+		self.fields.next();
+
+		seed.deserialize(ValueProbe)
+	}
+}
+
+// --------------------------------------------------
+// ValueProbe
+
+// Provides a placeholder value for a field of a primitive type.
+struct ValueProbe;
+
+macro_rules! declare_deserialize_placeholder {
+	($deserialize:ident, $visit:ident, $value:expr) => {
+		fn $deserialize<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+		where
+			V: Visitor<'de>,
+		{
+			visitor.$visit($value)
+		}
+	};
+}
+
+impl<'de> Deserializer<'de> for ValueProbe {
+	type Error = ProbeError;
+
+	fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		Err(ProbeError::UnsupportedType)
+	}
+
+	declare_deserialize_placeholder!(deserialize_bool, visit_bool, false);
+	declare_deserialize_placeholder!(deserialize_i8, visit_i8, 0);
+	declare_deserialize_placeholder!(deserialize_i16, visit_i16, 0);
+	declare_deserialize_placeholder!(deserialize_i32, visit_i32, 0);
+	declare_deserialize_placeholder!(deserialize_i64, visit_i64, 0);
+	declare_deserialize_placeholder!(deserialize_u8, visit_u8, 0);
+	declare_deserialize_placeholder!(deserialize_u16, visit_u16, 0);
+	declare_deserialize_placeholder!(deserialize_u32, visit_u32, 0);
+	declare_deserialize_placeholder!(deserialize_u64, visit_u64, 0);
+	declare_deserialize_placeholder!(deserialize_f32, visit_f32, 0.0);
+	declare_deserialize_placeholder!(deserialize_f64, visit_f64, 0.0);
+	declare_deserialize_placeholder!(deserialize_char, visit_char, '_');
+	declare_deserialize_placeholder!(deserialize_str, visit_str, "");
+	declare_deserialize_placeholder!(deserialize_string, visit_str, "");
+	declare_deserialize_placeholder!(deserialize_bytes, visit_bytes, b"");
+	declare_deserialize_placeholder!(deserialize_byte_buf, visit_bytes, b"");
+
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_none()
+	}
+
+	fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_newtype_struct(self)
+	}
+
+	forward_to_deserialize_any! {
+		i128 u128 unit_struct seq tuple tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+// --------------------------------------------------
+// ProbeError
+
+#[derive(Debug, crate::ImplError)]
+enum ProbeError {
+	#[error("missing field {0}")]
+	MissingField(&'static str),
+	#[error("unsupported type")]
+	UnsupportedType,
+	#[error("{0}")]
+	Message(String),
+}
+
+impl serde::de::Error for ProbeError {
+	fn custom<T: Display>(message: T) -> Self {
+		Self::Message(message.to_string())
+	}
+
+	fn missing_field(field: &'static str) -> Self {
+		Self::MissingField(field)
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+	use serde::Deserialize;
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Deserialize)]
+	#[allow(dead_code)]
+	struct Params {
+		id: u32,
+		some_name: Option<String>,
+		#[serde(default)]
+		page: u32,
+		#[serde(rename = "category")]
+		kind: String,
+	}
+
+	#[derive(Deserialize)]
+	#[allow(dead_code)]
+	struct Wrapper(Params);
+
+	// -------------------------
+
+	#[test]
+	fn expected_params_of() {
+		for expected_params in [
+			ExpectedParams::of::<Params>().unwrap(),
+			ExpectedParams::of::<Wrapper>().unwrap(),
+		] {
+			assert!(expected_params.has("id"));
+			assert!(expected_params.has("some_name"));
+			assert!(expected_params.has("page"));
+			assert!(expected_params.has("category"));
+			assert!(!expected_params.has("kind"));
+
+			assert_eq!(
+				expected_params.required_names().collect::<Vec<_>>(),
+				["id", "category"],
+			);
+		}
+
+		assert!(ExpectedParams::of::<u32>().is_none());
+		assert!(ExpectedParams::of::<(u32, String)>().is_none());
+		assert!(ExpectedParams::of::<std::collections::HashMap<String, String>>().is_none());
+	}
+
+	#[test]
+	fn expected_params_merge() {
+		let expected_params = ExpectedParams::of::<Params>()
+			.unwrap()
+			.merge(ExpectedParams::with_required_names(["page", "tag"]));
+
+		assert_eq!(
+			expected_params.required_names().collect::<Vec<_>>(),
+			["id", "page", "category", "tag"],
+		);
+	}
+}
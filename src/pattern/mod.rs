@@ -4,7 +4,7 @@ use std::{borrow::Cow, fmt::Display, slice, sync::Arc};
 #[cfg(feature = "regex")]
 use std::{iter::Peekable, str::Chars};
 
-use percent_encoding::{percent_encode, AsciiSet, CONTROLS};
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
 
 #[cfg(feature = "regex")]
 use regex::{CaptureLocations, CaptureNames, Regex};
@@ -12,9 +12,16 @@ use regex::{CaptureLocations, CaptureNames, Regex};
 // --------------------------------------------------
 
 mod deserializer;
-pub(crate) use deserializer::{DeserializerError, FromParamsList};
+pub(crate) use deserializer::{DeserializerError, ExpectedParams, FromParamsList};
 
-use crate::common::SCOPE_VALIDITY;
+mod constraint;
+pub(crate) use constraint::RegisteredConstraint;
+pub use constraint::SegmentConstraint;
+pub(crate) use constraint::{
+	add_registered as add_registered_constraint, register as register_constraint, Constraint,
+};
+
+use crate::{common::SCOPE_VALIDITY, resource::UrlForError, router::RouterReloadError};
 
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
@@ -23,13 +30,19 @@ use crate::common::SCOPE_VALIDITY;
 // static:		static, resource
 // regex: {capture_name:pattern}escaped{capture_name}.escaped{capture_name:pattern}
 // wildcard: {name}
+// constrained: {name:@constraint}
+// catch-all: {*name}, {*name:@constraint}, {*name:pattern}
+// subdomain (hosts only): {name}.domain, {name:@constraint}.domain
 
 #[derive(Debug, Clone)]
 pub(crate) enum Pattern {
 	Static(Arc<str>),
 	#[cfg(feature = "regex")]
-	Regex(RegexNames, Regex),
+	Regex(RegexNames, Regex, RegexTemplate),
 	Wildcard(Arc<str>),
+	Constrained(Arc<str>, Constraint),
+	CatchAll(Arc<str>, Option<Constraint>),
+	Subdomain(Arc<str>, Option<Constraint>, Arc<str>),
 }
 
 // ???
@@ -72,18 +85,33 @@ impl Pattern {
 					name: capture_name,
 					some_subpattern,
 				} => {
+					if let Some(capture_name) = capture_name.strip_prefix('*') {
+						return into_catch_all_pattern(capture_name, some_subpattern.as_deref());
+					}
+
 					let Some(subpattern) = some_subpattern else {
 						return Pattern::Wildcard((*capture_name).into());
 					};
 
-					let subpattern = restore_slashes(subpattern.into());
+					if let Some(constraint) = Constraint::named(&subpattern) {
+						return Pattern::Constrained(capture_name.into(), constraint);
+					}
+
+					let regex_subpattern = format!(
+						r"\A(?P<{}>{})\z",
+						capture_name,
+						restore_slashes(subpattern.as_str().into()),
+					);
 
-					let regex_subpattern = format!(r"\A(?P<{}>{})\z", capture_name, subpattern);
 					match Regex::new(&regex_subpattern) {
 						Ok(regex) => {
 							let capture_names = regex.capture_names();
+							let template = RegexTemplate::new(vec![TemplateSegment::Capture(
+								capture_name.into(),
+								Some(subpattern.into()),
+							)]);
 
-							return Pattern::Regex(RegexNames::new(capture_names), regex);
+							return Pattern::Regex(RegexNames::new(capture_names), regex, template);
 						}
 						Err(error) => panic!("{}", error),
 					}
@@ -92,20 +120,26 @@ impl Pattern {
 		}
 
 		let mut regex_pattern = "\\A".to_owned();
+		let mut template_segments = Vec::with_capacity(segments.len());
 
 		let end_index = segments.len() - 1;
 
 		for (index, segment) in segments.into_iter().enumerate() {
 			match segment {
 				Segment::Static(static_pattern) => {
-					let static_pattern = regex::escape(restore_slashes(static_pattern.into()).as_ref());
-					regex_pattern.push_str(&static_pattern);
+					let static_pattern = restore_slashes(static_pattern.into());
+					regex_pattern.push_str(&regex::escape(static_pattern.as_ref()));
+					template_segments.push(TemplateSegment::Static(static_pattern.into()));
 				}
 				Segment::Capturing {
 					name: capture_name,
 					some_subpattern,
 				} => {
-					let subpattern = if let Some(subpattern) = some_subpattern.as_ref() {
+					if capture_name.starts_with('*') {
+						panic!("a catch-all must be the only part of the path segment")
+					}
+
+					let subpattern = if let Some(subpattern) = some_subpattern.as_deref() {
 						restore_slashes(subpattern.into())
 					} else if index == end_index {
 						Cow::Borrowed(".+")
@@ -114,6 +148,10 @@ impl Pattern {
 					};
 
 					regex_pattern.push_str(&format!("(?P<{}>{})", &capture_name, subpattern));
+					template_segments.push(TemplateSegment::Capture(
+						capture_name.into(),
+						some_subpattern.map(Into::into),
+					));
 				}
 			}
 		}
@@ -123,12 +161,34 @@ impl Pattern {
 			Ok(regex) => {
 				let capture_names = regex.capture_names();
 
-				Pattern::Regex(RegexNames::new(capture_names), regex)
+				Pattern::Regex(
+					RegexNames::new(capture_names),
+					regex,
+					RegexTemplate::new(template_segments),
+				)
 			}
 			Err(error) => panic!("{}", error),
 		}
 	}
 
+	// Parses the host pattern without a scheme. The port is ignored, so the host matches
+	// the requests to any port. A capture followed by a static domain, e.g.,
+	// "{tenant}.example.com", becomes a subdomain pattern.
+	pub(crate) fn parse_host(pattern: &str) -> Pattern {
+		let pattern = match pattern.rsplit_once(':') {
+			Some((host, port)) if !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit()) => {
+				host
+			}
+			_ => pattern,
+		};
+
+		if let Some(subdomain_pattern) = into_subdomain_pattern(pattern) {
+			return subdomain_pattern;
+		}
+
+		Pattern::parse(pattern)
+	}
+
 	#[inline(always)]
 	pub(crate) fn is_static(&self) -> bool {
 		if let Pattern::Static(_) = self {
@@ -141,20 +201,33 @@ impl Pattern {
 	#[cfg(feature = "regex")]
 	#[inline(always)]
 	pub(crate) fn is_regex(&self) -> bool {
-		if let Pattern::Regex(_, _) = self {
+		if let Pattern::Regex(..) = self {
 			return true;
 		}
 
 		false
 	}
 
+	// Catch-all patterns are also wildcards.
 	#[inline(always)]
 	pub(crate) fn is_wildcard(&self) -> bool {
-		if let Pattern::Wildcard(_) = self {
-			return true;
+		matches!(self, Pattern::Wildcard(_) | Pattern::CatchAll(..))
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_catch_all(&self) -> bool {
+		matches!(self, Pattern::CatchAll(..))
+	}
+
+	// Returns the text of a static pattern. Static patterns keep the percent-encoded
+	// text, so it can be compared to the request's path segment without decoding.
+	#[inline(always)]
+	pub(crate) fn static_str(&self) -> Option<&Arc<str>> {
+		if let Self::Static(static_pattern) = self {
+			return Some(static_pattern);
 		}
 
-		false
+		None
 	}
 
 	#[inline(always)]
@@ -173,7 +246,7 @@ impl Pattern {
 	#[cfg(feature = "regex")]
 	#[inline]
 	pub(crate) fn is_regex_match(&self, text: &str, params_list: &mut ParamsList) -> Option<bool> {
-		if let Self::Regex(capture_names, regex) = self {
+		if let Self::Regex(capture_names, regex, _) = self {
 			let mut capture_locations = regex.capture_locations();
 			if regex.captures_read(&mut capture_locations, text).is_some() {
 				params_list.push(Params::with_regex_captures(
@@ -191,6 +264,77 @@ impl Pattern {
 		None
 	}
 
+	#[inline(always)]
+	pub(crate) fn is_constrained_match(
+		&self,
+		text: &str,
+		params_list: &mut ParamsList,
+	) -> Option<bool> {
+		if let Self::Constrained(name, constraint) = self {
+			if constraint.matches(text) {
+				params_list.push(Params::with_wildcard_value(name.clone(), text.into()));
+
+				return Some(true);
+			}
+
+			return Some(false);
+		}
+
+		None
+	}
+
+	// Matches the host that has a single label before the pattern's domain.
+	#[inline(always)]
+	pub(crate) fn is_subdomain_match(
+		&self,
+		text: &str,
+		params_list: &mut ParamsList,
+	) -> Option<bool> {
+		if let Self::Subdomain(name, some_constraint, domain) = self {
+			let Some(subdomain) = text
+				.strip_suffix(domain.as_ref())
+				.and_then(|text| text.strip_suffix('.'))
+			else {
+				return Some(false);
+			};
+
+			if subdomain.is_empty()
+				|| subdomain.contains('.')
+				|| some_constraint
+					.as_ref()
+					.is_some_and(|constraint| !constraint.matches(subdomain))
+			{
+				return Some(false);
+			}
+
+			params_list.push(Params::with_wildcard_value(name.clone(), subdomain.into()));
+
+			return Some(true);
+		}
+
+		None
+	}
+
+	// Regex, constrained, and subdomain patterns are kept in the same list and matched
+	// in the order they were added.
+	#[inline(always)]
+	pub(crate) fn is_regex_or_constrained_match(
+		&self,
+		text: &str,
+		params_list: &mut ParamsList,
+	) -> Option<bool> {
+		#[cfg(feature = "regex")]
+		if let Some(match_result) = self.is_regex_match(text, params_list) {
+			return Some(match_result);
+		}
+
+		if let Some(match_result) = self.is_subdomain_match(text, params_list) {
+			return Some(match_result);
+		}
+
+		self.is_constrained_match(text, params_list)
+	}
+
 	#[inline(always)]
 	pub(crate) fn is_wildcard_match(
 		&self,
@@ -206,6 +350,294 @@ impl Pattern {
 		}
 	}
 
+	// Matches the remaining path segments of the request without a trailing slash.
+	#[inline(always)]
+	pub(crate) fn is_catch_all_match(
+		&self,
+		text: Cow<str>,
+		params_list: &mut ParamsList,
+	) -> Option<bool> {
+		if let Self::CatchAll(name, some_constraint) = self {
+			if some_constraint
+				.as_ref()
+				.is_some_and(|constraint| !constraint.matches(&text))
+			{
+				return Some(false);
+			}
+
+			params_list.push(Params::with_wildcard_value(name.clone(), text.into()));
+
+			return Some(true);
+		}
+
+		None
+	}
+
+	// Resolves the pattern's registered constraint with the given constraints.
+	pub(crate) fn resolve_constraint(
+		&self,
+		constraints: &[&RegisteredConstraint],
+	) -> Result<(), RouterReloadError> {
+		match self {
+			Pattern::Constrained(_, constraint)
+			| Pattern::CatchAll(_, Some(constraint))
+			| Pattern::Subdomain(_, Some(constraint), _) => constraint.resolve(constraints),
+			_ => Ok(()),
+		}
+	}
+
+	// Returns the names of the params the pattern captures.
+	pub(crate) fn capture_names(&self) -> Vec<&str> {
+		match self {
+			Pattern::Static(_) => Vec::new(),
+			#[cfg(feature = "regex")]
+			Pattern::Regex(capture_names, ..) => capture_names
+				.as_ref()
+				.iter()
+				.map(|(capture_name, _)| capture_name.as_ref())
+				.collect(),
+			Pattern::Wildcard(name)
+			| Pattern::Constrained(name, _)
+			| Pattern::CatchAll(name, _)
+			| Pattern::Subdomain(name, ..) => vec![name.as_ref()],
+		}
+	}
+
+	// Fills the pattern with the given param values and returns the percent-encoded
+	// path segment.
+	pub(crate) fn fill_path_segment(
+		&self,
+		params: &[(&str, &str)],
+	) -> Result<Cow<'_, str>, UrlForError> {
+		if let Pattern::Static(pattern) = self {
+			return Ok(Cow::Borrowed(pattern.as_ref()));
+		}
+
+		let segment = self.fill(params)?;
+
+		if let Pattern::CatchAll(..) = self {
+			// The value of a catch-all param keeps its slashes.
+			let segments = segment
+				.split('/')
+				.map(|segment| percent_encode(segment.as_bytes(), ASCII_SET).to_string())
+				.collect::<Vec<_>>();
+
+			return Ok(Cow::Owned(segments.join("/")));
+		}
+
+		Ok(Cow::Owned(
+			percent_encode(segment.as_bytes(), ASCII_SET).to_string(),
+		))
+	}
+
+	// Fills the pattern with the given param values and returns the host.
+	pub(crate) fn fill_host(&self, params: &[(&str, &str)]) -> Result<Cow<'_, str>, UrlForError> {
+		if let Pattern::Static(pattern) = self {
+			return Ok(Cow::Borrowed(pattern.as_ref()));
+		}
+
+		// Host values go into the URL's authority without encoding, so they are limited to
+		// the characters of DNS labels. A subdomain value must be a single label.
+		let is_label_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '-';
+		let is_subdomain = matches!(self, Pattern::Subdomain(..));
+
+		for name in self.capture_names() {
+			let Some(value) = params
+				.iter()
+				.find_map(|(param_name, value)| (*param_name == name).then_some(*value))
+			else {
+				continue;
+			};
+
+			if !value
+				.chars()
+				.all(|ch| is_label_char(ch) || (ch == '.' && !is_subdomain))
+			{
+				return Err(UrlForError::InvalidParam(name.to_owned()));
+			}
+		}
+
+		self.fill(params).map(Cow::Owned)
+	}
+
+	// Returns the text that the pattern matches with the given param values. Each value
+	// must match its subpattern.
+	fn fill(&self, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+		let value_of = |name: &str| {
+			params
+				.iter()
+				.find_map(|(param_name, value)| (*param_name == name).then_some(*value))
+				.ok_or_else(|| UrlForError::MissingParam(name.to_owned()))
+		};
+
+		match self {
+			Pattern::Static(pattern) => Ok(pattern.to_string()),
+			#[cfg(feature = "regex")]
+			Pattern::Regex(capture_names, regex, template) => {
+				let mut text = String::new();
+				let mut capture_regexes = template.capture_regexes().iter();
+				for segment in template.as_ref() {
+					match segment {
+						TemplateSegment::Static(static_segment) => text.push_str(static_segment),
+						TemplateSegment::Capture(name, _) => {
+							let value = value_of(name)?;
+							let capture_regex = capture_regexes.next().expect(SCOPE_VALIDITY);
+							if !capture_regex.is_match(value) {
+								return Err(UrlForError::InvalidParam(name.to_string()));
+							}
+
+							text.push_str(value);
+						}
+					}
+				}
+
+				// Each value matches its own subpattern, so the text can only fail to be
+				// captured the same way when the values are ambiguous.
+				let Some(captures) = regex.captures(&text) else {
+					let (first_name, _) = capture_names.get(0).expect(SCOPE_VALIDITY);
+
+					return Err(UrlForError::InvalidParam(first_name.to_owned()));
+				};
+
+				for (name, _) in capture_names.as_ref() {
+					let value = value_of(name)?;
+					if captures.name(name).map(|capture| capture.as_str()) != Some(value) {
+						return Err(UrlForError::InvalidParam(name.to_string()));
+					}
+				}
+
+				Ok(text)
+			}
+			Pattern::Wildcard(name) => {
+				let value = value_of(name)?;
+				if value.is_empty() {
+					return Err(UrlForError::InvalidParam(name.to_string()));
+				}
+
+				Ok(value.to_owned())
+			}
+			Pattern::Constrained(name, constraint) => {
+				let value = value_of(name)?;
+				if !constraint.matches(value) {
+					return Err(UrlForError::InvalidParam(name.to_string()));
+				}
+
+				Ok(value.to_owned())
+			}
+			Pattern::CatchAll(name, some_constraint) => {
+				let value = value_of(name)?;
+				if value.is_empty()
+					|| some_constraint
+						.as_ref()
+						.is_some_and(|constraint| !constraint.matches(value))
+				{
+					return Err(UrlForError::InvalidParam(name.to_string()));
+				}
+
+				Ok(value.to_owned())
+			}
+			Pattern::Subdomain(name, some_constraint, domain) => {
+				let value = value_of(name)?;
+				if value.is_empty()
+					|| value.contains('.')
+					|| some_constraint
+						.as_ref()
+						.is_some_and(|constraint| !constraint.matches(value))
+				{
+					return Err(UrlForError::InvalidParam(name.to_string()));
+				}
+
+				Ok(format!("{}.{}", value, domain))
+			}
+		}
+	}
+
+	// Returns the pattern as it's written in a URI pattern.
+	pub(crate) fn pattern_str(&self) -> Cow<'_, str> {
+		match self {
+			Pattern::Static(pattern) => percent_decode_str(pattern).decode_utf8_lossy(),
+			#[cfg(feature = "regex")]
+			Pattern::Regex(_, _, template) => {
+				let mut pattern = String::new();
+				for segment in template.as_ref() {
+					match segment {
+						TemplateSegment::Static(static_segment) => pattern.push_str(static_segment),
+						TemplateSegment::Capture(name, Some(subpattern)) => {
+							pattern.push_str(&format!("{{{}:{}}}", name, subpattern))
+						}
+						TemplateSegment::Capture(name, None) => pattern.push_str(&format!("{{{}}}", name)),
+					}
+				}
+
+				Cow::Owned(pattern)
+			}
+			Pattern::Wildcard(name) => Cow::Owned(format!("{{{}}}", name)),
+			Pattern::Constrained(name, constraint) => {
+				Cow::Owned(format!("{{{}:{}}}", name, constraint.name()))
+			}
+			Pattern::CatchAll(name, Some(constraint)) => {
+				Cow::Owned(format!("{{*{}:{}}}", name, constraint.name()))
+			}
+			Pattern::CatchAll(name, None) => Cow::Owned(format!("{{*{}}}", name)),
+			Pattern::Subdomain(name, Some(constraint), domain) => {
+				Cow::Owned(format!("{{{}:{}}}.{}", name, constraint.name(), domain))
+			}
+			Pattern::Subdomain(name, None, domain) => Cow::Owned(format!("{{{}}}.{}", name, domain)),
+		}
+	}
+
+	// Returns the pattern with its captures written as `{name}`.
+	pub(crate) fn template_str(&self) -> Cow<'_, str> {
+		match self {
+			Pattern::Static(pattern) => percent_decode_str(pattern).decode_utf8_lossy(),
+			#[cfg(feature = "regex")]
+			Pattern::Regex(_, _, template) => {
+				let mut pattern = String::new();
+				for segment in template.as_ref() {
+					match segment {
+						TemplateSegment::Static(static_segment) => pattern.push_str(static_segment),
+						TemplateSegment::Capture(name, _) => pattern.push_str(&format!("{{{}}}", name)),
+					}
+				}
+
+				Cow::Owned(pattern)
+			}
+			Pattern::Wildcard(name) | Pattern::Constrained(name, _) | Pattern::CatchAll(name, _) => {
+				Cow::Owned(format!("{{{}}}", name))
+			}
+			Pattern::Subdomain(name, _, domain) => Cow::Owned(format!("{{{}}}.{}", name, domain)),
+		}
+	}
+
+	// Returns the names of the params the pattern captures with their regex subpatterns
+	// or constraints as they're written in the pattern, e.g., "@u64".
+	pub(crate) fn capture_subpatterns(&self) -> Vec<(&str, Option<&str>)> {
+		match self {
+			Pattern::Static(_) => Vec::new(),
+			#[cfg(feature = "regex")]
+			Pattern::Regex(_, _, template) => template
+				.as_ref()
+				.iter()
+				.filter_map(|segment| match segment {
+					TemplateSegment::Static(_) => None,
+					TemplateSegment::Capture(name, some_subpattern) => {
+						Some((name.as_ref(), some_subpattern.as_deref()))
+					}
+				})
+				.collect(),
+			Pattern::Constrained(name, constraint)
+			| Pattern::CatchAll(name, Some(constraint))
+			| Pattern::Subdomain(name, Some(constraint), _) => {
+				vec![(name.as_ref(), Some(constraint.name()))]
+			}
+			Pattern::Wildcard(name)
+			| Pattern::CatchAll(name, None)
+			| Pattern::Subdomain(name, None, _) => {
+				vec![(name.as_ref(), None)]
+			}
+		}
+	}
+
 	pub(crate) fn compare(&self, other: &Self) -> Similarity {
 		match self {
 			Pattern::Static(pattern) => {
@@ -216,8 +648,8 @@ impl Pattern {
 				}
 			}
 			#[cfg(feature = "regex")]
-			Pattern::Regex(_capture_names, regex) => {
-				if let Pattern::Regex(_other_capture_names, other_regex) = other {
+			Pattern::Regex(_capture_names, regex, _) => {
+				if let Pattern::Regex(_other_capture_names, other_regex, _) = other {
 					if regex.as_str() == other_regex.as_str() {
 						return Similarity::Same;
 					}
@@ -234,6 +666,34 @@ impl Pattern {
 					return Similarity::DifferentName;
 				}
 			}
+			Pattern::Constrained(name, constraint) => {
+				if let Pattern::Constrained(other_name, other_constraint) = other {
+					if name == other_name && constraint.name() == other_constraint.name() {
+						return Similarity::Same;
+					}
+				}
+			}
+			Pattern::CatchAll(name, some_constraint) => {
+				if let Pattern::CatchAll(other_name, some_other_constraint) = other {
+					if name == other_name
+						&& some_constraint.as_ref().map(Constraint::name)
+							== some_other_constraint.as_ref().map(Constraint::name)
+					{
+						return Similarity::Same;
+					}
+				}
+			}
+			Pattern::Subdomain(name, some_constraint, domain) => {
+				if let Pattern::Subdomain(other_name, some_other_constraint, other_domain) = other {
+					if name == other_name
+						&& domain == other_domain
+						&& some_constraint.as_ref().map(Constraint::name)
+							== some_other_constraint.as_ref().map(Constraint::name)
+					{
+						return Similarity::Same;
+					}
+				}
+			}
 		}
 
 		Similarity::Different
@@ -247,8 +707,27 @@ impl Display for Pattern {
 		match self {
 			Pattern::Static(pattern) => write!(f, "static pattern: {}", pattern),
 			#[cfg(feature = "regex")]
-			Pattern::Regex(_, regex) => write!(f, "regex pattern: {}", regex),
+			Pattern::Regex(_, regex, _) => write!(f, "regex pattern: {}", regex),
 			Pattern::Wildcard(name) => write!(f, "wildcard pattern: {}", name),
+			Pattern::Constrained(name, constraint) => {
+				write!(f, "constrained pattern: {}:{}", name, constraint.name())
+			}
+			Pattern::CatchAll(name, Some(constraint)) => {
+				write!(f, "catch-all pattern: {}:{}", name, constraint.name())
+			}
+			Pattern::CatchAll(name, None) => write!(f, "catch-all pattern: {}", name),
+			Pattern::Subdomain(name, Some(constraint), domain) => {
+				write!(
+					f,
+					"subdomain pattern: {}:{}.{}",
+					name,
+					constraint.name(),
+					domain
+				)
+			}
+			Pattern::Subdomain(name, None, domain) => {
+				write!(f, "subdomain pattern: {}.{}", name, domain)
+			}
 		}
 	}
 }
@@ -307,6 +786,60 @@ impl AsRef<[(Box<str>, usize)]> for RegexNames {
 	}
 }
 
+// The static and capturing segments of the regex pattern in their original order, with
+// the regexes of each capture's subpattern.
+#[cfg(feature = "regex")]
+#[derive(Debug, Default, Clone)]
+pub(crate) struct RegexTemplate(Arc<[TemplateSegment]>, Arc<[Regex]>);
+
+#[cfg(feature = "regex")]
+impl RegexTemplate {
+	fn new(segments: Vec<TemplateSegment>) -> Self {
+		let end_index = segments.len() - 1;
+
+		let capture_regexes = segments
+			.iter()
+			.enumerate()
+			.filter_map(|(index, segment)| match segment {
+				TemplateSegment::Static(_) => None,
+				TemplateSegment::Capture(_, some_subpattern) => {
+					// The same subpatterns as in the pattern's regex.
+					let subpattern = if let Some(subpattern) = some_subpattern.as_deref() {
+						restore_slashes(subpattern.into())
+					} else if index == end_index {
+						Cow::Borrowed(".+")
+					} else {
+						Cow::Borrowed("[^.]+")
+					};
+
+					Some(Regex::new(&format!(r"\A(?:{})\z", subpattern)).expect(SCOPE_VALIDITY))
+				}
+			})
+			.collect::<Vec<_>>();
+
+		Self(Arc::from(segments), Arc::from(capture_regexes))
+	}
+
+	#[inline]
+	fn capture_regexes(&self) -> &[Regex] {
+		&self.1
+	}
+}
+
+#[cfg(feature = "regex")]
+impl AsRef<[TemplateSegment]> for RegexTemplate {
+	fn as_ref(&self) -> &[TemplateSegment] {
+		&self.0
+	}
+}
+
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub(crate) enum TemplateSegment {
+	Static(Box<str>),
+	Capture(Box<str>, Option<Box<str>>),
+}
+
 // --------------------------------------------------
 
 #[derive(Debug, Default, Clone)]
@@ -337,6 +870,12 @@ impl ParamsList {
 	pub(crate) fn deserializer(&self) -> FromParamsList<'_> {
 		FromParamsList::new(&self.0)
 	}
+
+	// Returns the value of the param with the given name. If several params have the name,
+	// the last one's value is returned, as when the params are deserialized into a map.
+	pub(crate) fn value_of(&self, name: &str) -> Option<&str> {
+		self.iter().rev().find_map(|params| params.value_of(name))
+	}
 }
 
 // --------------------------------------------------
@@ -363,6 +902,22 @@ impl Params {
 	fn with_wildcard_value(name: Arc<str>, value: Box<str>) -> Self {
 		Self::Wildcard(name, value)
 	}
+
+	fn value_of(&self, name: &str) -> Option<&str> {
+		match self {
+			#[cfg(feature = "regex")]
+			Self::Regex(regex_names, capture_locations, values) => regex_names
+				.as_ref()
+				.iter()
+				.rev()
+				.find(|(capture_name, _)| capture_name.as_ref() == name)
+				.and_then(|(_, index)| capture_locations.get(*index))
+				.map(|(start, end)| &values[start..end]),
+			Self::Wildcard(capture_name, value) => {
+				(capture_name.as_ref() == name).then_some(value.as_ref())
+			}
+		}
+	}
 }
 
 impl Display for Params {
@@ -500,7 +1055,7 @@ fn into_static_or_wildcard_pattern(pattern: Cow<str>) -> Pattern {
 			let mut pattern_bytes = pattern.bytes();
 			if let Some(ch) = pattern_bytes.next() {
 				if ch != b'{' {
-					return Pattern::Wildcard(pattern.into());
+					return into_wildcard_or_constrained_pattern(pattern);
 				}
 
 				let ch = pattern_bytes.last().expect(SCOPE_VALIDITY);
@@ -508,7 +1063,7 @@ fn into_static_or_wildcard_pattern(pattern: Cow<str>) -> Pattern {
 					// Static pattern with escaped curly braces.
 					return Pattern::Static(restore_slashes(pattern.into()).into());
 				} else {
-					return Pattern::Wildcard(pattern.into());
+					return into_wildcard_or_constrained_pattern(pattern);
 				}
 			} else {
 				panic!("empty wildcard capture name")
@@ -519,6 +1074,89 @@ fn into_static_or_wildcard_pattern(pattern: Cow<str>) -> Pattern {
 	Pattern::Static(restore_slashes(pattern).into())
 }
 
+#[cfg(not(feature = "regex"))]
+fn into_wildcard_or_constrained_pattern(pattern: &str) -> Pattern {
+	if let Some(pattern) = pattern.strip_prefix('*') {
+		let (name, some_subpattern) = match pattern.split_once(':') {
+			Some((name, subpattern)) => (name, Some(subpattern)),
+			None => (pattern, None),
+		};
+
+		return into_catch_all_pattern(name, some_subpattern);
+	}
+
+	let Some((name, constraint_name)) = pattern.split_once(':') else {
+		return Pattern::Wildcard(pattern.into());
+	};
+
+	if name.is_empty() {
+		panic!("empty capture name")
+	}
+
+	let Some(constraint) = Constraint::named(constraint_name) else {
+		panic!(
+			"segment constraint '{}' must start with '@'",
+			constraint_name
+		)
+	};
+
+	Pattern::Constrained(name.into(), constraint)
+}
+
+// Returns a subdomain pattern if the host pattern is a single capture followed by a static
+// domain. A capture with a subpattern that isn't a named constraint is left to be parsed
+// as any other pattern.
+fn into_subdomain_pattern(pattern: &str) -> Option<Pattern> {
+	let (capture, domain) = pattern.strip_prefix('{')?.split_once("}.")?;
+
+	if capture.is_empty()
+		|| capture.starts_with(['{', '*'])
+		|| domain.is_empty()
+		|| domain.contains(['{', '}'])
+	{
+		return None;
+	}
+
+	let Some((name, constraint_name)) = capture.split_once(':') else {
+		return Some(Pattern::Subdomain(capture.into(), None, domain.into()));
+	};
+
+	if name.is_empty() {
+		panic!("empty capture name")
+	}
+
+	let constraint = Constraint::named(constraint_name)?;
+
+	Some(Pattern::Subdomain(
+		name.into(),
+		Some(constraint),
+		domain.into(),
+	))
+}
+
+fn into_catch_all_pattern(name: &str, some_subpattern: Option<&str>) -> Pattern {
+	if name.is_empty() {
+		panic!("empty capture name")
+	}
+
+	let Some(subpattern) = some_subpattern else {
+		return Pattern::CatchAll(name.into(), None);
+	};
+
+	if let Some(constraint) = Constraint::named(subpattern) {
+		return Pattern::CatchAll(name.into(), Some(constraint));
+	}
+
+	#[cfg(feature = "regex")]
+	return Pattern::CatchAll(
+		name.into(),
+		Some(Constraint::regex(&restore_slashes(subpattern.into()))),
+	);
+
+	#[cfg(not(feature = "regex"))]
+	panic!("segment constraint '{}' must start with '@'", subpattern)
+}
+
 // --------------------------------------------------
 
 #[cfg(feature = "regex")]
@@ -1093,6 +1731,7 @@ mod test {
 							.capture_names(),
 					),
 					Regex::new(r"\A(?P<capture_name>pattern)\z").unwrap(),
+					RegexTemplate::default(),
 				),
 			),
 			(
@@ -1104,6 +1743,7 @@ mod test {
 							.capture_names(),
 					),
 					Regex::new(r"\Astatic(?P<capture_name>pattern)\.static\{not_capture_name\}\z").unwrap(),
+					RegexTemplate::default(),
 				),
 			),
 			(
@@ -1115,6 +1755,7 @@ mod test {
 							.capture_names(),
 					),
 					Regex::new(r"\Astatic(?P<capture_name_1>[^.]+)\.static(?P<capture_name_2>.+)\z").unwrap(),
+					RegexTemplate::default(),
 				),
 			),
 			(
@@ -1126,6 +1767,7 @@ mod test {
 							.capture_names(),
 					),
 					Regex::new(r"\A\{not_capture_name:pattern\}(?P<capture_name>[^.]+)\.\{\z").unwrap(),
+					RegexTemplate::default(),
 				),
 			),
 			("{capture_name}", Pattern::Wildcard("capture_name".into())),
@@ -1294,4 +1936,72 @@ mod test {
 			}
 		}
 	}
+
+	#[test]
+	fn parse_host() {
+		let cases = [
+			("example.com", "example.com"),
+			("example.com:8080", "example.com"),
+			("{tenant}.example.com", "{tenant}.example.com"),
+			("{tenant}.example.com:8080", "{tenant}.example.com"),
+			("{id:@u64}.example.com", "{id:@u64}.example.com"),
+		];
+
+		for (host_pattern, expected_pattern) in cases {
+			let pattern = Pattern::parse_host(host_pattern);
+			assert_eq!(pattern.pattern_str(), expected_pattern, "{}", host_pattern);
+		}
+
+		assert!(matches!(
+			Pattern::parse_host("{tenant}.example.com"),
+			Pattern::Subdomain(..)
+		));
+
+		assert!(matches!(
+			Pattern::parse_host("{tenant:[a-z]+}.example.com"),
+			Pattern::Regex(..)
+		));
+
+		assert!(matches!(
+			Pattern::parse_host("{tenant}.{domain}.com"),
+			Pattern::Regex(..)
+		));
+	}
+
+	#[test]
+	fn is_subdomain_match() {
+		let pattern = Pattern::parse_host("{tenant}.example.com");
+
+		let mut params_list = ParamsList::new();
+		assert!(pattern
+			.is_regex_or_constrained_match("acme.example.com", &mut params_list)
+			.unwrap());
+
+		let params = params_list.iter().next().unwrap();
+		assert_eq!(params.to_string(), "wildcard param: [tenant:acme]");
+
+		let mut params_list = ParamsList::new();
+		for host in [
+			"example.com",
+			".example.com",
+			"a.b.example.com",
+			"acmeexample.com",
+			"acme.example.org",
+		] {
+			assert!(!pattern
+				.is_regex_or_constrained_match(host, &mut params_list)
+				.unwrap());
+		}
+
+		assert!(params_list.iter().next().is_none());
+
+		let pattern = Pattern::parse_host("{id:@u64}.example.com");
+		assert!(pattern
+			.is_subdomain_match("42.example.com", &mut params_list)
+			.unwrap());
+
+		assert!(!pattern
+			.is_subdomain_match("acme.example.com", &mut params_list)
+			.unwrap());
+	}
 }
@@ -12,8 +12,8 @@ pub use crate::{
 		RequestHandler, RequestPasser, RequestReceiver,
 	},
 	request::{
-		ExtractorGuard, FromRequest, MistargetedRequest, PathParamsError, Request, RequestContext,
-		RequestHead, RequestHeadParts,
+		ExtractorGuard, FromRequest, MistargetedRequest, PathParams, PathParamsError, Request,
+		RequestContext, RequestHead, RequestHeadParts,
 	},
 	response::{
 		BoxedErrorResponse, ErrorResponse, Html, IntoResponse, IntoResponseHeadParts,
@@ -24,4 +24,4 @@ pub use crate::{
 };
 
 #[cfg(feature = "query-params")]
-pub use crate::request::QueryParamsError;
+pub use crate::request::{QueryParams, QueryParamsError};
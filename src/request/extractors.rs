@@ -0,0 +1,428 @@
+use std::{
+	convert::Infallible,
+	future::{ready, Future},
+};
+
+#[cfg(feature = "peer-addr")]
+use std::net::SocketAddr;
+
+use http::{
+	header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT},
+	HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, Version,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+	handler::Args,
+	pattern::ExpectedParams,
+	response::{IntoResponse, Response},
+};
+
+use super::{ExtractorGuard, PathParamsError, RequestContext};
+
+#[cfg(feature = "query-params")]
+use super::QueryParamsError;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// PathParams
+
+/// An extractor guard that deserializes the path params as type `T`.
+///
+/// ```
+/// use argan::{Resource, http::Method, handler::HandlerSetter, request::PathParams};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///   id: u32,
+/// }
+///
+/// async fn user(PathParams(user): PathParams<User>) {
+///   // ...
+/// }
+///
+/// let mut resource = Resource::new("/users/{id}");
+/// resource.set_handler_for(Method::GET.to(user));
+/// ```
+#[derive(Debug)]
+pub struct PathParams<T>(pub T);
+
+impl<B, Ext, T> ExtractorGuard<B, Ext> for PathParams<T>
+where
+	Ext: Clone,
+	T: DeserializeOwned + Send,
+{
+	type Error = PathParamsError;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		ready(request_context.path_params_as::<T>().map(Self))
+	}
+
+	#[allow(private_interfaces)]
+	fn expected_path_params() -> Option<ExpectedParams> {
+		ExpectedParams::of::<T>()
+	}
+}
+
+// --------------------------------------------------
+// QueryParams
+
+/// An extractor guard that deserializes the query params as type `T`.
+///
+/// ```
+/// use argan::{Resource, http::Method, handler::HandlerSetter, request::QueryParams};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Pagination {
+///   page: u32,
+///   per_page: Option<u32>,
+/// }
+///
+/// async fn list(QueryParams(pagination): QueryParams<Pagination>) {
+///   // ...
+/// }
+///
+/// let mut resource = Resource::new("/items");
+/// resource.set_handler_for(Method::GET.to(list));
+/// ```
+#[cfg(feature = "query-params")]
+#[derive(Debug)]
+pub struct QueryParams<T>(pub T);
+
+#[cfg(feature = "query-params")]
+impl<B, Ext, T> ExtractorGuard<B, Ext> for QueryParams<T>
+where
+	Ext: Clone,
+	T: DeserializeOwned + Send,
+{
+	type Error = QueryParamsError;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		ready(request_context.query_params_as::<T>().map(Self))
+	}
+}
+
+// --------------------------------------------------
+// PeerAddr
+
+/// An extractor guard that provides the peer address.
+#[cfg(feature = "peer-addr")]
+#[derive(Debug, Clone, Copy)]
+pub struct PeerAddr(pub SocketAddr);
+
+#[cfg(feature = "peer-addr")]
+impl<B, Ext> ExtractorGuard<B, Ext> for PeerAddr
+where
+	Ext: Clone,
+{
+	type Error = Infallible;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		ready(Ok(Self(*request_context.peer_addr())))
+	}
+}
+
+// --------------------------------------------------
+// Method, Uri, Version, HeaderMap
+
+macro_rules! impl_extractor_guard_for_head_part {
+	($type:ty, $getter:ident) => {
+		impl<B, Ext> ExtractorGuard<B, Ext> for $type
+		where
+			Ext: Clone,
+		{
+			type Error = Infallible;
+
+			fn from_request_context_and_args(
+				request_context: &mut RequestContext<B>,
+				_args: &Args<'static, Ext>,
+			) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+				ready(Ok(request_context.$getter().clone()))
+			}
+		}
+	};
+}
+
+impl_extractor_guard_for_head_part!(Method, method_ref);
+impl_extractor_guard_for_head_part!(Uri, uri_ref);
+impl_extractor_guard_for_head_part!(HeaderMap<HeaderValue>, headers_ref);
+
+impl<B, Ext> ExtractorGuard<B, Ext> for Version
+where
+	Ext: Clone,
+{
+	type Error = Infallible;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		ready(Ok(request_context.version()))
+	}
+}
+
+// --------------------------------------------------
+// TypedHeader
+
+/// A trait for types that can be decoded from a single header value.
+pub trait TypedHeader: Sized {
+	/// Returns the name of the header.
+	fn name() -> HeaderName;
+
+	/// Decodes the header value.
+	fn from_header_value(header_value: &HeaderValue) -> Option<Self>;
+}
+
+// ----------
+
+/// An extractor guard for the types that implement the [`TypedHeader`] trait.
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   http::Method,
+///   handler::HandlerSetter,
+///   request::{ContentType, Header, UserAgent},
+/// };
+///
+/// async fn handler(
+///   Header(ContentType(content_type)): Header<ContentType>,
+///   some_user_agent: Option<Header<UserAgent>>,
+/// ) {
+///   // ...
+/// }
+///
+/// let mut resource = Resource::new("/");
+/// resource.set_handler_for(Method::POST.to(handler));
+/// ```
+#[derive(Debug)]
+pub struct Header<H>(pub H);
+
+impl<B, Ext, H> ExtractorGuard<B, Ext> for Header<H>
+where
+	Ext: Clone,
+	H: TypedHeader + Send,
+{
+	type Error = HeaderError;
+
+	fn from_request_context_and_args(
+		request_context: &mut RequestContext<B>,
+		_args: &Args<'static, Ext>,
+	) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+		let name = H::name();
+
+		let result = match request_context.headers_ref().get(&name) {
+			Some(header_value) => H::from_header_value(header_value)
+				.map(Self)
+				.ok_or(HeaderError::InvalidValue(name)),
+			None => Err(HeaderError::Missing(name)),
+		};
+
+		ready(result)
+	}
+}
+
+// ----------
+
+/// An error type that's returned on failure when extracting a typed header.
+#[derive(Debug, crate::ImplError)]
+pub enum HeaderError {
+	/// Returned when the request doesn't have the header.
+	#[error("missing {0} header")]
+	Missing(HeaderName),
+	/// Returned when the header value cannot be decoded.
+	#[error("invalid {0} header value")]
+	InvalidValue(HeaderName),
+}
+
+impl IntoResponse for HeaderError {
+	fn into_response(self) -> Response {
+		StatusCode::BAD_REQUEST.into_response()
+	}
+}
+
+// ----------
+
+/// A typed `Content-Type` header.
+#[derive(Debug, Clone)]
+pub struct ContentType(pub mime::Mime);
+
+impl TypedHeader for ContentType {
+	fn name() -> HeaderName {
+		CONTENT_TYPE
+	}
+
+	fn from_header_value(header_value: &HeaderValue) -> Option<Self> {
+		header_value
+			.to_str()
+			.ok()
+			.and_then(|value| value.parse().ok())
+			.map(Self)
+	}
+}
+
+/// A typed `Content-Length` header.
+#[derive(Debug, Clone, Copy)]
+pub struct ContentLength(pub u64);
+
+impl TypedHeader for ContentLength {
+	fn name() -> HeaderName {
+		CONTENT_LENGTH
+	}
+
+	fn from_header_value(header_value: &HeaderValue) -> Option<Self> {
+		header_value
+			.to_str()
+			.ok()
+			.and_then(|value| value.trim().parse().ok())
+			.map(Self)
+	}
+}
+
+/// A typed `User-Agent` header.
+#[derive(Debug, Clone)]
+pub struct UserAgent(pub String);
+
+impl TypedHeader for UserAgent {
+	fn name() -> HeaderName {
+		USER_AGENT
+	}
+
+	fn from_header_value(header_value: &HeaderValue) -> Option<Self> {
+		header_value
+			.to_str()
+			.ok()
+			.map(|value| Self(value.to_owned()))
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use bytes::Bytes;
+	use http::header::COOKIE;
+	use http_body_util::{BodyExt, Empty};
+	use hyper::service::Service;
+	use serde::Deserialize;
+
+	use crate::{
+		data::{cookies::CookieJar, json::Json},
+		handler::HandlerSetter,
+		request::Request,
+		resource::Resource,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Deserialize)]
+	struct Item {
+		category: String,
+		id: u32,
+	}
+
+	#[derive(Deserialize)]
+	struct Pagination {
+		page: u32,
+	}
+
+	#[derive(Deserialize)]
+	struct Data {
+		name: String,
+	}
+
+	// -------------------------
+
+	#[tokio::test]
+	async fn head_extractor_guards() {
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/{category}/{id}")
+			.set_handler_for(Method::POST.to(
+				|PathParams(item): PathParams<Item>,
+				 QueryParams(pagination): QueryParams<Pagination>,
+				 cookies: CookieJar,
+				 PeerAddr(peer_addr): PeerAddr,
+				 Header(ContentType(content_type)): Header<ContentType>,
+				 some_user_agent: Option<Header<UserAgent>>,
+				 Json(data): Json<Data>| async move {
+					format!(
+						"{} {} {} {} {} {} {} {}",
+						item.category,
+						item.id,
+						pagination.page,
+						cookies.plain_cookie("key").unwrap().value(),
+						peer_addr,
+						content_type,
+						some_user_agent.is_some(),
+						data.name,
+					)
+				},
+			));
+
+		let service = root.into_service();
+
+		// ----------
+
+		let request = Request::post("/books/42?page=3")
+			.header(COOKIE, "key=value")
+			.header(CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+			.body(r#"{"name": "argan"}"#.to_string())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let body = response.collect().await.unwrap().to_bytes();
+		assert_eq!(
+			body,
+			"books 42 3 value 0.0.0.0:0 application/json false argan"
+		);
+
+		// ----------
+
+		let request = Request::post("/books/id?page=3")
+			.header(CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+			.body(r#"{"name": "argan"}"#.to_string())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+		// ----------
+
+		let request = Request::post("/books/42?page=three")
+			.header(CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+			.body(r#"{"name": "argan"}"#.to_string())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+		// ----------
+
+		let request = Request::post("/books/42?page=3")
+			.body(Empty::<Bytes>::new())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	}
+}
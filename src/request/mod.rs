@@ -2,7 +2,7 @@
 
 // ----------
 
-use std::{convert::Infallible, fmt::Debug, future::Future};
+use std::{convert::Infallible, fmt::Debug, future::Future, sync::Arc};
 
 #[cfg(feature = "peer-addr")]
 use std::net::SocketAddr;
@@ -18,7 +18,8 @@ use serde::Deserialize;
 use crate::{
 	common::header_utils::{host_header_value, HostHeaderError},
 	handler::Args,
-	pattern::{self, ParamsList},
+	pattern::{self, ExpectedParams, ParamsList},
+	resource::{NamedRoutes, UrlForError},
 	response::{BoxedErrorResponse, IntoResponse, Response},
 };
 
@@ -32,7 +33,13 @@ pub use argan_core::request::*;
 // ----------
 
 pub(crate) mod routing;
-use routing::RoutingState;
+use routing::{CanonicalSegment, RoutingState};
+
+mod extractors;
+pub use extractors::*;
+
+#[cfg(feature = "query-params")]
+pub mod query;
 
 #[cfg(feature = "websockets")]
 pub mod websocket;
@@ -190,6 +197,19 @@ impl<B> RequestContext<B> {
 			.remaining_segments(self.request.uri().path())
 	}
 
+	/// Returns the URL of the resource with the given `name`, filling the params captured
+	/// by its route with the given values.
+	///
+	/// Each value must match the pattern of its param. Path values are percent-encoded. If
+	/// the resource belongs to a host, the URL starts with `//` followed by the host. Host
+	/// values may contain only ASCII letters, digits, `-` and, except for a subdomain, `.`.
+	///
+	/// See [`Resource::set_name()`](crate::Resource::set_name).
+	#[inline]
+	pub fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+		self.properties.url_for(name, params)
+	}
+
 	/// Consumes the `RequestContext`, returning the request's head and body.
 	pub async fn into_head_and_body(self) -> (RequestHead, B)
 	where
@@ -277,6 +297,12 @@ impl<B> RequestContext<B> {
 			.ends_with_slash(self.request.uri().path())
 	}
 
+	// Returns the value of the path param with the given name.
+	#[inline(always)]
+	pub(crate) fn path_param_value(&self, name: &str) -> Option<&str> {
+		self.routing_state.uri_params.value_of(name)
+	}
+
 	#[inline(always)]
 	pub(crate) fn routing_has_remaining_segments(&self) -> bool {
 		self
@@ -311,6 +337,25 @@ impl<B> RequestContext<B> {
 		Some((next_segment, &mut self.routing_state.uri_params))
 	}
 
+	// Returns the path segments starting from the segment at the given index, without
+	// a trailing slash, and marks them all as routed.
+	#[inline(always)]
+	pub(crate) fn routing_catch_all_segments_and_uri_params_mut(
+		&mut self,
+		segment_index: usize,
+	) -> (&str, &mut ParamsList) {
+		let path = self.request.uri().path();
+		self
+			.routing_state
+			.route_traversal
+			.revert_to_segment(path.len());
+
+		let segments = &path[segment_index..];
+		let segments = segments.strip_suffix('/').unwrap_or(segments);
+
+		(segments, &mut self.routing_state.uri_params)
+	}
+
 	#[inline(always)]
 	pub(crate) fn routing_revert_to_segment(&mut self, segment_index: usize) {
 		self
@@ -329,6 +374,28 @@ impl<B> RequestContext<B> {
 		self.routing_state.subtree_handler_exists
 	}
 
+	#[inline(always)]
+	pub(crate) fn note_canonical_segment(&mut self, canonical_segment: CanonicalSegment) {
+		self
+			.routing_state
+			.canonical_segments
+			.push(canonical_segment);
+	}
+
+	#[inline(always)]
+	pub(crate) fn noted_canonical_segments(&self) -> &[CanonicalSegment] {
+		&self.routing_state.canonical_segments
+	}
+
+	// Forgets the canonical segments starting from the segment at the given index.
+	#[inline(always)]
+	pub(crate) fn forget_canonical_segments_from(&mut self, segment_index: usize) {
+		self
+			.routing_state
+			.canonical_segments
+			.retain(|(index, ..)| *index < segment_index);
+	}
+
 	pub(crate) fn into_request(self) -> Request<B> {
 		self.request
 	}
@@ -377,6 +444,13 @@ pub trait ExtractorGuard<B = Body, Ext: Clone = ()>: Sized {
 		request_context: &mut RequestContext<B>,
 		args: &Args<'static, Ext>,
 	) -> impl Future<Output = Result<Self, Self::Error>> + Send;
+
+	// Names of the path params the guard expects to be captured by the resource's route.
+	#[doc(hidden)]
+	#[allow(private_interfaces)]
+	fn expected_path_params() -> Option<ExpectedParams> {
+		None
+	}
 }
 
 // ----------
@@ -585,6 +659,19 @@ impl RequestHead {
 			.route_traversal
 			.remaining_segments(self.uri.path())
 	}
+
+	/// Returns the URL of the resource with the given `name`, filling the params captured
+	/// by its route with the given values.
+	///
+	/// Each value must match the pattern of its param. Path values are percent-encoded. If
+	/// the resource belongs to a host, the URL starts with `//` followed by the host. Host
+	/// values may contain only ASCII letters, digits, `-` and, except for a subdomain, `.`.
+	///
+	/// See [`Resource::set_name()`](crate::Resource::set_name).
+	#[inline]
+	pub fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+		self.request_context_properties.url_for(name, params)
+	}
 }
 
 // --------------------------------------------------
@@ -594,6 +681,7 @@ impl RequestHead {
 pub(crate) struct RequestContextProperties {
 	#[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 	some_cookie_key: Option<cookie::Key>,
+	some_named_routes: Option<Arc<NamedRoutes>>,
 }
 
 impl RequestContextProperties {
@@ -609,7 +697,19 @@ impl RequestContextProperties {
 		self.some_cookie_key.clone()
 	}
 
-	#[allow(unused_variables)]
+	#[inline]
+	pub(crate) fn set_named_routes(&mut self, named_routes: Arc<NamedRoutes>) {
+		self.some_named_routes = Some(named_routes);
+	}
+
+	#[inline]
+	pub(crate) fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+		match self.some_named_routes.as_deref() {
+			Some(named_routes) => named_routes.url_for(name, params),
+			None => Err(UrlForError::UnknownName(name.to_owned())),
+		}
+	}
+
 	pub(crate) fn clone_valid_properties_from(&mut self, context_properties: &Self) {
 		#[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 		if context_properties.some_cookie_key.is_some() {
@@ -617,6 +717,12 @@ impl RequestContextProperties {
 				.some_cookie_key
 				.clone_from(&context_properties.some_cookie_key);
 		}
+
+		if context_properties.some_named_routes.is_some() {
+			self
+				.some_named_routes
+				.clone_from(&context_properties.some_named_routes);
+		}
 	}
 }
 
@@ -633,6 +739,16 @@ impl RequestContextProperties {
 /// ```
 pub struct MistargetedRequest;
 
+// --------------------------------------------------
+// OriginalUri
+
+/// The request's URI before the path of the resource was stripped from it.
+///
+/// Inserted into the request's extensions before the request is passed to the service
+/// mounted with [`Resource::mount_service()`](crate::Resource::mount_service).
+#[derive(Debug, Clone)]
+pub struct OriginalUri(pub Uri);
+
 // --------------------------------------------------
 // SizeLimit
 
@@ -668,6 +784,7 @@ impl IntoResponse for PathParamsError {
 ///
 /// See [`RequestContext::query_params_as()`] and [`RequestHead::query_params_as()`].
 #[cfg(feature = "query-params")]
+#[non_exhaustive]
 #[derive(Debug, crate::ImplError)]
 pub enum QueryParamsError {
 	/// Returned when a request doesn't have query params.
@@ -676,6 +793,14 @@ pub enum QueryParamsError {
 	/// Returned when the deserialization of the query params fails.
 	#[error(transparent)]
 	InvalidData(#[from] serde_urlencoded::de::Error),
+	/// Returned by the [`Query`](query::Query) extractor when the param with the `key`
+	/// fails to deserialize.
+	#[error("invalid query param '{key}': {message}")]
+	InvalidParam { key: String, message: String },
+	/// Returned by the [`Query`](query::Query) extractor when the param with the `key`
+	/// has more nested segments than allowed.
+	#[error("query param '{key}' is nested too deeply")]
+	TooDeeplyNested { key: String },
 }
 
 #[cfg(feature = "query-params")]
@@ -0,0 +1,997 @@
+//! Query params extractor and its parsing styles.
+
+// ----------
+
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	fmt::Display,
+	future::{ready, Future},
+};
+
+use serde::{
+	de::{
+		value::Error as ValueError, DeserializeOwned, DeserializeSeed, Error as _, IntoDeserializer,
+		MapAccess, SeqAccess, Visitor,
+	},
+	forward_to_deserialize_any, Deserializer,
+};
+
+use crate::{common::marker::Sealed, handler::Args};
+
+use super::{ExtractorGuard, QueryParamsError, RequestContext};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// Query
+
+/// An extractor guard that deserializes the query params as type `T` with the parsing
+/// style `S`.
+///
+/// The parsing style is one of [`Flat`], [`RepeatedKeys`], and [`Nested`]. When the request
+/// doesn't have a query string, `T` is deserialized from empty params.
+///
+/// ```
+/// use argan::{
+///   Resource,
+///   http::Method,
+///   handler::HandlerSetter,
+///   request::query::{Query, Nested, RepeatedKeys},
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Tags {
+///   tag: Vec<String>,
+/// }
+///
+/// // ?tag=a&tag=b
+/// async fn tagged(Query(tags): Query<Tags, RepeatedKeys>) {
+///   // ...
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Filter {
+///   status: String,
+///   labels: Option<Vec<String>>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Search {
+///   filter: Filter,
+///   page: Option<u32>,
+/// }
+///
+/// // ?filter[status]=open&filter[labels][]=bug&filter[labels][]=ui&page=2
+/// async fn search(Query(search): Query<Search, Nested>) {
+///   // ...
+/// }
+///
+/// let mut root = Resource::new("/");
+/// root.subresource_mut("/items").set_handler_for(Method::GET.to(tagged));
+/// root.subresource_mut("/search").set_handler_for(Method::GET.to(search));
+/// ```
+#[derive(Debug)]
+pub struct Query<T, S: QueryStyle = Flat>(pub S::Value<T>);
+
+// The style is known in each implementation, so the extracted value's type is `T`.
+macro_rules! impl_extractor_guard_for_query {
+	($($style:ident),*) => {
+		$(
+			impl<B, Ext, T> ExtractorGuard<B, Ext> for Query<T, $style>
+			where
+				Ext: Clone,
+				T: DeserializeOwned + Send,
+			{
+				type Error = QueryParamsError;
+
+				fn from_request_context_and_args(
+					request_context: &mut RequestContext<B>,
+					_args: &Args<'static, Ext>,
+				) -> impl Future<Output = Result<Self, Self::Error>> + Send {
+					let query_string = request_context.uri_ref().query().unwrap_or("");
+
+					ready(deserialize_query::<T>(query_string, $style::KIND).map(Self))
+				}
+			}
+		)*
+	};
+}
+
+impl_extractor_guard_for_query!(Flat, RepeatedKeys, Nested);
+
+// --------------------------------------------------
+// QueryStyle
+
+/// A trait for the parsing styles of the [`Query`] extractor.
+pub trait QueryStyle: Sealed {
+	/// The type of the extracted value. It's always `T`; the style only appears in
+	/// the type so the extractor destructures as `Query(value)`.
+	type Value<T>;
+
+	#[doc(hidden)]
+	const KIND: StyleKind;
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StyleKind {
+	Flat,
+	RepeatedKeys,
+	Nested,
+}
+
+// ----------
+
+/// Each key is taken verbatim and must appear at most once (`?page=2&per_page=10`).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Flat;
+
+impl Sealed for Flat {}
+
+impl QueryStyle for Flat {
+	type Value<T> = T;
+
+	const KIND: StyleKind = StyleKind::Flat;
+}
+
+/// The values of the repeated keys are collected into a sequence (`?tag=a&tag=b`).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RepeatedKeys;
+
+impl Sealed for RepeatedKeys {}
+
+impl QueryStyle for RepeatedKeys {
+	type Value<T> = T;
+
+	const KIND: StyleKind = StyleKind::RepeatedKeys;
+}
+
+/// Bracketed keys are parsed as nested params (`?filter[status]=open`), and the values of
+/// the repeated keys and the keys with the trailing `[]` are collected into a sequence
+/// (`?tag[]=a&tag[]=b`). Nested params with numeric keys can also be deserialized as a
+/// sequence ordered by those keys (`?ids[1]=7&ids[0]=3`).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Nested;
+
+impl Sealed for Nested {}
+
+impl QueryStyle for Nested {
+	type Value<T> = T;
+
+	const KIND: StyleKind = StyleKind::Nested;
+}
+
+// --------------------------------------------------------------------------------
+
+pub(crate) fn deserialize_query<T>(
+	query_string: &str,
+	style: StyleKind,
+) -> Result<T, QueryParamsError>
+where
+	T: DeserializeOwned,
+{
+	let root = QueryNode::parse(query_string, style)?;
+
+	T::deserialize(NodeDeserializer::new(&root, None)).map_err(|error| match error {
+		QueryError::Param { key, message } | QueryError::Field { key, message } => {
+			QueryParamsError::InvalidParam { key, message }
+		}
+		QueryError::Message(message) => QueryParamsError::InvalidData(ValueError::custom(message)),
+	})
+}
+
+// --------------------------------------------------
+// QueryNode
+
+// The maximum number of bracketed segments in a nested key.
+const MAX_NESTING_DEPTH: usize = 32;
+
+#[derive(Debug, Default)]
+struct QueryNode<'q> {
+	values: Vec<Cow<'q, str>>,
+	children: Vec<(Cow<'q, str>, QueryNode<'q>)>,
+	// The positions of the children by their keys.
+	child_positions: HashMap<Cow<'q, str>, usize>,
+}
+
+impl<'q> QueryNode<'q> {
+	fn parse(query_string: &'q str, style: StyleKind) -> Result<Self, QueryParamsError> {
+		let mut root = QueryNode::default();
+
+		for (key, value) in form_urlencoded::parse(query_string.as_bytes()) {
+			match style {
+				StyleKind::Flat => root.children.push((
+					key,
+					QueryNode {
+						values: vec![value],
+						..QueryNode::default()
+					},
+				)),
+				StyleKind::RepeatedKeys => root.child_mut(key).values.push(value),
+				StyleKind::Nested => {
+					let Some((base, segments)) = split_nested_key(&key) else {
+						root.child_mut(key).values.push(value);
+
+						continue;
+					};
+
+					if segments.len() > MAX_NESTING_DEPTH {
+						return Err(QueryParamsError::TooDeeplyNested {
+							key: key.into_owned(),
+						});
+					}
+
+					let mut node = root.child_mut(Cow::Owned(base.to_owned()));
+					let last_index = segments.len() - 1;
+					for (index, segment) in segments.into_iter().enumerate() {
+						if index == last_index && segment.is_empty() {
+							break;
+						}
+
+						node = node.child_mut(Cow::Owned(segment.to_owned()));
+					}
+
+					node.values.push(value);
+				}
+			}
+		}
+
+		Ok(root)
+	}
+
+	fn child_mut(&mut self, key: Cow<'q, str>) -> &mut QueryNode<'q> {
+		let position = match self.child_positions.get(&key) {
+			Some(position) => *position,
+			None => {
+				let position = self.children.len();
+				self.child_positions.insert(key.clone(), position);
+				self.children.push((key, QueryNode::default()));
+
+				position
+			}
+		};
+
+		&mut self.children[position].1
+	}
+
+	#[inline]
+	fn is_empty(&self) -> bool {
+		self.values.is_empty() && self.children.is_empty()
+	}
+}
+
+// Splits the key 'base[segment_1][segment_2]...' into its base and segments. Returns `None`
+// if the key is not a well-formed nested key.
+fn split_nested_key(key: &str) -> Option<(&str, Vec<&str>)> {
+	let bracket_position = key.find('[')?;
+	if bracket_position == 0 {
+		return None;
+	}
+
+	let (base, mut rest) = key.split_at(bracket_position);
+	let mut segments = Vec::new();
+
+	while !rest.is_empty() {
+		let segment_end = rest.find(']')?;
+		let segment = rest.strip_prefix('[')?.get(..segment_end - 1)?;
+		if segment.contains('[') {
+			return None;
+		}
+
+		segments.push(segment);
+		rest = &rest[segment_end + 1..];
+	}
+
+	Some((base, segments))
+}
+
+// --------------------------------------------------
+// NodeDeserializer
+
+struct NodeDeserializer<'n, 'q> {
+	node: &'n QueryNode<'q>,
+	some_key: Option<String>,
+}
+
+impl<'n, 'q> NodeDeserializer<'n, 'q> {
+	#[inline]
+	fn new(node: &'n QueryNode<'q>, some_key: Option<String>) -> Self {
+		Self { node, some_key }
+	}
+
+	fn single_value(&self) -> Result<ValueDeserializer<'n>, QueryError> {
+		match self.node.values.as_slice() {
+			[value] if self.node.children.is_empty() => Ok(ValueDeserializer(value)),
+			[] => Err(QueryError::custom("expected a value")),
+			_ => Err(QueryError::custom("expected a single value")),
+		}
+	}
+
+	fn child_key(&self, key: &str) -> String {
+		match self.some_key.as_deref() {
+			Some(parent_key) => format!("{}[{}]", parent_key, key),
+			None => key.to_owned(),
+		}
+	}
+
+	fn deserialize_children_as_map<'de, V>(self, visitor: V) -> Result<V::Value, QueryError>
+	where
+		V: Visitor<'de>,
+	{
+		let result = visitor.visit_map(NodeMapAccess {
+			deserializer: &self,
+			children: self.node.children.iter(),
+			some_next_child: None,
+		});
+
+		result.map_err(|error| match error {
+			QueryError::Field { key, message } => QueryError::Param {
+				key: self.child_key(&key),
+				message,
+			},
+			error => error,
+		})
+	}
+}
+
+macro_rules! declare_deserialize_for_single_value {
+	($($deserialize:ident)*) => {
+		$(
+			fn $deserialize<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where
+				V: Visitor<'de>,
+			{
+				self.single_value()?.$deserialize(visitor)
+			}
+		)*
+	};
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer<'_, '_> {
+	type Error = QueryError;
+
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		if !self.node.children.is_empty() || self.node.values.is_empty() {
+			self.deserialize_children_as_map(visitor)
+		} else if self.node.values.len() == 1 {
+			visitor.visit_str(&self.node.values[0])
+		} else {
+			self.deserialize_seq(visitor)
+		}
+	}
+
+	declare_deserialize_for_single_value! {
+		deserialize_bool
+		deserialize_i8
+		deserialize_i16
+		deserialize_i32
+		deserialize_i64
+		deserialize_u8
+		deserialize_u16
+		deserialize_u32
+		deserialize_u64
+		deserialize_f32
+		deserialize_f64
+		deserialize_char
+		deserialize_str
+		deserialize_string
+		deserialize_bytes
+		deserialize_byte_buf
+		deserialize_identifier
+	}
+
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		if self.node.is_empty()
+			|| (self.node.children.is_empty() && self.node.values.iter().all(|value| value.is_empty()))
+		{
+			visitor.visit_none()
+		} else {
+			visitor.visit_some(self)
+		}
+	}
+
+	fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_unit()
+	}
+
+	fn deserialize_unit_struct<V>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_unit()
+	}
+
+	fn deserialize_newtype_struct<V>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		if self.node.children.is_empty() {
+			return visitor.visit_seq(ValuesSeqAccess(self.node.values.iter()));
+		}
+
+		let mut indexed_children = Vec::with_capacity(self.node.children.len());
+		for (key, child) in self.node.children.iter() {
+			let Ok(index) = key.parse::<usize>() else {
+				return Err(QueryError::custom("expected a sequence"));
+			};
+
+			indexed_children.push((index, key, child));
+		}
+
+		indexed_children.sort_by_key(|(index, _, _)| *index);
+
+		visitor.visit_seq(ChildrenSeqAccess {
+			deserializer: &self,
+			children: indexed_children.into_iter(),
+		})
+	}
+
+	fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_tuple_struct<V>(
+		self,
+		_name: &'static str,
+		_len: usize,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self.deserialize_seq(visitor)
+	}
+
+	fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self.deserialize_children_as_map(visitor)
+	}
+
+	fn deserialize_struct<V>(
+		self,
+		_name: &'static str,
+		_fields: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self.deserialize_children_as_map(visitor)
+	}
+
+	fn deserialize_enum<V>(
+		self,
+		name: &'static str,
+		variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		self
+			.single_value()?
+			.deserialize_enum(name, variants, visitor)
+	}
+
+	fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_unit()
+	}
+
+	forward_to_deserialize_any! { i128 u128 }
+}
+
+// -------------------------
+
+struct NodeMapAccess<'d, 'n, 'q> {
+	deserializer: &'d NodeDeserializer<'n, 'q>,
+	children: std::slice::Iter<'n, (Cow<'q, str>, QueryNode<'q>)>,
+	some_next_child: Option<&'n (Cow<'q, str>, QueryNode<'q>)>,
+}
+
+impl<'de> MapAccess<'de> for NodeMapAccess<'_, '_, '_> {
+	type Error = QueryError;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+	where
+		K: DeserializeSeed<'de>,
+	{
+		let Some(child) = self.children.next() else {
+			return Ok(None);
+		};
+
+		self.some_next_child = Some(child);
+
+		seed
+			.deserialize(child.0.as_ref().into_deserializer())
+			.map(Some)
+			.map_err(|error: QueryError| error.with_key(self.deserializer.child_key(&child.0)))
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+	where
+		V: DeserializeSeed<'de>,
+	{
+		let Some((key, node)) = self.some_next_child.take() else {
+			return Err(QueryError::custom("value is requested before its key"));
+		};
+
+		let child_key = self.deserializer.child_key(key);
+
+		seed
+			.deserialize(NodeDeserializer::new(node, Some(child_key.clone())))
+			.map_err(|error| error.with_key(child_key))
+	}
+}
+
+// -------------------------
+
+struct ChildrenSeqAccess<'d, 'n, 'q> {
+	deserializer: &'d NodeDeserializer<'n, 'q>,
+	children: std::vec::IntoIter<(usize, &'n Cow<'q, str>, &'n QueryNode<'q>)>,
+}
+
+impl<'de> SeqAccess<'de> for ChildrenSeqAccess<'_, '_, '_> {
+	type Error = QueryError;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+	where
+		T: DeserializeSeed<'de>,
+	{
+		let Some((_, key, node)) = self.children.next() else {
+			return Ok(None);
+		};
+
+		let child_key = self.deserializer.child_key(key);
+
+		seed
+			.deserialize(NodeDeserializer::new(node, Some(child_key.clone())))
+			.map(Some)
+			.map_err(|error| error.with_key(child_key))
+	}
+}
+
+// -------------------------
+
+struct ValuesSeqAccess<'n, 'q>(std::slice::Iter<'n, Cow<'q, str>>);
+
+impl<'de> SeqAccess<'de> for ValuesSeqAccess<'_, '_> {
+	type Error = QueryError;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+	where
+		T: DeserializeSeed<'de>,
+	{
+		self
+			.0
+			.next()
+			.map(|value| seed.deserialize(ValueDeserializer(value)))
+			.transpose()
+	}
+}
+
+// --------------------------------------------------
+// ValueDeserializer
+
+struct ValueDeserializer<'v>(&'v str);
+
+macro_rules! declare_deserialize_for_parsable {
+	($($deserialize:ident, $visit:ident);* $(;)?) => {
+		$(
+			fn $deserialize<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where
+				V: Visitor<'de>,
+			{
+				match self.0.parse() {
+					Ok(value) => visitor.$visit(value),
+					Err(error) => Err(QueryError::custom(error)),
+				}
+			}
+		)*
+	};
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'_> {
+	type Error = QueryError;
+
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_str(self.0)
+	}
+
+	declare_deserialize_for_parsable! {
+		deserialize_bool, visit_bool;
+		deserialize_i8, visit_i8;
+		deserialize_i16, visit_i16;
+		deserialize_i32, visit_i32;
+		deserialize_i64, visit_i64;
+		deserialize_u8, visit_u8;
+		deserialize_u16, visit_u16;
+		deserialize_u32, visit_u32;
+		deserialize_u64, visit_u64;
+		deserialize_f32, visit_f32;
+		deserialize_f64, visit_f64;
+		deserialize_char, visit_char;
+	}
+
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		if self.0.is_empty() {
+			visitor.visit_none()
+		} else {
+			visitor.visit_some(self)
+		}
+	}
+
+	fn deserialize_newtype_struct<V>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error>
+	where
+		V: Visitor<'de>,
+	{
+		visitor.visit_enum(self.0.into_deserializer())
+	}
+
+	forward_to_deserialize_any! {
+		i128 u128 str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+		identifier ignored_any
+	}
+}
+
+// --------------------------------------------------
+// QueryError
+
+#[derive(Debug, crate::ImplError)]
+enum QueryError {
+	#[error("{0}")]
+	Message(String),
+	#[error("query param {key}: {message}")]
+	Param { key: String, message: String },
+	// Errors of a struct field reported by the struct's visitor. The key is relative to
+	// the struct.
+	#[error("field {key}: {message}")]
+	Field { key: String, message: String },
+}
+
+impl QueryError {
+	fn with_key(self, key: String) -> Self {
+		match self {
+			Self::Message(message) => Self::Param { key, message },
+			error => error,
+		}
+	}
+}
+
+impl serde::de::Error for QueryError {
+	fn custom<T: Display>(message: T) -> Self {
+		Self::Message(message.to_string())
+	}
+
+	fn missing_field(field: &'static str) -> Self {
+		Self::Field {
+			key: field.to_owned(),
+			message: "missing".to_owned(),
+		}
+	}
+
+	fn duplicate_field(field: &'static str) -> Self {
+		Self::Field {
+			key: field.to_owned(),
+			message: "duplicated".to_owned(),
+		}
+	}
+
+	fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+		Self::Field {
+			key: field.to_owned(),
+			message: "unknown".to_owned(),
+		}
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use std::collections::HashMap;
+
+	use http::StatusCode;
+	use http_body_util::{BodyExt, Empty};
+	use hyper::service::Service;
+	use serde::Deserialize;
+
+	use crate::{handler::HandlerSetter, request::Request, resource::Resource};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Pagination {
+		page: u32,
+		per_page: Option<u32>,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Tags {
+		tag: Vec<String>,
+		some_limit: Option<u8>,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	#[serde(rename_all = "lowercase")]
+	enum Status {
+		Open,
+		Closed,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Filter {
+		status: Status,
+		labels: Vec<String>,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Search {
+		filter: Filter,
+		ids: Option<Vec<u32>>,
+		q: Option<String>,
+	}
+
+	// -------------------------
+
+	#[test]
+	fn split_nested_key() {
+		assert_eq!(
+			super::split_nested_key("filter[status]"),
+			Some(("filter", vec!["status"]))
+		);
+
+		assert_eq!(
+			super::split_nested_key("filter[labels][]"),
+			Some(("filter", vec!["labels", ""]))
+		);
+
+		assert_eq!(super::split_nested_key("filter"), None);
+		assert_eq!(super::split_nested_key("[status]"), None);
+		assert_eq!(super::split_nested_key("filter[status"), None);
+		assert_eq!(super::split_nested_key("filter[status]x"), None);
+		assert_eq!(super::split_nested_key("filter[sta[tus]"), None);
+	}
+
+	#[test]
+	fn deserialize_flat_query() {
+		let pagination = deserialize_query::<Pagination>("page=2&per_page=", StyleKind::Flat).unwrap();
+		assert_eq!(
+			pagination,
+			Pagination {
+				page: 2,
+				per_page: None
+			}
+		);
+
+		let pagination = deserialize_query::<Pagination>("per_page=10", StyleKind::Flat);
+		assert!(matches!(
+			pagination,
+			Err(QueryParamsError::InvalidParam { key, message }) if key == "page" && message == "missing",
+		));
+
+		let pagination = deserialize_query::<Pagination>("page=1&page=2", StyleKind::Flat);
+		assert!(matches!(
+			pagination,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "page",
+		));
+
+		let pagination = deserialize_query::<Pagination>("page=1&per_page=ten", StyleKind::Flat);
+		assert!(matches!(
+			pagination,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "per_page",
+		));
+
+		let map = deserialize_query::<HashMap<String, String>>("a=1&b[c]=2", StyleKind::Flat).unwrap();
+		assert_eq!(map.get("a").unwrap(), "1");
+		assert_eq!(map.get("b[c]").unwrap(), "2");
+
+		let result = deserialize_query::<u32>("a=1", StyleKind::Flat);
+		assert!(matches!(result, Err(QueryParamsError::InvalidData(_))));
+	}
+
+	#[test]
+	fn deserialize_repeated_keys_query() {
+		let tags =
+			deserialize_query::<Tags>("tag=a&some_limit=3&tag=b", StyleKind::RepeatedKeys).unwrap();
+		assert_eq!(
+			tags,
+			Tags {
+				tag: vec!["a".to_string(), "b".to_string()],
+				some_limit: Some(3)
+			}
+		);
+
+		let tags = deserialize_query::<Tags>("tag=a", StyleKind::RepeatedKeys).unwrap();
+		assert_eq!(tags.tag, ["a"]);
+
+		let tags =
+			deserialize_query::<Tags>("tag=a&some_limit=1&some_limit=2", StyleKind::RepeatedKeys);
+		assert!(matches!(
+			tags,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "some_limit",
+		));
+	}
+
+	#[test]
+	fn deserialize_nested_query() {
+		let search = deserialize_query::<Search>(
+			"filter[status]=open&filter[labels][]=bug&filter[labels][]=ui&ids[1]=7&ids[0]=3",
+			StyleKind::Nested,
+		)
+		.unwrap();
+
+		assert_eq!(
+			search,
+			Search {
+				filter: Filter {
+					status: Status::Open,
+					labels: vec!["bug".to_string(), "ui".to_string()],
+				},
+				ids: Some(vec![3, 7]),
+				q: None,
+			}
+		);
+
+		let search = deserialize_query::<Search>(
+			"filter[status]=closed&filter[labels]=bug&q=hello+world",
+			StyleKind::Nested,
+		)
+		.unwrap();
+
+		assert_eq!(search.filter.status, Status::Closed);
+		assert_eq!(search.filter.labels, ["bug"]);
+		assert_eq!(search.q.as_deref(), Some("hello world"));
+
+		let search = deserialize_query::<Search>(
+			"filter[status]=pending&filter[labels][]=bug",
+			StyleKind::Nested,
+		);
+
+		assert!(matches!(
+			search,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "filter[status]",
+		));
+
+		let search = deserialize_query::<Search>("filter[status]=open", StyleKind::Nested);
+		assert!(matches!(
+			search,
+			Err(QueryParamsError::InvalidParam { key, message }) if key == "filter[labels]" && message == "missing",
+		));
+
+		let search = deserialize_query::<Search>(
+			"filter[status]=open&filter[labels][]=bug&ids[0]=3&ids[x]=7",
+			StyleKind::Nested,
+		);
+
+		assert!(matches!(
+			search,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "ids",
+		));
+
+		let search = deserialize_query::<Search>(
+			"filter[status]=open&filter[labels][]=bug&ids[0]=3&ids[1]=x",
+			StyleKind::Nested,
+		);
+
+		assert!(matches!(
+			search,
+			Err(QueryParamsError::InvalidParam { key, .. }) if key == "ids[1]",
+		));
+
+		let deep_key = format!("a{}", "[b]".repeat(MAX_NESTING_DEPTH + 1));
+		let result =
+			deserialize_query::<HashMap<String, String>>(&format!("{}=1", deep_key), StyleKind::Nested);
+
+		assert!(matches!(
+			result,
+			Err(QueryParamsError::TooDeeplyNested { key }) if key == deep_key,
+		));
+
+		// Many distinct keys.
+		let query_string = (0..10_000)
+			.map(|index| format!("m[k{}]={}", index, index))
+			.collect::<Vec<_>>()
+			.join("&");
+
+		let map =
+			deserialize_query::<HashMap<String, HashMap<String, u32>>>(&query_string, StyleKind::Nested)
+				.unwrap();
+
+		assert_eq!(map["m"].len(), 10_000);
+		assert_eq!(map["m"]["k9999"], 9999);
+	}
+
+	#[tokio::test]
+	async fn query_extractor() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(
+			http::Method::GET.to(|Query(search): Query<Search, Nested>| async move {
+				format!("{:?} {:?}", search.filter.status, search.filter.labels)
+			}),
+		);
+
+		let service = root.into_service();
+
+		// ----------
+
+		let request = Request::get("/?filter[status]=open&filter[labels][]=bug&filter[labels][]=ui")
+			.body(Empty::<bytes::Bytes>::new())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+
+		let body = response.collect().await.unwrap().to_bytes();
+		assert_eq!(body, r#"Open ["bug", "ui"]"#);
+
+		// ----------
+
+		let request = Request::get("/?filter[status]=open")
+			.body(Empty::<bytes::Bytes>::new())
+			.unwrap();
+
+		let response = service.call(request).await.unwrap();
+		assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+	}
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, str::Utf8Error};
+use std::{borrow::Cow, str::Utf8Error, sync::Arc};
 
 use argan_core::response::{IntoResponse, Response};
 use http::{header::ALLOW, HeaderValue, Method, StatusCode, Uri};
@@ -14,6 +14,7 @@ pub(crate) struct RoutingState {
 	pub(crate) route_traversal: RouteTraversal,
 	pub(crate) uri_params: ParamsList,
 	pub(crate) subtree_handler_exists: bool,
+	pub(crate) canonical_segments: Vec<CanonicalSegment>,
 }
 
 impl RoutingState {
@@ -23,10 +24,16 @@ impl RoutingState {
 			route_traversal,
 			uri_params: ParamsList::new(),
 			subtree_handler_exists: false,
+			canonical_segments: Vec::new(),
 		}
 	}
 }
 
+// A path segment that matched a static pattern only after the normalization. The index
+// and the length of the segment in the path, and the registered pattern that replaces it
+// in the canonical path.
+pub(crate) type CanonicalSegment = (usize, usize, Arc<str>);
+
 // --------------------------------------------------
 
 #[derive(Debug, Default)]
@@ -75,7 +75,7 @@ impl WebSocketUpgrade {
 	pub fn select_protocol<Func>(
 		&mut self,
 		selector: Func,
-	) -> Result<Option<Cow<str>>, WebSocketUpgradeError>
+	) -> Result<Option<Cow<'_, str>>, WebSocketUpgradeError>
 	where
 		Func: Fn(&str) -> bool,
 	{
@@ -3,22 +3,37 @@
 // ----------
 
 use std::{
+	collections::HashMap,
 	fmt::{Debug, Display},
 	sync::Arc,
 };
 
+use argan_core::{
+	body::{Body, Bytes, HttpBody},
+	BoxedError,
+};
+use http::Request;
+use tower_service::Service as TowerService;
+
 use crate::{
 	common::{
-		node_properties::NodeProperty, patterns_to_route, IntoArray, NodeExtension, SCOPE_VALIDITY,
+		node_properties::{NodeProperty, StaticMatching},
+		patterns_to_route, IntoArray, NodeExtension, SCOPE_VALIDITY,
 	},
 	handler::{
 		kind::HandlerKind,
 		request_handlers::{wrap_mistargeted_request_handler, MethodHandlers, SupportedMethods},
 		BoxedHandler,
 	},
+	http::WildcardMethod,
 	middleware::targets::LayerTarget,
-	pattern::{split_uri_host_and_path, Pattern, Similarity},
+	pattern::{
+		add_registered_constraint, split_uri_host_and_path, ExpectedParams, Pattern,
+		RegisteredConstraint, Similarity,
+	},
 	request::{routing::RouteSegments, RequestContextProperties},
+	response::{BoxedErrorResponse, Response},
+	router::RouterReloadError,
 };
 
 // --------------------------------------------------
@@ -27,9 +42,26 @@ mod config;
 
 use self::{
 	config::{resource_config_from, ConfigFlags},
-	service::{ResourceRequestHandler, ResourceRequestPasser, ResourceRequestReceiver},
+	service::{
+		RelaxedStaticMatching, ResourceRequestHandler, ResourceRequestPasser, ResourceRequestReceiver,
+	},
 };
 
+mod path_params;
+pub use path_params::{PathParamsCheckError, PathParamsIssue};
+
+pub use crate::pattern::SegmentConstraint;
+
+mod named_routes;
+pub(crate) use named_routes::NamedRoutes;
+pub use named_routes::UrlForError;
+
+mod mounted_service;
+use mounted_service::MountedService;
+
+mod routes;
+pub use routes::{LayerTargetKind, Route, SlashPolicy};
+
 mod service;
 pub(crate) use service::FinalResource;
 pub use service::{
@@ -42,6 +74,9 @@ mod static_files;
 #[cfg(feature = "file-stream")]
 pub use static_files::StaticFiles;
 
+#[cfg(feature = "openapi")]
+use crate::openapi::Operation;
+
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
 
@@ -51,6 +86,7 @@ pub struct Resource {
 	pattern: Pattern,
 	prefix_segment_patterns: Vec<Pattern>,
 	some_host_pattern: Option<Pattern>,
+	some_name: Option<Box<str>>,
 
 	static_resources: Vec<Resource>,
 	regex_resources: Vec<Resource>,
@@ -58,8 +94,13 @@ pub struct Resource {
 
 	method_handlers: MethodHandlers,
 	some_mistargeted_request_handler: Option<BoxedHandler>,
+	handlers_expected_path_params: Vec<(String, Option<ExpectedParams>)>,
+	#[cfg(feature = "openapi")]
+	operations: Vec<(http::Method, Operation)>,
 
 	request_context_properties: RequestContextProperties,
+	some_static_matching: Option<StaticMatching>,
+	segment_constraints: Vec<RegisteredConstraint>,
 	extension: NodeExtension,
 	middleware: Vec<LayerTarget<Self>>,
 
@@ -94,7 +135,7 @@ impl Resource {
 			split_uri_host_and_path(uri_pattern.as_ref());
 
 		let some_host_pattern = some_host_pattern_str.map(|host_pattern_str| {
-			let host_pattern = Pattern::parse(host_pattern_str);
+			let host_pattern = Pattern::parse_host(host_pattern_str);
 			if host_pattern.is_wildcard() {
 				panic!("host pattern cannot be a wildcard")
 			}
@@ -135,6 +176,10 @@ impl Resource {
 			let pattern = Pattern::parse(route_segment);
 
 			if route_segments.has_remaining_segments() {
+				if pattern.is_catch_all() {
+					panic!("a catch-all must be the last segment of the path pattern")
+				}
+
 				prefix_path_pattern.push(pattern);
 
 				continue;
@@ -161,12 +206,18 @@ impl Resource {
 			pattern: resource_pattern,
 			prefix_segment_patterns: prefix_path_pattern,
 			some_host_pattern,
+			some_name: None,
 			static_resources: Vec::new(),
 			regex_resources: Vec::new(),
 			some_wildcard_resource: None,
 			method_handlers: MethodHandlers::new(),
 			some_mistargeted_request_handler: None,
+			handlers_expected_path_params: Vec::new(),
+			#[cfg(feature = "openapi")]
+			operations: Vec::new(),
 			request_context_properties: RequestContextProperties::default(),
+			some_static_matching: None,
+			segment_constraints: Vec::new(),
 			extension: NodeExtension::new(),
 			middleware: Vec::new(),
 			config_flags,
@@ -198,7 +249,7 @@ impl Resource {
 			.or_else(|| host_pattern_str.strip_prefix("http://"))
 			.unwrap_or(host_pattern_str);
 
-		let host_pattern = Pattern::parse(host_pattern_str);
+		let host_pattern = Pattern::parse_host(host_pattern_str);
 
 		self
 			.some_host_pattern
@@ -393,6 +444,10 @@ impl Resource {
 			}
 		};
 
+		if self.pattern.is_catch_all() {
+			panic!("a catch-all must be the last segment of the path pattern")
+		}
+
 		self.check_names_are_unique_in_the_path(&new_resource);
 
 		// -----
@@ -450,7 +505,9 @@ impl Resource {
 			Pattern::Static(_) => add_resource!(self.static_resources, new_resource),
 			#[cfg(feature = "regex")]
 			Pattern::Regex(..) => add_resource!(self.regex_resources, new_resource),
-			Pattern::Wildcard(_) => {
+			Pattern::Constrained(..) => add_resource!(self.regex_resources, new_resource),
+			Pattern::Subdomain(..) => unreachable!("subdomain patterns are only used for hosts"),
+			Pattern::Wildcard(_) | Pattern::CatchAll(..) => {
 				// Explanation inside the above macro 'add_resource!' also applies here.
 				if let Some(mut wildcard_resource) = self.some_wildcard_resource.take() {
 					if wildcard_resource.pattern.compare(&new_resource.pattern) == Similarity::Same {
@@ -555,21 +612,7 @@ impl Resource {
 						break;
 					}
 				}
-				#[cfg(feature = "regex")]
-				Pattern::Regex(_, _) => {
-					let some_position = leaf_resource
-						.regex_resources
-						.iter()
-						.position(|resource| resource.pattern.compare(pattern) == Similarity::Same);
-
-					if let Some(position) = some_position {
-						leaf_resource = &mut leaf_resource.regex_resources[position];
-						peekable_patterns.next();
-					} else {
-						break;
-					}
-				}
-				Pattern::Wildcard(_) => {
+				Pattern::Wildcard(_) | Pattern::CatchAll(..) => {
 					if leaf_resource
 						.some_wildcard_resource
 						.as_ref()
@@ -585,6 +628,20 @@ impl Resource {
 						break;
 					}
 				}
+				// Regex and constrained patterns.
+				_ => {
+					let some_position = leaf_resource
+						.regex_resources
+						.iter()
+						.position(|resource| resource.pattern.compare(pattern) == Similarity::Same);
+
+					if let Some(position) = some_position {
+						leaf_resource = &mut leaf_resource.regex_resources[position];
+						peekable_patterns.next();
+					} else {
+						break;
+					}
+				}
 			}
 		}
 
@@ -648,6 +705,34 @@ impl Resource {
 	// of self and keeps the ones that have some effect on a request or have no corresponding
 	// resource.
 	pub(crate) fn keep_subresources(&mut self, mut other: Resource) {
+		if let Some(other_name) = other.some_name.take() {
+			match self.some_name.as_ref() {
+				Some(name) if name != &other_name => {
+					panic!(
+						"conflicting names '{}' and '{}' of the resource with a pattern '{}'",
+						name, other_name, self.pattern
+					)
+				}
+				_ => self.some_name = Some(other_name),
+			}
+		}
+
+		if let Some(other_static_matching) = other.some_static_matching.take() {
+			match self.some_static_matching {
+				Some(static_matching) if static_matching != other_static_matching => {
+					panic!(
+						"conflicting static matching rules of the resource with a pattern '{}'",
+						self.pattern
+					)
+				}
+				_ => self.some_static_matching = Some(other_static_matching),
+			}
+		}
+
+		for constraint in std::mem::take(&mut other.segment_constraints) {
+			add_registered_constraint(&mut self.segment_constraints, constraint);
+		}
+
 		macro_rules! keep_other_resources {
 			(mut $resources:expr, mut $other_resources:expr) => {
 				if !$other_resources.is_empty() {
@@ -971,22 +1056,7 @@ impl Resource {
 						break;
 					}
 				}
-				#[cfg(feature = "regex")]
-				Pattern::Regex(_, _) => {
-					let some_position = leaf_resource
-						.regex_resources
-						.iter()
-						.position(|resource| resource.pattern.compare(&pattern) == Similarity::Same);
-
-					if let Some(position) = some_position {
-						leaf_resource = &leaf_resource.regex_resources[position];
-					} else {
-						route_segments.revert_to_segment(segment_index);
-
-						break;
-					}
-				}
-				Pattern::Wildcard(_) => {
+				Pattern::Wildcard(_) | Pattern::CatchAll(..) => {
 					if leaf_resource
 						.some_wildcard_resource
 						.as_ref()
@@ -999,6 +1069,21 @@ impl Resource {
 					} else {
 						route_segments.revert_to_segment(segment_index);
 
+						break;
+					}
+				}
+				// Regex and constrained patterns.
+				_ => {
+					let some_position = leaf_resource
+						.regex_resources
+						.iter()
+						.position(|resource| resource.pattern.compare(&pattern) == Similarity::Same);
+
+					if let Some(position) = some_position {
+						leaf_resource = &leaf_resource.regex_resources[position];
+					} else {
+						route_segments.revert_to_segment(segment_index);
+
 						break;
 					}
 				}
@@ -1032,22 +1117,7 @@ impl Resource {
 						break;
 					}
 				}
-				#[cfg(feature = "regex")]
-				Pattern::Regex(_, _) => {
-					let some_position = leaf_resource
-						.regex_resources
-						.iter()
-						.position(|resource| resource.pattern.compare(&pattern) == Similarity::Same);
-
-					if let Some(position) = some_position {
-						leaf_resource = &mut leaf_resource.regex_resources[position];
-					} else {
-						route_segments.revert_to_segment(segment_index);
-
-						break;
-					}
-				}
-				Pattern::Wildcard(_) => {
+				Pattern::Wildcard(_) | Pattern::CatchAll(..) => {
 					if leaf_resource
 						.some_wildcard_resource
 						.as_ref()
@@ -1060,6 +1130,21 @@ impl Resource {
 					} else {
 						route_segments.revert_to_segment(segment_index);
 
+						break;
+					}
+				}
+				// Regex and constrained patterns.
+				_ => {
+					let some_position = leaf_resource
+						.regex_resources
+						.iter()
+						.position(|resource| resource.pattern.compare(&pattern) == Similarity::Same);
+
+					if let Some(position) = some_position {
+						leaf_resource = &mut leaf_resource.regex_resources[position];
+					} else {
+						route_segments.revert_to_segment(segment_index);
+
 						break;
 					}
 				}
@@ -1077,21 +1162,9 @@ impl Resource {
 		for (segment, _) in segments {
 			let pattern = Pattern::parse(segment);
 
-			#[cfg(feature = "regex")]
-			if let Pattern::Regex(_, _) = &pattern {
-				if let Some(capture_name) =
-					current_resource.find_duplicate_capture_name_in_the_path(&pattern)
-				{
-					panic!("capture name '{}' is not unique in the path", capture_name)
-				}
-			}
-
-			if let Pattern::Wildcard(_) = &pattern {
-				if let Some(capture_name) =
-					current_resource.find_duplicate_capture_name_in_the_path(&pattern)
-				{
-					panic!("capture name '{}' is not unique in the path", capture_name)
-				}
+			if let Some(capture_name) = current_resource.find_duplicate_capture_name_in_the_path(&pattern)
+			{
+				panic!("capture name '{}' is not unique in the path", capture_name)
 			}
 
 			current_resource.add_subresource(Resource::with_pattern(pattern));
@@ -1110,63 +1183,12 @@ impl Resource {
 	}
 
 	fn find_duplicate_capture_name_in_the_path<'p>(&self, pattern: &'p Pattern) -> Option<&'p str> {
-		match pattern {
-			#[cfg(feature = "regex")]
-			Pattern::Regex(capture_names, _) => {
-				for prefix_pattern in self.prefix_segment_patterns.iter() {
-					match prefix_pattern {
-						Pattern::Regex(other_capture_names, _) => {
-							let some_capture_name = capture_names
-								.as_ref()
-								.iter()
-								.find(|(capture_name, _)| other_capture_names.has(capture_name.as_ref()));
-
-							if let Some((capture_name, _)) = some_capture_name {
-								return Some(capture_name.as_ref());
-							}
-						}
-						Pattern::Wildcard(other_capture_name) => {
-							let some_capture_name = capture_names
-								.as_ref()
-								.iter()
-								.find(|(capture_name, _)| capture_name.as_ref() == other_capture_name.as_ref());
-
-							if let Some((capture_name, _)) = some_capture_name {
-								return Some(capture_name.as_ref());
-							}
-						}
-						Pattern::Static(_) => {}
-					}
-				}
-
-				None
-			}
-			Pattern::Wildcard(capture_name) => {
-				for prefix_pattern in self.prefix_segment_patterns.iter() {
-					match prefix_pattern {
-						#[cfg(feature = "regex")]
-						Pattern::Regex(other_capture_names, _) => {
-							if other_capture_names
-								.as_ref()
-								.iter()
-								.any(|(other_capture_name, _)| capture_name.as_ref() == other_capture_name.as_ref())
-							{
-								return Some(capture_name.as_ref());
-							}
-						}
-						Pattern::Wildcard(other_capture_name) => {
-							if capture_name.as_ref() == other_capture_name.as_ref() {
-								return Some(capture_name.as_ref());
-							}
-						}
-						Pattern::Static(_) => {}
-					}
-				}
-
-				None
-			}
-			Pattern::Static(_) => None,
-		}
+		pattern.capture_names().into_iter().find(|capture_name| {
+			self
+				.prefix_segment_patterns
+				.iter()
+				.any(|prefix_pattern| prefix_pattern.capture_names().contains(capture_name))
+		})
 	}
 
 	// -------------------------
@@ -1186,6 +1208,100 @@ impl Resource {
 		self.extension.set_value_to(extension)
 	}
 
+	/// Sets the name of the `Resource`. The name can be used to generate the resource's URL
+	/// with the `url_for()` method of the [`RequestContext`] and [`RequestHead`].
+	///
+	/// ```
+	/// use argan::{Resource, handler::HandlerSetter, http::Method, request::RequestHead};
+	///
+	/// let mut root = Resource::new("/");
+	/// root.subresource_mut("/users/{id}").set_name("user");
+	/// root
+	///   .subresource_mut("/profile")
+	///   .set_handler_for(Method::GET.to(|head: RequestHead| async move {
+	///     head.url_for("user", &[("id", "42")]).unwrap() // "/users/42"
+	///   }));
+	/// ```
+	///
+	/// # Panics
+	///
+	/// - if the `Resource` already has a name
+	/// - if some other resource in the service has the same name (when the resource tree
+	///   is being converted into a service)
+	///
+	/// [`RequestContext`]: crate::request::RequestContext
+	/// [`RequestHead`]: crate::request::RequestHead
+	pub fn set_name<N: AsRef<str>>(&mut self, name: N) {
+		if self.some_name.is_some() {
+			panic!("resource already has a name");
+		}
+
+		self.some_name = Some(name.as_ref().into())
+	}
+
+	/// Mounts the tower `service` at the given relative path.
+	///
+	/// The resource at the path becomes a subtree handler that passes the requests with any
+	/// method to the `service`, with or without a trailing slash. Before the `service` is
+	/// called, the resource's path is stripped from the request's URI, so the `service`
+	/// receives the remaining path segments as its path. The original URI is kept in the
+	/// request's extensions as [`OriginalUri`](crate::request::OriginalUri).
+	///
+	/// ```
+	/// use std::{
+	///   convert::Infallible,
+	///   future::{ready, Ready},
+	///   task::{Context, Poll},
+	/// };
+	///
+	/// use argan::{Resource, body::Body, request::Request, response::Response};
+	///
+	/// #[derive(Clone)]
+	/// struct Metrics;
+	///
+	/// impl tower_service::Service<Request<Body>> for Metrics {
+	///   type Response = Response<String>;
+	///   type Error = Infallible;
+	///   type Future = Ready<Result<Self::Response, Self::Error>>;
+	///
+	///   fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+	///     Poll::Ready(Ok(()))
+	///   }
+	///
+	///   fn call(&mut self, request: Request<Body>) -> Self::Future {
+	///     // The request to "/internal/metrics/requests" has a "/requests" path here.
+	///     ready(Ok(Response::new(request.uri().path().to_owned())))
+	///   }
+	/// }
+	///
+	/// let mut root = Resource::new("/");
+	/// root.mount_service("/internal/metrics", Metrics);
+	/// ```
+	///
+	/// # Panics
+	///
+	/// - if the path has config symbols
+	/// - if the resource at the path already has a *wildcard method* handler
+	pub fn mount_service<P, S, B>(&mut self, relative_path: P, service: S)
+	where
+		P: AsRef<str>,
+		S: TowerService<Request<Body>, Response = Response<B>> + Clone + Send + Sync + 'static,
+		S::Error: Into<BoxedErrorResponse>,
+		S::Future: Send + 'static,
+		B: HttpBody<Data = Bytes> + Send + Sync + 'static,
+		B::Error: Into<BoxedError>,
+	{
+		let relative_path = relative_path.as_ref();
+
+		if relative_path.contains(' ') {
+			panic!("the path of the mounted service cannot have config symbols")
+		}
+
+		self
+			.subresource_mut(format!("{} ?*", relative_path))
+			.set_handler_for(WildcardMethod.to(Some(MountedService::new(service))));
+	}
+
 	// pub fn extension_ref<E: Clone + Send + Sync + 'static>(&self) -> &E {
 	// 	self.extensions.get::<E>().expect(&format!(
 	// 		"resource should have been provided with an extension of type '{}'",
@@ -1214,15 +1330,60 @@ impl Resource {
 			use HandlerKind::*;
 
 			match handler_kind {
-				Method(method, handler) => self.method_handlers.set_handler(method, handler),
-				WildcardMethod(some_handler) => self
-					.method_handlers
-					.set_wildcard_method_handler(some_handler),
-				MistargetedRequest(handler) => self.some_mistargeted_request_handler = Some(handler),
+				Method(method, handler, some_expected_params) => {
+					self.note_expected_path_params(method.to_string(), some_expected_params);
+					self.method_handlers.set_handler(method, handler);
+				}
+				WildcardMethod(some_handler, some_expected_params) => {
+					let handler_name = "wildcard method".to_string();
+
+					if some_handler.is_some() {
+						self.note_expected_path_params(handler_name, some_expected_params);
+					} else {
+						self
+							.handlers_expected_path_params
+							.retain(|(name, _)| *name != handler_name);
+					}
+
+					self
+						.method_handlers
+						.set_wildcard_method_handler(some_handler);
+				}
+				MistargetedRequest(handler, some_expected_params) => {
+					self.note_expected_path_params("mistargeted request".to_string(), some_expected_params);
+					self.some_mistargeted_request_handler = Some(handler);
+				}
+				#[cfg(feature = "openapi")]
+				WithOperation(handler_kind, operation) => {
+					let Method(method, ..) = handler_kind.as_ref() else {
+						panic!("an operation can only be attached to a method handler")
+					};
+
+					self.operations.push((method.clone(), operation));
+					self.set_handler_for(*handler_kind);
+				}
 			}
 		}
 	}
 
+	fn note_expected_path_params(
+		&mut self,
+		handler_name: String,
+		some_expected_params: Option<ExpectedParams>,
+	) {
+		if let Some(position) = self
+			.handlers_expected_path_params
+			.iter()
+			.position(|(name, _)| *name == handler_name)
+		{
+			self.handlers_expected_path_params[position].1 = some_expected_params;
+		} else {
+			self
+				.handlers_expected_path_params
+				.push((handler_name, some_expected_params));
+		}
+	}
+
 	/// Adds middleware to be applied on the resource's components, like request receiver,
 	/// passer, and method and other kind of handlers.
 	///
@@ -1300,19 +1461,25 @@ impl Resource {
 	/// // routed or handled.
 	/// resource.set_property(NodeCookieKey.to(Key::generate()));
 	/// ```
+	///
+	/// See [`StaticMatching`](crate::common::node_properties::StaticMatching) for the rules
+	/// of matching the request's path segments against the static subresources.
 	pub fn set_property<C, const N: usize>(&mut self, properties: C)
 	where
 		C: IntoArray<NodeProperty<Self>, N>,
 	{
 		#![allow(unused_imports)]
 
-		// Currently we have only one property.
-		if let Some(property) = properties.into_array().into_iter().next() {
+		for property in properties.into_array() {
 			use NodeProperty::*;
 
 			match property {
 				#[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 				CookieKey(cookie_key) => self.request_context_properties.set_cookie_key(cookie_key),
+				StaticMatching(static_matching) => self.some_static_matching = Some(static_matching),
+				SegmentConstraint(name, constraint) => {
+					add_registered_constraint(&mut self.segment_constraints, (name, constraint))
+				}
 				_ => unreachable!("ConfigOption::None should never be used"),
 			}
 		}
@@ -1356,59 +1523,407 @@ impl Resource {
 		}
 	}
 
-	pub(crate) fn finalize(self) -> FinalResource {
-		let Resource {
-			pattern,
-			prefix_segment_patterns: __prefix_segment_patterns,
-			some_host_pattern: __some_host_pattern,
-			static_resources,
-			regex_resources,
-			some_wildcard_resource,
-			method_handlers,
-			some_mistargeted_request_handler,
-			request_context_properties: context,
-			extension,
-			mut middleware,
-			config_flags,
-		} = self;
+	// -------------------------
 
-		// ----------
+	/// Checks the path params expected by the handlers in the resource tree against the
+	/// params captured by their resources' routes.
+	///
+	/// The expected params are derived from the handler's [`PathParams`] guard or declared
+	/// explicitly with the `expecting_path_params()` method of the handler kind. Handlers
+	/// whose expected params are unknown are not checked.
+	///
+	/// An unused capture is reported only when all the handlers of the resource have known
+	/// expected params.
+	///
+	/// ```
+	/// use argan::{Resource, handler::HandlerSetter, http::Method, request::PathParams};
+	/// use serde::Deserialize;
+	///
+	/// #[derive(Deserialize)]
+	/// struct User {
+	///   user_id: u32,
+	/// }
+	///
+	/// async fn user(PathParams(user): PathParams<User>) {
+	///   // ...
+	/// }
+	///
+	/// let mut root = Resource::new("/");
+	/// root.subresource_mut("/users/{id}").set_handler_for(Method::GET.to(user));
+	///
+	/// let error = root.check_path_params().unwrap_err();
+	/// assert!(error.has_mismatches());
+	/// ```
+	///
+	/// [`PathParams`]: crate::request::PathParams
+	pub fn check_path_params(&self) -> Result<(), PathParamsCheckError> {
+		let mut issues = Vec::new();
+		self.collect_path_params_issues(&mut issues);
 
-		let some_static_resources = if static_resources.is_empty() {
-			None
-		} else {
-			Some(
-				static_resources
-					.into_iter()
-					.map(Resource::finalize)
-					.collect(),
-			)
-		};
+		PathParamsCheckError::from_issues(issues)
+	}
 
-		let some_regex_resources = if regex_resources.is_empty() {
-			None
-		} else {
-			Some(
-				regex_resources
-					.into_iter()
-					.map(Resource::finalize)
-					.collect(),
-			)
-		};
+	pub(crate) fn collect_path_params_issues(&self, issues: &mut Vec<PathParamsIssue>) {
+		let host_capture_names = self
+			.some_host_pattern
+			.as_ref()
+			.map(Pattern::capture_names)
+			.unwrap_or_default();
 
-		let some_wildcard_resource =
-			some_wildcard_resource.map(|resource| Arc::new(resource.finalize()));
+		let mut path_patterns = self.prefix_segment_patterns.iter().collect::<Vec<_>>();
 
-		// ----------
+		self.collect_subtree_path_params_issues(&host_capture_names, &mut path_patterns, issues);
+	}
 
-		let MethodHandlers {
-			method_handlers_list,
-			wildcard_method_handler,
-			supported_methods,
-		} = method_handlers;
+	fn collect_subtree_path_params_issues<'r>(
+		&'r self,
+		host_capture_names: &[&str],
+		path_patterns: &mut Vec<&'r Pattern>,
+		issues: &mut Vec<PathParamsIssue>,
+	) {
+		path_patterns.push(&self.pattern);
+
+		if !self.handlers_expected_path_params.is_empty() {
+			let mut capture_names = host_capture_names.to_vec();
+			capture_names.extend(
+				path_patterns
+					.iter()
+					.flat_map(|pattern| pattern.capture_names()),
+			);
 
-		// -------------------------
-		// MistargetedRequestHandller
+			let mut route = String::new();
+			for pattern in path_patterns.iter() {
+				if pattern.is_static_match("/") != Some(true) {
+					route.push('/');
+					route.push_str(&pattern.pattern_str());
+				}
+			}
+
+			if route.is_empty() {
+				route.push('/');
+			}
+
+			let mut all_expected_params_are_known = true;
+
+			for (handler_name, some_expected_params) in self.handlers_expected_path_params.iter() {
+				let Some(expected_params) = some_expected_params else {
+					all_expected_params_are_known = false;
+
+					continue;
+				};
+
+				for param in expected_params.required_names() {
+					if !capture_names.contains(&param) {
+						issues.push(PathParamsIssue::Mismatch {
+							route: route.clone(),
+							handler: handler_name.clone(),
+							param: param.to_string(),
+						});
+					}
+				}
+			}
+
+			if all_expected_params_are_known {
+				for capture_name in capture_names {
+					if !self
+						.handlers_expected_path_params
+						.iter()
+						.filter_map(|(_, some_expected_params)| some_expected_params.as_ref())
+						.any(|expected_params| expected_params.has(capture_name))
+					{
+						issues.push(PathParamsIssue::UnusedCapture {
+							route: route.clone(),
+							param: capture_name.to_string(),
+						});
+					}
+				}
+			}
+		}
+
+		for subresource in self
+			.static_resources
+			.iter()
+			.chain(self.regex_resources.iter())
+			.chain(self.some_wildcard_resource.as_deref())
+		{
+			subresource.collect_subtree_path_params_issues(host_capture_names, path_patterns, issues);
+		}
+
+		path_patterns.pop();
+	}
+
+	/// Returns the routes of the resource tree.
+	///
+	/// Only the resources that have a handler or were wrapped in a middleware are listed.
+	/// The routes are listed in a depth-first manner, static resources first, then regex
+	/// and wildcard resources.
+	///
+	/// ```
+	/// use argan::{Resource, handler::HandlerSetter, http::Method};
+	///
+	/// let mut root = Resource::new("/");
+	/// root.set_handler_for(Method::GET.to(|| async {}));
+	/// root
+	///   .subresource_mut("/articles/{id:@u64}")
+	///   .set_handler_for(Method::GET.to(|| async {}));
+	///
+	/// for route in root.routes() {
+	///   println!("{}", route);
+	/// }
+	/// ```
+	pub fn routes(&self) -> impl Iterator<Item = Route> {
+		let mut routes = Vec::new();
+		self.collect_routes(&mut routes);
+
+		routes.into_iter()
+	}
+
+	pub(crate) fn collect_routes(&self, routes: &mut Vec<Route>) {
+		let some_host = self
+			.some_host_pattern
+			.as_ref()
+			.map(|host_pattern| host_pattern.pattern_str().into_owned());
+
+		let mut path_patterns = self.prefix_segment_patterns.iter().collect::<Vec<_>>();
+
+		self.collect_subtree_routes(some_host.as_deref(), &mut path_patterns, routes);
+	}
+
+	fn collect_subtree_routes<'r>(
+		&'r self,
+		some_host: Option<&str>,
+		path_patterns: &mut Vec<&'r Pattern>,
+		routes: &mut Vec<Route>,
+	) {
+		path_patterns.push(&self.pattern);
+
+		if self.has_some_effect() || self.some_mistargeted_request_handler.is_some() {
+			routes.push(Route::new(
+				self,
+				some_host.map(ToOwned::to_owned),
+				path_patterns,
+			));
+		}
+
+		for subresource in self
+			.static_resources
+			.iter()
+			.chain(self.regex_resources.iter())
+			.chain(self.some_wildcard_resource.as_deref())
+		{
+			subresource.collect_subtree_routes(some_host, path_patterns, routes);
+		}
+
+		path_patterns.pop();
+	}
+
+	// Sets the router's static matching rules if the resource doesn't have its own.
+	#[inline(always)]
+	pub(crate) fn inherit_static_matching(&mut self, static_matching: StaticMatching) {
+		self.some_static_matching.get_or_insert(static_matching);
+	}
+
+	#[inline(always)]
+	pub(crate) fn segment_constraints_ref(&self) -> &[RegisteredConstraint] {
+		&self.segment_constraints
+	}
+
+	// Resolves the registered constraints of the patterns in the resource tree. Each resource
+	// can use the constraints registered on itself and its parents.
+	pub(crate) fn resolve_segment_constraints<'c>(
+		&'c self,
+		constraints: &mut Vec<&'c RegisteredConstraint>,
+	) -> Result<(), RouterReloadError> {
+		let constraints_count = constraints.len();
+		constraints.extend(self.segment_constraints.iter());
+
+		if let Some(host_pattern) = self.some_host_pattern.as_ref() {
+			host_pattern.resolve_constraint(constraints)?;
+		}
+
+		for pattern in self
+			.prefix_segment_patterns
+			.iter()
+			.chain(std::iter::once(&self.pattern))
+		{
+			pattern.resolve_constraint(constraints)?;
+		}
+
+		for subresource in self
+			.static_resources
+			.iter()
+			.chain(self.regex_resources.iter())
+			.chain(self.some_wildcard_resource.as_deref())
+		{
+			subresource.resolve_segment_constraints(constraints)?;
+		}
+
+		constraints.truncate(constraints_count);
+
+		Ok(())
+	}
+
+	pub(crate) fn collect_named_routes(
+		&self,
+		named_routes: &mut NamedRoutes,
+	) -> Result<(), RouterReloadError> {
+		let mut path_patterns = self.prefix_segment_patterns.iter().collect::<Vec<_>>();
+
+		self.collect_subtree_named_routes(
+			self.some_host_pattern.as_ref(),
+			&mut path_patterns,
+			named_routes,
+		)
+	}
+
+	fn collect_subtree_named_routes<'r>(
+		&'r self,
+		some_host_pattern: Option<&Pattern>,
+		path_patterns: &mut Vec<&'r Pattern>,
+		named_routes: &mut NamedRoutes,
+	) -> Result<(), RouterReloadError> {
+		path_patterns.push(&self.pattern);
+
+		if let Some(name) = self.some_name.as_deref() {
+			named_routes.add(
+				name,
+				some_host_pattern,
+				path_patterns,
+				self.config_flags.has(ConfigFlags::ENDS_WITH_SLASH),
+			)?;
+		}
+
+		for subresource in self
+			.static_resources
+			.iter()
+			.chain(self.regex_resources.iter())
+			.chain(self.some_wildcard_resource.as_deref())
+		{
+			subresource.collect_subtree_named_routes(some_host_pattern, path_patterns, named_routes)?;
+		}
+
+		path_patterns.pop();
+
+		Ok(())
+	}
+
+	// Makes the named routes available to the requests passing through the resource.
+	#[inline(always)]
+	pub(crate) fn set_named_routes(&mut self, named_routes: NamedRoutes) {
+		if !named_routes.is_empty() {
+			self
+				.request_context_properties
+				.set_named_routes(Arc::new(named_routes));
+		}
+	}
+
+	#[inline(always)]
+	pub(crate) fn finalize(self) -> FinalResource {
+		self.finalize_with_inherited_static_matching(None)
+	}
+
+	fn finalize_with_inherited_static_matching(
+		self,
+		some_inherited_static_matching: Option<StaticMatching>,
+	) -> FinalResource {
+		let Resource {
+			pattern,
+			prefix_segment_patterns: __prefix_segment_patterns,
+			some_host_pattern: __some_host_pattern,
+			some_name: _,
+			static_resources,
+			regex_resources,
+			some_wildcard_resource,
+			method_handlers,
+			some_mistargeted_request_handler,
+			handlers_expected_path_params: _,
+			#[cfg(feature = "openapi")]
+				operations: _,
+			request_context_properties: context,
+			some_static_matching,
+			segment_constraints: _,
+			extension,
+			mut middleware,
+			config_flags,
+		} = self;
+
+		// ----------
+
+		// Local rules apply only to the resource's own static subresources.
+		let some_subtree_static_matching = match some_static_matching {
+			Some(static_matching) if !static_matching.is_local() => Some(static_matching),
+			_ => some_inherited_static_matching,
+		};
+
+		let some_static_matching = some_static_matching.or(some_inherited_static_matching);
+
+		let (some_static_resources, some_relaxed_static_matching) = if static_resources.is_empty() {
+			(None, None)
+		} else {
+			let some_relaxed_static_matching = some_static_matching
+				.filter(|static_matching| !static_matching.is_exact())
+				.map(|static_matching| {
+					RelaxedStaticMatching::new(
+						static_matching,
+						static_resources.iter().map(|resource| {
+							resource
+								.pattern
+								.static_str()
+								.expect("static_resources must keep only the resources with a static pattern")
+						}),
+					)
+				})
+				.map(Arc::new);
+
+			let static_resources = static_resources
+				.into_iter()
+				.map(|resource| {
+					let static_pattern = resource
+						.pattern
+						.static_str()
+						.expect("static_resources must keep only the resources with a static pattern")
+						.clone();
+
+					(
+						static_pattern,
+						resource.finalize_with_inherited_static_matching(some_subtree_static_matching),
+					)
+				})
+				.collect::<HashMap<_, _>>();
+
+			(
+				Some(Arc::new(static_resources)),
+				some_relaxed_static_matching,
+			)
+		};
+
+		let some_regex_resources = if regex_resources.is_empty() {
+			None
+		} else {
+			Some(
+				regex_resources
+					.into_iter()
+					.map(|resource| {
+						resource.finalize_with_inherited_static_matching(some_subtree_static_matching)
+					})
+					.collect(),
+			)
+		};
+
+		let some_wildcard_resource = some_wildcard_resource.map(|resource| {
+			Arc::new(resource.finalize_with_inherited_static_matching(some_subtree_static_matching))
+		});
+
+		// ----------
+
+		let MethodHandlers {
+			method_handlers_list,
+			wildcard_method_handler,
+			supported_methods,
+		} = method_handlers;
+
+		// -------------------------
+		// MistargetedRequestHandller
 
 		let some_mistargeted_request_handler =
 			wrap_mistargeted_request_handler(some_mistargeted_request_handler, &mut middleware)
@@ -1445,6 +1960,7 @@ impl Resource {
 		{
 			Some(ResourceRequestPasser::new(
 				some_static_resources,
+				some_relaxed_static_matching,
 				some_regex_resources,
 				some_wildcard_resource,
 				some_mistargeted_request_handler.clone(),
@@ -1483,8 +1999,31 @@ impl Resource {
 	///
 	/// This method ignores the parent resources. Thus, it should be called on the first
 	/// resource in the resource tree.
+	///
+	/// # Panics
+	///
+	/// - if some handler expects a path param that its resource's route doesn't capture
+	///   (see [`check_path_params()`](Self::check_path_params))
+	/// - if two resources in the resource tree have the same name
+	/// - if some pattern uses a segment constraint that isn't registered on its resource
+	///   or the resource's parents
 	#[inline(always)]
-	pub fn into_service(self) -> ResourceService {
+	pub fn into_service(mut self) -> ResourceService {
+		if let Err(error) = self.resolve_segment_constraints(&mut Vec::new()) {
+			panic!("{}", error)
+		}
+
+		if let Err(error) = self.check_path_params() {
+			error.panic_on_mismatches();
+		}
+
+		let mut named_routes = NamedRoutes::default();
+		if let Err(error) = self.collect_named_routes(&mut named_routes) {
+			panic!("{}", error)
+		}
+
+		self.set_named_routes(named_routes);
+
 		ResourceService::new(self.finalize())
 	}
 
@@ -2203,4 +2742,916 @@ mod test {
 			1
 		);
 	}
+
+	#[test]
+	fn resource_check_path_params() {
+		use serde::Deserialize;
+
+		use crate::request::{PathParams, RequestHead};
+
+		#[derive(Deserialize)]
+		struct Item {
+			category: String,
+			id: u32,
+		}
+
+		#[derive(Deserialize)]
+		struct Page {
+			page: Option<u32>,
+		}
+
+		#[derive(Deserialize)]
+		struct User {
+			user_id: u32,
+		}
+
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/{category:[a-z]+}/{id}")
+			.set_handler_for([
+				Method::GET.to(|_: PathParams<Item>| async {}),
+				Method::POST.to(|_: PathParams<Item>, _: PathParams<Page>| async {}),
+				Method::PUT.to(|_: RequestHead| async {}),
+			]);
+
+		root.check_path_params().unwrap();
+
+		root
+			.subresource_mut("/{category:[a-z]+}/{id}/{page}")
+			.set_handler_for(Method::GET.to(|_: PathParams<Page>| async {}));
+
+		// ----------
+
+		root
+			.subresource_mut("/users/{id}")
+			.set_handler_for(Method::GET.to(|_: PathParams<User>| async {}));
+
+		root
+			.subresource_mut("/users/{id}/posts/{post}")
+			.set_handler_for(
+				Method::GET
+					.to(|_: RequestHead| async {})
+					.expecting_path_params(["id"]),
+			);
+
+		let error = root.check_path_params().unwrap_err();
+		assert!(error.has_mismatches());
+		assert_eq!(
+			error.issues(),
+			[
+				PathParamsIssue::Mismatch {
+					route: "/users/{id}".to_string(),
+					handler: "GET".to_string(),
+					param: "user_id".to_string(),
+				},
+				PathParamsIssue::UnusedCapture {
+					route: "/users/{id}".to_string(),
+					param: "id".to_string(),
+				},
+				PathParamsIssue::UnusedCapture {
+					route: "/users/{id}/posts/{post}".to_string(),
+					param: "post".to_string(),
+				},
+				PathParamsIssue::UnusedCapture {
+					route: "/{category:[a-z]+}/{id}/{page}".to_string(),
+					param: "category".to_string(),
+				},
+				PathParamsIssue::UnusedCapture {
+					route: "/{category:[a-z]+}/{id}/{page}".to_string(),
+					param: "id".to_string(),
+				},
+			]
+		);
+
+		// ----------
+
+		let mut root = Resource::new("/");
+		root.subresource_mut("/users/{id}").set_handler_for(
+			Method::GET
+				.to(|_: PathParams<User>| async {})
+				.expecting_path_params(["id"]),
+		);
+
+		root.check_path_params().unwrap();
+		root.into_service();
+	}
+
+	#[test]
+	#[should_panic(expected = "expects a path param 'user_id'")]
+	fn resource_check_path_params_panic() {
+		use serde::Deserialize;
+
+		use crate::request::PathParams;
+
+		#[derive(Deserialize)]
+		struct User {
+			user_id: u32,
+		}
+
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/users/{id}")
+			.set_handler_for(Method::GET.to(|_: PathParams<User>| async {}));
+
+		root.into_service();
+	}
+
+	#[test]
+	#[should_panic(expected = "a wildcard method without a handler cannot expect path params")]
+	fn resource_check_path_params_wildcard_method_panic() {
+		use crate::http::WildcardMethod;
+
+		WildcardMethod
+			.to(None::<DummyHandler>)
+			.expecting_path_params(["id"]);
+	}
+
+	#[test]
+	fn resource_routes() {
+		use crate::{http::WildcardMethod, middleware::HandlerWrapper, request::MistargetedRequest};
+
+		let mut root = Resource::new("http://example.com/");
+		root.set_handler_for(Method::GET.to(DummyHandler));
+		root
+			.subresource_mut("/files/{name}.{ext:txt|md}/ ?")
+			.set_handler_for([
+				Method::GET.to(DummyHandler),
+				WildcardMethod.to(Some(DummyHandler)),
+			]);
+
+		root.subresource_mut(r"/docs *").set_handler_for([
+			Method::GET.to(DummyHandler),
+			MistargetedRequest.to(DummyHandler),
+		]);
+
+		let users = root.subresource_mut("/users/{id}");
+		users.set_name("user");
+		users.wrap([
+			RequestReceiver.component_in(RequestExtensionsModifierLayer::new(|_: &mut Extensions| {})),
+			Method::GET.handler_in(RequestExtensionsModifierLayer::new(|_: &mut Extensions| {})),
+		]);
+		users.set_handler_for(Method::GET.to(DummyHandler));
+
+		let routes = root
+			.routes()
+			.map(|route| route.to_string())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			routes,
+			[
+				"//example.com/ [GET] (handles_on_unmatching_slash)",
+				"//example.com/files/{name}.{ext:txt|md}/ [GET, *] (handles_on_unmatching_slash, \
+				ends_with_slash)",
+				"//example.com/docs [GET, mistargeted] (redirects_on_unmatching_slash, subtree_handler)",
+				"//example.com/users/{id} [GET] (redirects_on_unmatching_slash) name: user \
+				middleware: [request_receiver, method_handler(GET)]",
+			]
+		);
+
+		let route = root.routes().nth(1).unwrap();
+		assert_eq!(route.host(), Some("example.com"));
+		assert_eq!(route.path(), "/files/{name}.{ext:txt|md}/");
+		assert_eq!(route.slash_policy(), SlashPolicy::Handle);
+		assert!(route.has_wildcard_method_handler());
+		assert!(!route.has_mistargeted_request_handler());
+
+		let route = root.routes().nth(3).unwrap();
+		assert_eq!(
+			serde_json::to_value(&route).unwrap(),
+			serde_json::json!({
+				"host": "example.com",
+				"path": "/users/{id}",
+				"name": "user",
+				"ends_with_slash": false,
+				"subtree_handler": false,
+				"slash_policy": "redirect",
+				"methods": ["GET"],
+				"wildcard_method_handler": false,
+				"mistargeted_request_handler": false,
+				"middleware": ["request_receiver", "method_handler(GET)"],
+			}),
+		);
+	}
+
+	#[tokio::test]
+	async fn resource_constrained_segments() {
+		use http::StatusCode;
+
+		use crate::{request::PathParams, test_client::TestClient};
+
+		let mut root = Resource::new("/");
+		root.subresource_mut("/items/{id:@u64}").set_handler_for(
+			Method::GET.to(|PathParams(id): PathParams<u64>| async move { format!("id: {}", id) }),
+		);
+
+		root.subresource_mut("/items/{uuid:@uuid}").set_handler_for(
+			Method::GET
+				.to(|PathParams(uuid): PathParams<String>| async move { format!("uuid: {}", uuid) }),
+		);
+
+		root.subresource_mut("/items/{slug}").set_handler_for(
+			Method::GET
+				.to(|PathParams(slug): PathParams<String>| async move { format!("slug: {}", slug) }),
+		);
+
+		root
+			.subresource_mut("/days/{when:@date}")
+			.set_handler_for(Method::GET.to(|PathParams(when): PathParams<String>| async move { when }));
+
+		assert_eq!(
+			root
+				.routes()
+				.map(|route| route.path().to_owned())
+				.collect::<Vec<_>>(),
+			[
+				"/items/{id:@u64}",
+				"/items/{uuid:@uuid}",
+				"/items/{slug}",
+				"/days/{when:@date}",
+			],
+		);
+
+		root.subresource_mut("/days/{when:@date}").set_name("day");
+
+		let mut named_routes = NamedRoutes::default();
+		root.collect_named_routes(&mut named_routes).unwrap();
+
+		assert_eq!(
+			named_routes.url_for("day", &[("when", "2024-02-29")]),
+			Ok("/days/2024-02-29".to_owned()),
+		);
+
+		assert_eq!(
+			named_routes.url_for("day", &[("when", "2023-02-29")]),
+			Err(UrlForError::InvalidParam("when".to_owned())),
+		);
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let cases = [
+			("/items/42", StatusCode::OK, "id: 42"),
+			(
+				"/items/67e55044-10b1-426f-9247-bb680e5fe0c8",
+				StatusCode::OK,
+				"uuid: 67e55044-10b1-426f-9247-bb680e5fe0c8",
+			),
+			("/items/abc", StatusCode::OK, "slug: abc"),
+			(
+				"/items/18446744073709551616",
+				StatusCode::OK,
+				"slug: 18446744073709551616",
+			),
+			("/days/2024-02-29", StatusCode::OK, "2024-02-29"),
+			("/days/2024-02-30", StatusCode::NOT_FOUND, ""),
+		];
+
+		for (path, status_code, body) in cases {
+			let response = client.get(path).send().await;
+			assert_eq!(response.status(), status_code, "{}", path);
+			assert_eq!(response.text(), body, "{}", path);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "capture name 'id' is not unique in the path")]
+	fn resource_constrained_segment_duplicate_capture_name() {
+		let mut root = Resource::new("/");
+		root.subresource_mut("/{id}/items/{id:@u64}");
+	}
+
+	#[tokio::test]
+	async fn resource_registered_segment_constraints() {
+		use http::StatusCode;
+
+		use crate::{
+			common::node_properties::NodeSegmentConstraint, request::PathParams, test_client::TestClient,
+		};
+
+		struct Even;
+
+		impl SegmentConstraint for Even {
+			fn matches(&self, value: &str) -> bool {
+				value.parse::<u64>().is_ok_and(|number| number % 2 == 0)
+			}
+		}
+
+		struct Odd;
+
+		impl SegmentConstraint for Odd {
+			fn matches(&self, value: &str) -> bool {
+				value.parse::<u64>().is_ok_and(|number| number % 2 == 1)
+			}
+		}
+
+		let mut root = Resource::new("/");
+		root.set_property(NodeSegmentConstraint.to("number", Even));
+
+		root
+			.subresource_mut("/even/{n:@number}")
+			.set_handler_for(Method::GET.to(|PathParams(n): PathParams<String>| async move { n }));
+
+		// The closest registration takes precedence.
+		root
+			.subresource_mut("/odd")
+			.set_property(NodeSegmentConstraint.to("number", Odd));
+
+		root
+			.subresource_mut("/odd/{n:@number}")
+			.set_handler_for(Method::GET.to(|PathParams(n): PathParams<String>| async move { n }));
+
+		// Without '@', the subpattern is a regex even if it equals a constraint name.
+		root
+			.subresource_mut("/word/{w:date}")
+			.set_handler_for(Method::GET.to(|PathParams(w): PathParams<String>| async move { w }));
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let cases = [
+			("/even/42", StatusCode::OK, "42"),
+			("/even/43", StatusCode::NOT_FOUND, ""),
+			("/odd/43", StatusCode::OK, "43"),
+			("/odd/42", StatusCode::NOT_FOUND, ""),
+			("/word/date", StatusCode::OK, "date"),
+			("/word/2024-02-29", StatusCode::NOT_FOUND, ""),
+		];
+
+		for (path, status_code, body) in cases {
+			let response = client.get(path).send().await;
+			assert_eq!(response.status(), status_code, "{}", path);
+			assert_eq!(response.text(), body, "{}", path);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "unknown segment constraint 'number'")]
+	fn resource_unregistered_segment_constraint() {
+		use crate::common::node_properties::NodeSegmentConstraint;
+
+		struct Any;
+
+		impl SegmentConstraint for Any {
+			fn matches(&self, _: &str) -> bool {
+				true
+			}
+		}
+
+		let mut root = Resource::new("/");
+		root.subresource_mut("/a/{n:@number}");
+
+		// The constraint registered on a sibling isn't visible.
+		root
+			.subresource_mut("/b")
+			.set_property(NodeSegmentConstraint.to("number", Any));
+
+		let _ = root.into_service();
+	}
+
+	#[tokio::test]
+	async fn resource_catch_all_segments() {
+		use http::{header::LOCATION, StatusCode};
+
+		use crate::{request::PathParams, test_client::TestClient};
+
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/files/{*path}")
+			.set_handler_for(Method::GET.to(|PathParams(path): PathParams<String>| async move { path }));
+
+		root
+			.subresource_mut("/files/readme")
+			.set_handler_for(Method::GET.to(|| async { "readme" }));
+
+		root
+			.subresource_mut("/images/{*path:.*\\.png}/")
+			.set_handler_for(Method::GET.to(|PathParams(path): PathParams<String>| async move { path }));
+
+		root.subresource_mut("/files/{*path}").set_name("file");
+
+		assert_eq!(
+			root
+				.routes()
+				.map(|route| route.path().to_owned())
+				.collect::<Vec<_>>(),
+			[
+				"/files/readme",
+				"/files/{*path}",
+				"/images/{*path:.*\\.png}/"
+			],
+		);
+
+		let mut named_routes = NamedRoutes::default();
+		root.collect_named_routes(&mut named_routes).unwrap();
+
+		assert_eq!(
+			named_routes.url_for("file", &[("path", "docs/a b.txt")]),
+			Ok("/files/docs/a%20b.txt".to_owned()),
+		);
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let cases = [
+			("/files/readme", StatusCode::OK, "readme"),
+			("/files/docs/a%20b.txt", StatusCode::OK, "docs/a b.txt"),
+			("/files/docs", StatusCode::OK, "docs"),
+			("/images/x/y.png/", StatusCode::OK, "x/y.png"),
+			("/images/x/y.jpg/", StatusCode::NOT_FOUND, ""),
+		];
+
+		for (path, status_code, body) in cases {
+			let response = client.get(path).send().await;
+			assert_eq!(response.status(), status_code, "{}", path);
+			assert_eq!(response.text(), body, "{}", path);
+		}
+
+		let response = client.get("/files/docs/").send().await;
+		assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+		assert_eq!(response.header(LOCATION.as_str()), Some("/files/docs"));
+
+		let response = client.get("/images/x/y.png").send().await;
+		assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+		assert_eq!(response.header(LOCATION.as_str()), Some("/images/x/y.png/"));
+
+		// ----------
+
+		let mut catch_all = Resource::new("/{*path}");
+		catch_all
+			.set_handler_for(Method::GET.to(|PathParams(path): PathParams<String>| async move { path }));
+
+		let client = TestClient::new(catch_all.into_arc_service());
+
+		let response = client.get("/a/b/c").send().await;
+		assert_eq!(response.text(), "a/b/c");
+	}
+
+	#[tokio::test]
+	async fn resource_invalid_path_encoding() {
+		use http::StatusCode;
+
+		use crate::test_client::TestClient;
+
+		let mut root = Resource::new("/");
+		root
+			.subresource_mut("/files/{*path}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		root
+			.subresource_mut("/items/{id}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		let client = TestClient::new(root.into_arc_service());
+
+		for path in ["/files/a/%FF", "/items/%FF"] {
+			let response = client.get(path).send().await;
+			assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", path);
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "a catch-all must be the last segment of the path pattern")]
+	fn resource_catch_all_subresource() {
+		let mut root = Resource::new("/");
+		root.subresource_mut("/files/{*path}/info");
+	}
+
+	#[tokio::test]
+	async fn resource_static_matching() {
+		use http::{header::LOCATION, StatusCode};
+
+		use crate::{
+			common::node_properties::{NodeStaticMatching, StaticMatching},
+			test_client::TestClient,
+		};
+
+		let mut root = Resource::new("/");
+		root.set_property(NodeStaticMatching.to(StaticMatching::exact().case_insensitive()));
+
+		root
+			.subresource_mut("/users/profile")
+			.set_handler_for(Method::GET.to(|| async { "profile" }));
+
+		// Local rules don't affect the subtree, which keeps the inherited rules.
+		root
+			.subresource_mut("/Docs")
+			.set_property(NodeStaticMatching.to(StaticMatching::exact().local()));
+
+		root
+			.subresource_mut("/Docs/Intro")
+			.set_handler_for(Method::GET.to(|| async { "intro" }));
+
+		root
+			.subresource_mut("/Docs/Intro/Exact")
+			.set_property(NodeStaticMatching.to(StaticMatching::exact()));
+
+		root
+			.subresource_mut("/Docs/Intro/Exact/Page")
+			.set_handler_for(Method::GET.to(|| async { "page" }));
+
+		root.subresource_mut("/Old").set_property(
+			NodeStaticMatching.to(
+				StaticMatching::exact()
+					.case_insensitive()
+					.redirects_to_canonical_path(),
+			),
+		);
+
+		root
+			.subresource_mut("/Old/Path")
+			.set_handler_for(Method::GET.to(|| async { "path" }));
+
+		root
+			.subresource_mut("/Old/Users/Profile/Settings")
+			.set_handler_for(Method::GET.to(|| async { "settings" }));
+
+		root
+			.subresource_mut("/Old/Files *")
+			.set_handler_for(Method::GET.to(|| async { "files" }));
+
+		root.subresource_mut("/Old/Files/Images");
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let cases = [
+			("/users/profile", StatusCode::OK, "profile"),
+			("/Users/Profile", StatusCode::OK, "profile"),
+			("/USERS/profile", StatusCode::OK, "profile"),
+			("/docs/Intro", StatusCode::OK, "intro"),
+			("/Docs/intro", StatusCode::NOT_FOUND, ""),
+			("/docs/Intro/exact/Page", StatusCode::OK, "page"),
+			("/Docs/Intro/Exact/page", StatusCode::NOT_FOUND, ""),
+			("/old/Path", StatusCode::OK, "path"),
+		];
+
+		for (path, status_code, body) in cases {
+			let response = client.get(path).send().await;
+			assert_eq!(response.status(), status_code, "{}", path);
+			assert_eq!(response.text(), body, "{}", path);
+		}
+
+		let response = client.get("/Old/path?q=1").send().await;
+		assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+		assert_eq!(response.header(LOCATION.as_str()), Some("/Old/Path?q=1"));
+
+		// The methods other than GET and HEAD are kept by the client.
+		let response = client.post("/Old/path").send().await;
+		assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+		assert_eq!(response.header(LOCATION.as_str()), Some("/Old/Path"));
+
+		// All the segments are replaced in a single redirect. The root's rules don't
+		// redirect, so its subresource's segment is kept.
+		let response = client.get("/old/users/profile/settings?q=1").send().await;
+		assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+		assert_eq!(
+			response.header(LOCATION.as_str()),
+			Some("/old/Users/Profile/Settings?q=1")
+		);
+
+		let response = client.get("/Old/Users/profile/SETTINGS").send().await;
+		assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+		assert_eq!(
+			response.header(LOCATION.as_str()),
+			Some("/Old/Users/Profile/Settings")
+		);
+
+		// The segments below the subtree handler that handles the request are kept.
+		let response = client.get("/old/files/images/a.png").send().await;
+		assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+		assert_eq!(
+			response.header(LOCATION.as_str()),
+			Some("/old/Files/images/a.png")
+		);
+
+		// Requests to non-existent resources are not redirected.
+		let response = client.get("/old/users/unknown").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[cfg(feature = "unicode-normalization")]
+	#[tokio::test]
+	async fn resource_static_matching_nfc_normalized() {
+		use http::{header::LOCATION, StatusCode};
+
+		use crate::{
+			common::node_properties::{NodeStaticMatching, StaticMatching},
+			test_client::TestClient,
+		};
+
+		let mut root = Resource::new("/");
+		root.set_property(
+			NodeStaticMatching.to(
+				StaticMatching::exact()
+					.nfc_normalized()
+					.redirects_to_canonical_path(),
+			),
+		);
+
+		// "café" with a precomposed "é".
+		root
+			.subresource_mut("/café")
+			.set_handler_for(Method::GET.to(|| async { "café" }));
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let response = client.get("/caf%C3%A9").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "café");
+
+		// "café" with a decomposed "é".
+		let response = client.get("/cafe%CC%81").send().await;
+		assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+		assert_eq!(response.header(LOCATION.as_str()), Some("/caf%C3%A9"));
+
+		let response = client.get("/CAF%C3%89").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[test]
+	#[should_panic(
+		expected = "static patterns 'users' and 'Users' are the same after the normalization"
+	)]
+	fn resource_static_matching_same_normalized_patterns() {
+		use crate::common::node_properties::{NodeStaticMatching, StaticMatching};
+
+		let mut root = Resource::new("/");
+		root.set_property(NodeStaticMatching.to(StaticMatching::exact().case_insensitive()));
+		root.subresource_mut("/users");
+		root.subresource_mut("/Users");
+
+		let _ = root.into_service();
+	}
+
+	#[tokio::test]
+	async fn resource_mounted_service() {
+		use std::{
+			convert::Infallible,
+			future::{ready, Ready},
+			task::{Context, Poll},
+		};
+
+		use http::StatusCode;
+
+		use crate::{request::OriginalUri, test_client::TestClient};
+
+		#[derive(Clone)]
+		struct UriEcho;
+
+		impl TowerService<Request<Body>> for UriEcho {
+			type Response = Response<String>;
+			type Error = Infallible;
+			type Future = Ready<Result<Self::Response, Self::Error>>;
+
+			fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+				Poll::Ready(Ok(()))
+			}
+
+			fn call(&mut self, request: Request<Body>) -> Self::Future {
+				let OriginalUri(original_uri) = request.extensions().get::<OriginalUri>().unwrap();
+
+				ready(Ok(Response::new(format!(
+					"{} {} {}",
+					request.method(),
+					request.uri(),
+					original_uri,
+				))))
+			}
+		}
+
+		let mut root = Resource::new("/");
+		root.mount_service("/internal/{tenant}", UriEcho);
+
+		root
+			.subresource_mut("/internal/{tenant}/own")
+			.set_handler_for(Method::GET.to(|| async { "own" }));
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let cases = [
+			(Method::GET, "/internal/a", "GET / /internal/a"),
+			(Method::GET, "/internal/a/", "GET / /internal/a/"),
+			(
+				Method::POST,
+				"/internal/a/metrics/requests?b=1",
+				"POST /metrics/requests?b=1 /internal/a/metrics/requests?b=1",
+			),
+			(Method::GET, "/internal/a/own", "own"),
+			(
+				Method::GET,
+				"/internal/a/own/x/",
+				"GET /own/x/ /internal/a/own/x/",
+			),
+		];
+
+		for (method, path, body) in cases {
+			let response = client.request(method, path).send().await;
+			assert_eq!(response.status(), StatusCode::OK, "{}", path);
+			assert_eq!(response.text(), body, "{}", path);
+		}
+
+		let response = client.get("/internal").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[test]
+	#[should_panic(expected = "the path of the mounted service cannot have config symbols")]
+	fn resource_mounted_service_with_config_symbols() {
+		use crate::handler::{HandlerService, IntoHandler};
+
+		let mut root = Resource::new("/");
+		root.mount_service(
+			"/internal *",
+			HandlerService::from((|| async {}).into_handler()),
+		);
+	}
+
+	#[test]
+	fn resource_url_for() {
+		let mut root = Resource::new("http://{sub:[a-z]+}.example.com/");
+		root.set_name("root");
+		root.subresource_mut("/users/{id}").set_name("user");
+		root
+			.subresource_mut("/users/{id}/files/{file_name}.{ext:txt|md}/")
+			.set_name("file");
+
+		root.subresource_mut("/{{static}}/posts").set_name("posts");
+
+		let mut named_routes = NamedRoutes::default();
+		root.collect_named_routes(&mut named_routes).unwrap();
+
+		let cases = [
+			("root", vec![("sub", "abc")], Ok("//abc.example.com/")),
+			(
+				"user",
+				vec![("sub", "abc"), ("id", "a b/c")],
+				Ok("//abc.example.com/users/a%20b%2Fc"),
+			),
+			(
+				"file",
+				vec![
+					("sub", "abc"),
+					("id", "1"),
+					("file_name", "notes"),
+					("ext", "md"),
+				],
+				Ok("//abc.example.com/users/1/files/notes.md/"),
+			),
+			(
+				"posts",
+				vec![("sub", "abc")],
+				Ok("//abc.example.com/{static}/posts"),
+			),
+			(
+				"unknown",
+				vec![],
+				Err(UrlForError::UnknownName("unknown".to_string())),
+			),
+			(
+				"user",
+				vec![("sub", "abc")],
+				Err(UrlForError::MissingParam("id".to_string())),
+			),
+			(
+				"user",
+				vec![("sub", "ABC"), ("id", "1")],
+				Err(UrlForError::InvalidParam("sub".to_string())),
+			),
+			(
+				"file",
+				vec![
+					("sub", "abc"),
+					("id", "1"),
+					("file_name", "notes"),
+					("ext", "pdf"),
+				],
+				Err(UrlForError::InvalidParam("ext".to_string())),
+			),
+		];
+
+		for (name, params, expected) in cases {
+			let result = named_routes.url_for(name, &params);
+			assert_eq!(
+				result.as_deref(),
+				expected.as_ref().map(|url| *url),
+				"{}",
+				name
+			);
+		}
+	}
+
+	#[test]
+	fn resource_url_for_host_values() {
+		let mut tenant_root = Resource::new("http://{tenant}.example.com/");
+		tenant_root
+			.subresource_mut("/users")
+			.set_name("tenant_users");
+
+		let mut any_host_root = Resource::new("http://{host:.+}/");
+		any_host_root
+			.subresource_mut("/users")
+			.set_name("any_host_users");
+
+		let mut static_host_root = Resource::new("http://example.com/");
+		static_host_root
+			.subresource_mut("/users")
+			.set_name("static_host_users");
+
+		let mut named_routes = NamedRoutes::default();
+		tenant_root.collect_named_routes(&mut named_routes).unwrap();
+		any_host_root
+			.collect_named_routes(&mut named_routes)
+			.unwrap();
+		static_host_root
+			.collect_named_routes(&mut named_routes)
+			.unwrap();
+
+		let invalid_tenant = || Err(UrlForError::InvalidParam("tenant".to_string()));
+		let invalid_host = || Err(UrlForError::InvalidParam("host".to_string()));
+
+		let cases = [
+			(
+				"tenant_users",
+				("tenant", "acme-1"),
+				Ok("//acme-1.example.com/users"),
+			),
+			("tenant_users", ("tenant", "evil%2ecom/"), invalid_tenant()),
+			("tenant_users", ("tenant", "evil.com"), invalid_tenant()),
+			("tenant_users", ("tenant", "evil.com/x?"), invalid_tenant()),
+			("tenant_users", ("tenant", "user@evil"), invalid_tenant()),
+			(
+				"any_host_users",
+				("host", "api.example.com"),
+				Ok("//api.example.com/users"),
+			),
+			("any_host_users", ("host", "evil.com/"), invalid_host()),
+			("any_host_users", ("host", "evil.com:8080"), invalid_host()),
+			("any_host_users", ("host", "a@evil.com"), invalid_host()),
+			("any_host_users", ("host", "evil%2ecom"), invalid_host()),
+			(
+				"static_host_users",
+				("host", "evil.com"),
+				Ok("//example.com/users"),
+			),
+		];
+
+		for (name, param, expected) in cases {
+			let result = named_routes.url_for(name, &[param]);
+			assert_eq!(
+				result.as_deref(),
+				expected.as_ref().map(|url| *url),
+				"{}: {}",
+				name,
+				param.1,
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn resource_service_url_for() {
+		use crate::{request::RequestHead, test_client::TestClient, Host, Router};
+
+		let mut root = Resource::new("/");
+		root.subresource_mut("/users/{id}").set_name("user");
+		root.subresource_mut("/profile").set_handler_for(
+			Method::GET
+				.to(|head: RequestHead| async move { head.url_for("user", &[("id", "42")]).unwrap() }),
+		);
+
+		let mut host_root = Resource::new("/");
+		host_root.subresource_mut("/docs").set_name("docs");
+
+		let mut router = Router::new();
+		router.add_resource(root);
+		router.add_host(Host::new("example.com", host_root));
+
+		let client = TestClient::new(router.into_arc_service());
+
+		let response = client.get("/profile").send().await;
+		assert_eq!(response.text(), "/users/42");
+
+		// ----------
+
+		let mut root = Resource::new("/");
+		root.subresource_mut("/users/{id}").set_name("user");
+		root
+			.subresource_mut("/profile")
+			.set_handler_for(Method::GET.to(|head: RequestHead| async move {
+				head
+					.url_for("user", &[("name", "42")])
+					.unwrap_err()
+					.to_string()
+			}));
+
+		let client = TestClient::new(root.into_arc_service());
+
+		let response = client.get("/profile").send().await;
+		assert_eq!(response.text(), "missing param 'id'");
+	}
+
+	#[test]
+	#[should_panic(expected = "duplicate resource name 'user'")]
+	fn resource_url_for_duplicate_name() {
+		let mut root = Resource::new("/");
+		root.subresource_mut("/users/{id}").set_name("user");
+		root.subresource_mut("/user").set_name("user");
+
+		root.into_service();
+	}
 }
@@ -0,0 +1,79 @@
+use argan_core::{
+	body::{Body, Bytes, HttpBody},
+	BoxedError, BoxedFuture,
+};
+use http::{uri::PathAndQuery, Request, Uri};
+use tower_service::Service as TowerService;
+
+use crate::{
+	handler::{futures::ResponseBodyAdapterFuture, Args, Handler},
+	request::{OriginalUri, RequestContext},
+	response::{BoxedErrorResponse, Response},
+};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// MountedService
+
+// A handler that calls the tower service with the resource's path stripped from
+// the request's URI.
+#[derive(Clone)]
+pub(super) struct MountedService<S>(S);
+
+impl<S> MountedService<S> {
+	#[inline(always)]
+	pub(super) fn new(service: S) -> Self {
+		Self(service)
+	}
+}
+
+impl<S, B> Handler for MountedService<S>
+where
+	S: TowerService<Request<Body>, Response = Response<B>> + Clone,
+	S::Error: Into<BoxedErrorResponse>,
+	S::Future: Send + 'static,
+	B: HttpBody<Data = Bytes> + Send + Sync + 'static,
+	B::Error: Into<BoxedError>,
+{
+	type Response = Response;
+	type Error = BoxedErrorResponse;
+	type Future = BoxedFuture<Result<Response, BoxedErrorResponse>>;
+
+	fn handle(&self, request_context: RequestContext, _args: Args) -> Self::Future {
+		let uri = stripped_uri(
+			request_context.uri_ref(),
+			request_context.subtree_path_segments(),
+		);
+
+		let mut request = request_context.into_request();
+		let original_uri = std::mem::replace(request.uri_mut(), uri);
+		request.extensions_mut().insert(OriginalUri(original_uri));
+
+		let future_response_result = self.0.clone().call(request);
+
+		Box::pin(ResponseBodyAdapterFuture::from(future_response_result))
+	}
+}
+
+// Returns the URI with the given remaining path segments as its path.
+fn stripped_uri(uri: &Uri, remaining_path_segments: &str) -> Uri {
+	let mut path_and_query =
+		String::with_capacity(uri.path_and_query().map_or(1, |pq| pq.as_str().len()));
+	path_and_query.push('/');
+	path_and_query.push_str(remaining_path_segments);
+
+	if let Some(query) = uri.query() {
+		path_and_query.push('?');
+		path_and_query.push_str(query);
+	}
+
+	let mut uri_parts = uri.clone().into_parts();
+	uri_parts.path_and_query = Some(
+		PathAndQuery::try_from(path_and_query)
+			.expect("stripped path and query must be valid since they're part of a valid URI"),
+	);
+
+	Uri::from_parts(uri_parts).expect("stripped URI must be valid since it's built from a valid URI")
+}
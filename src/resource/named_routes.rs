@@ -0,0 +1,101 @@
+use crate::{pattern::Pattern, router::RouterReloadError};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// NamedRoutes
+
+// Routes of the named resources in the resource tree(s) of a service.
+#[derive(Debug, Default)]
+pub(crate) struct NamedRoutes(Vec<(Box<str>, NamedRoute)>);
+
+#[derive(Debug)]
+struct NamedRoute {
+	some_host_pattern: Option<Pattern>,
+	path_patterns: Vec<Pattern>,
+	ends_with_slash: bool,
+}
+
+impl NamedRoutes {
+	#[inline(always)]
+	pub(crate) fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	pub(super) fn add(
+		&mut self,
+		name: &str,
+		some_host_pattern: Option<&Pattern>,
+		path_patterns: &[&Pattern],
+		ends_with_slash: bool,
+	) -> Result<(), RouterReloadError> {
+		if self
+			.0
+			.iter()
+			.any(|(existing_name, _)| existing_name.as_ref() == name)
+		{
+			return Err(RouterReloadError::DuplicateResourceName(name.into()));
+		}
+
+		let named_route = NamedRoute {
+			some_host_pattern: some_host_pattern.cloned(),
+			path_patterns: path_patterns
+				.iter()
+				.filter(|pattern| pattern.is_static_match("/") != Some(true))
+				.map(|pattern| (*pattern).clone())
+				.collect(),
+			ends_with_slash,
+		};
+
+		self.0.push((name.into(), named_route));
+
+		Ok(())
+	}
+
+	pub(crate) fn url_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, UrlForError> {
+		let Some((_, named_route)) = self
+			.0
+			.iter()
+			.find(|(route_name, _)| route_name.as_ref() == name)
+		else {
+			return Err(UrlForError::UnknownName(name.to_owned()));
+		};
+
+		let mut url = String::new();
+
+		if let Some(host_pattern) = named_route.some_host_pattern.as_ref() {
+			url.push_str("//");
+			url.push_str(&host_pattern.fill_host(params)?);
+		}
+
+		for pattern in named_route.path_patterns.iter() {
+			url.push('/');
+			url.push_str(&pattern.fill_path_segment(params)?);
+		}
+
+		if named_route.path_patterns.is_empty() || named_route.ends_with_slash {
+			url.push('/');
+		}
+
+		Ok(url)
+	}
+}
+
+// --------------------------------------------------
+// UrlForError
+
+/// An error that's returned when the URL of a named resource cannot be generated.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, crate::ImplError)]
+pub enum UrlForError {
+	/// No resource with the name exists.
+	#[error("unknown resource name '{0}'")]
+	UnknownName(String),
+	/// The value of a param that the resource's route captures is missing.
+	#[error("missing param '{0}'")]
+	MissingParam(String),
+	/// The value of a param doesn't match its pattern.
+	#[error("param '{0}' doesn't match its pattern")]
+	InvalidParam(String),
+}
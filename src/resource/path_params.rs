@@ -0,0 +1,96 @@
+use std::fmt::{self, Display};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// PathParamsIssue
+
+/// An issue found when checking the path params expected by handlers against the
+/// params captured by their resources' routes.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, crate::ImplError)]
+pub enum PathParamsIssue {
+	/// The handler expects a param that no pattern in the resource's route captures.
+	#[error("{handler} handler of the resource '{route}' expects a path param '{param}' that the route doesn't capture")]
+	Mismatch {
+		route: String,
+		handler: String,
+		param: String,
+	},
+	/// The resource's route captures a param that none of its handlers expects.
+	#[error("resource '{route}' captures a path param '{param}' that none of its handlers expects")]
+	UnusedCapture { route: String, param: String },
+}
+
+impl PathParamsIssue {
+	/// Returns `true` if the issue is a mismatch.
+	#[inline(always)]
+	pub fn is_mismatch(&self) -> bool {
+		matches!(self, Self::Mismatch { .. })
+	}
+}
+
+// --------------------------------------------------
+// PathParamsCheckError
+
+/// An error that's returned from the path params check of the resource tree.
+#[derive(Debug, Clone)]
+pub struct PathParamsCheckError(Vec<PathParamsIssue>);
+
+impl PathParamsCheckError {
+	#[inline(always)]
+	pub(crate) fn from_issues(issues: Vec<PathParamsIssue>) -> Result<(), Self> {
+		if issues.is_empty() {
+			Ok(())
+		} else {
+			Err(Self(issues))
+		}
+	}
+
+	/// Returns all the found issues.
+	#[inline(always)]
+	pub fn issues(&self) -> &[PathParamsIssue] {
+		&self.0
+	}
+
+	/// Returns `true` if any of the handlers expects a param that its route doesn't capture.
+	#[inline(always)]
+	pub fn has_mismatches(&self) -> bool {
+		self.0.iter().any(PathParamsIssue::is_mismatch)
+	}
+
+	// Called when the resource tree is being converted into a service.
+	pub(crate) fn panic_on_mismatches(self) {
+		if let Err(error) = self.into_mismatches() {
+			panic!("{}", error)
+		}
+	}
+
+	// Keeps only the mismatches. Returns `Ok` if there are none.
+	pub(crate) fn into_mismatches(self) -> Result<(), Self> {
+		let mismatches = self
+			.0
+			.into_iter()
+			.filter(PathParamsIssue::is_mismatch)
+			.collect();
+
+		Self::from_issues(mismatches)
+	}
+}
+
+impl Display for PathParamsCheckError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, issue) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str("\n")?;
+			}
+
+			Display::fmt(issue, f)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl std::error::Error for PathParamsCheckError {}
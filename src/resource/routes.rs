@@ -0,0 +1,370 @@
+use std::{
+	borrow::Cow,
+	fmt::{self, Display},
+};
+
+use http::Method;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{middleware::targets::LayerTarget, pattern::Pattern};
+
+#[cfg(feature = "openapi")]
+use crate::openapi::Operation;
+
+use super::{config::ConfigFlags, Resource};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// Route
+
+/// Information about a route of the resource tree and the resource that handles it.
+///
+/// Routes are listed by [`Resource::routes()`](super::Resource::routes) and
+/// [`Router::routes()`](crate::Router::routes). The `Display` implementation writes
+/// the route on a single line, and the `Serialize` implementation can be used to get
+/// a machine-readable dump.
+///
+/// ```
+/// use argan::{Resource, handler::HandlerSetter, http::Method};
+///
+/// let mut root = Resource::new("/");
+/// root
+///   .subresource_mut("/users/{id}/")
+///   .set_handler_for([Method::GET.to(|| async {}), Method::PUT.to(|| async {})]);
+///
+/// let route = root.routes().next().unwrap();
+/// assert_eq!(route.path(), "/users/{id}/");
+/// assert_eq!(route.methods(), [Method::GET, Method::PUT]);
+/// assert!(route.ends_with_slash());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+	some_host: Option<String>,
+	path: String,
+	some_name: Option<String>,
+	ends_with_slash: bool,
+	subtree_handler: bool,
+	slash_policy: SlashPolicy,
+	methods: Vec<Method>,
+	wildcard_method_handler: bool,
+	mistargeted_request_handler: bool,
+	middleware: Vec<LayerTargetKind>,
+
+	#[cfg(feature = "openapi")]
+	path_template: String,
+	#[cfg(feature = "openapi")]
+	path_params: Vec<(String, Option<String>)>,
+	#[cfg(feature = "openapi")]
+	operations: Vec<(Method, Operation)>,
+}
+
+impl Route {
+	pub(super) fn new(
+		resource: &Resource,
+		some_host: Option<String>,
+		path_patterns: &[&Pattern],
+	) -> Self {
+		let config_flags = &resource.config_flags;
+
+		let path_patterns = path_patterns
+			.iter()
+			.filter(|pattern| pattern.is_static_match("/") != Some(true))
+			.collect::<Vec<_>>();
+
+		let ends_with_slash = config_flags.has(ConfigFlags::ENDS_WITH_SLASH);
+
+		let join_path = |segment: fn(&Pattern) -> Cow<'_, str>| {
+			let mut path = String::new();
+			for pattern in path_patterns.iter() {
+				path.push('/');
+				path.push_str(&segment(pattern));
+			}
+
+			if path.is_empty() || ends_with_slash {
+				path.push('/');
+			}
+
+			path
+		};
+
+		let slash_policy = if config_flags.has(ConfigFlags::REDIRECTS_ON_UNMATCHING_SLASH) {
+			SlashPolicy::Redirect
+		} else if config_flags.has(ConfigFlags::DROPS_ON_UNMATCHING_SLASH) {
+			SlashPolicy::NotFound
+		} else {
+			SlashPolicy::Handle
+		};
+
+		Self {
+			some_host,
+			path: join_path(Pattern::pattern_str),
+			some_name: resource.some_name.as_deref().map(ToOwned::to_owned),
+			ends_with_slash,
+			subtree_handler: config_flags.has(ConfigFlags::SUBTREE_HANDLER),
+			slash_policy,
+			methods: resource
+				.method_handlers
+				.method_handlers_list
+				.iter()
+				.map(|(method, _)| method.clone())
+				.collect(),
+			wildcard_method_handler: resource
+				.method_handlers
+				.has_custom_wildcard_method_handler(),
+			mistargeted_request_handler: resource.some_mistargeted_request_handler.is_some(),
+			middleware: resource
+				.middleware
+				.iter()
+				.filter_map(LayerTargetKind::from_layer_target)
+				.collect(),
+
+			#[cfg(feature = "openapi")]
+			path_template: join_path(Pattern::template_str),
+			#[cfg(feature = "openapi")]
+			path_params: path_patterns
+				.iter()
+				.flat_map(|pattern| pattern.capture_subpatterns())
+				.map(|(name, some_subpattern)| (name.to_owned(), some_subpattern.map(ToOwned::to_owned)))
+				.collect(),
+			#[cfg(feature = "openapi")]
+			operations: resource.operations.clone(),
+		}
+	}
+
+	/// Returns the host pattern of the route if the resource belongs to a host.
+	#[inline(always)]
+	pub fn host(&self) -> Option<&str> {
+		self.some_host.as_deref()
+	}
+
+	/// Returns the path pattern of the route.
+	#[inline(always)]
+	pub fn path(&self) -> &str {
+		&self.path
+	}
+
+	/// Returns the name of the resource if it has one.
+	#[inline(always)]
+	pub fn name(&self) -> Option<&str> {
+		self.some_name.as_deref()
+	}
+
+	/// Returns `true` if the path pattern ends with a slash `/`.
+	#[inline(always)]
+	pub fn ends_with_slash(&self) -> bool {
+		self.ends_with_slash
+	}
+
+	/// Returns `true` if the resource is a subtree handler.
+	#[inline(always)]
+	pub fn is_subtree_handler(&self) -> bool {
+		self.subtree_handler
+	}
+
+	/// Returns how the resource treats the requests with an unmatching trailing slash.
+	#[inline(always)]
+	pub fn slash_policy(&self) -> SlashPolicy {
+		self.slash_policy
+	}
+
+	/// Returns the methods that have a handler, in the order they were set.
+	#[inline(always)]
+	pub fn methods(&self) -> &[Method] {
+		&self.methods
+	}
+
+	/// Returns `true` if a custom wildcard method handler is set.
+	#[inline(always)]
+	pub fn has_wildcard_method_handler(&self) -> bool {
+		self.wildcard_method_handler
+	}
+
+	/// Returns `true` if a custom mistargeted request handler is set.
+	#[inline(always)]
+	pub fn has_mistargeted_request_handler(&self) -> bool {
+		self.mistargeted_request_handler
+	}
+
+	/// Returns the targets of the middleware that the resource was wrapped in.
+	#[inline(always)]
+	pub fn middleware(&self) -> &[LayerTargetKind] {
+		&self.middleware
+	}
+
+	// Returns the path with its captures written as `{name}`.
+	#[cfg(feature = "openapi")]
+	#[inline(always)]
+	pub(crate) fn path_template(&self) -> &str {
+		&self.path_template
+	}
+
+	// Returns the names of the path params with their regex subpatterns or constraints.
+	#[cfg(feature = "openapi")]
+	#[inline(always)]
+	pub(crate) fn path_params(&self) -> &[(String, Option<String>)] {
+		&self.path_params
+	}
+
+	#[cfg(feature = "openapi")]
+	#[inline(always)]
+	pub(crate) fn operations(&self) -> &[(Method, Operation)] {
+		&self.operations
+	}
+}
+
+impl Display for Route {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(host) = self.some_host.as_deref() {
+			write!(f, "//{}", host)?;
+		}
+
+		f.write_str(&self.path)?;
+
+		let mut handlers = self
+			.methods
+			.iter()
+			.map(Method::to_string)
+			.collect::<Vec<_>>();
+
+		if self.wildcard_method_handler {
+			handlers.push("*".to_owned());
+		}
+
+		if self.mistargeted_request_handler {
+			handlers.push("mistargeted".to_owned());
+		}
+
+		write!(f, " [{}] ({}", handlers.join(", "), self.slash_policy)?;
+
+		if self.ends_with_slash {
+			f.write_str(", ends_with_slash")?;
+		}
+
+		if self.subtree_handler {
+			f.write_str(", subtree_handler")?;
+		}
+
+		f.write_str(")")?;
+
+		if let Some(name) = self.some_name.as_deref() {
+			write!(f, " name: {}", name)?;
+		}
+
+		if !self.middleware.is_empty() {
+			let middleware = self
+				.middleware
+				.iter()
+				.map(LayerTargetKind::to_string)
+				.collect::<Vec<_>>();
+
+			write!(f, " middleware: [{}]", middleware.join(", "))?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Serialize for Route {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let methods = self.methods.iter().map(Method::as_str).collect::<Vec<_>>();
+
+		let mut state = serializer.serialize_struct("Route", 10)?;
+		state.serialize_field("host", &self.some_host)?;
+		state.serialize_field("path", &self.path)?;
+		state.serialize_field("name", &self.some_name)?;
+		state.serialize_field("ends_with_slash", &self.ends_with_slash)?;
+		state.serialize_field("subtree_handler", &self.subtree_handler)?;
+		state.serialize_field("slash_policy", &self.slash_policy)?;
+		state.serialize_field("methods", &methods)?;
+		state.serialize_field("wildcard_method_handler", &self.wildcard_method_handler)?;
+		state.serialize_field(
+			"mistargeted_request_handler",
+			&self.mistargeted_request_handler,
+		)?;
+		state.serialize_field("middleware", &self.middleware)?;
+		state.end()
+	}
+}
+
+// --------------------------------------------------
+// SlashPolicy
+
+/// A way the resource treats the requests whose path has an unmatching trailing slash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashPolicy {
+	/// The requests are redirected to the resource's path.
+	Redirect,
+	/// The requests get a `404 Not Found` response.
+	NotFound,
+	/// The requests are handled.
+	Handle,
+}
+
+impl Display for SlashPolicy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Redirect => f.write_str("redirects_on_unmatching_slash"),
+			Self::NotFound => f.write_str("drops_on_unmatching_slash"),
+			Self::Handle => f.write_str("handles_on_unmatching_slash"),
+		}
+	}
+}
+
+// --------------------------------------------------
+// LayerTargetKind
+
+/// A component of the resource that was wrapped in a middleware.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayerTargetKind {
+	/// The *request receiver*.
+	RequestReceiver,
+	/// The *request passer*.
+	RequestPasser,
+	/// The *request handler*.
+	RequestHandler,
+	/// The handler of the method.
+	MethodHandler(Method),
+	/// The wildcard method handler.
+	WildcardMethodHandler,
+	/// The mistargeted request handler.
+	MistargetedRequestHandler,
+}
+
+impl LayerTargetKind {
+	pub(crate) fn from_layer_target<Mark>(layer_target: &LayerTarget<Mark>) -> Option<Self> {
+		let kind = match layer_target {
+			LayerTarget::None(_) => return None,
+			LayerTarget::RequestReceiver(_) => Self::RequestReceiver,
+			LayerTarget::RequestPasser(_) => Self::RequestPasser,
+			LayerTarget::RequestHandler(_) => Self::RequestHandler,
+			LayerTarget::MethodHandler(method, _) => Self::MethodHandler(method.clone()),
+			LayerTarget::WildcardMethodHandler(_) => Self::WildcardMethodHandler,
+			LayerTarget::MistargetedRequestHandler(_) => Self::MistargetedRequestHandler,
+		};
+
+		Some(kind)
+	}
+}
+
+impl Display for LayerTargetKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::RequestReceiver => f.write_str("request_receiver"),
+			Self::RequestPasser => f.write_str("request_passer"),
+			Self::RequestHandler => f.write_str("request_handler"),
+			Self::MethodHandler(method) => write!(f, "method_handler({})", method),
+			Self::WildcardMethodHandler => f.write_str("wildcard_method_handler"),
+			Self::MistargetedRequestHandler => f.write_str("mistargeted_request_handler"),
+		}
+	}
+}
+
+impl Serialize for LayerTargetKind {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
@@ -1,5 +1,6 @@
 use std::{
 	borrow::Cow,
+	collections::HashMap,
 	convert::Infallible,
 	fmt::{Debug, Display},
 	future::ready,
@@ -12,12 +13,15 @@ use argan_core::{
 	BoxedError, BoxedFuture,
 };
 use bytes::Bytes;
-use http::{Method, StatusCode, Uri};
+use http::{header::LOCATION, HeaderValue, Method, StatusCode, Uri};
 use hyper::service::Service;
 use percent_encoding::percent_decode_str;
 
 use crate::{
-	common::{marker::Sealed, CloneWithPeerAddr, MaybeBoxed, NodeExtension, SCOPE_VALIDITY},
+	common::{
+		marker::Sealed, node_properties::StaticMatching, CloneWithPeerAddr, MaybeBoxed, NodeExtension,
+		SCOPE_VALIDITY,
+	},
 	handler::{
 		request_handlers::{handle_mistargeted_request, SupportedMethods, WildcardMethodHandler},
 		ArcHandler, Args, BoxedHandler, Handler,
@@ -199,6 +203,20 @@ where
 		} else if self.is_root() {
 			// Resource is a root and the request's path always starts from root.
 			true
+		} else if self.resource_ref().pattern.is_catch_all() {
+			let segments = route_traversal.remaining_segments(route);
+			let segments = segments.strip_suffix('/').unwrap_or(segments);
+			route_traversal.revert_to_segment(route.len());
+
+			percent_decode_str(segments)
+				.decode_utf8()
+				.is_ok_and(|decoded_segments| {
+					self
+						.resource_ref()
+						.pattern
+						.is_catch_all_match(decoded_segments, &mut path_params)
+						.expect(SCOPE_VALIDITY)
+				})
 		} else {
 			let (next_segment, _) = route_traversal.next_segment(route).expect(SCOPE_VALIDITY);
 
@@ -207,14 +225,10 @@ where
 			if let Some(match_result) = self.resource_ref().pattern.is_static_match(next_segment) {
 				match_result
 			} else if let Ok(decoded_segment) = percent_decode_str(next_segment).decode_utf8() {
-				#[cfg(not(feature = "regex"))]
-				let some_match_result = None;
-
-				#[cfg(feature = "regex")]
 				let some_match_result = self
 					.resource_ref()
 					.pattern
-					.is_regex_match(decoded_segment.as_ref(), &mut path_params);
+					.is_regex_or_constrained_match(decoded_segment.as_ref(), &mut path_params);
 
 				if let Some(match_result) = some_match_result {
 					match_result
@@ -534,8 +548,14 @@ impl Handler for ResourceRequestReceiver {
 					};
 
 					// We need to revert to the next segment index so the remaining path segments
-					// start from that segment.
+					// start from that segment. The segments below this resource are handled as
+					// they are, so only the segments up to this resource are canonicalized.
 					request_context.routing_revert_to_segment(next_segment_index);
+					request_context.forget_canonical_segments_from(next_segment_index);
+
+					if !request_context.noted_canonical_segments().is_empty() {
+						return Ok(redirect_to_canonical_path(&request_context));
+					}
 
 					let args = Args {
 						node_extension: Cow::Owned(node_extension),
@@ -563,6 +583,10 @@ impl Handler for ResourceRequestReceiver {
 		}
 
 		if let Some(request_handler) = self.some_request_handler.as_ref() {
+			if !request_context.noted_canonical_segments().is_empty() {
+				return Box::pin(ready(Ok(redirect_to_canonical_path(&request_context))));
+			}
+
 			let request_path_ends_with_slash = request_context.path_ends_with_slash();
 			let resource_path_ends_with_slash = self.config_flags.has(ConfigFlags::ENDS_WITH_SLASH);
 
@@ -627,7 +651,8 @@ impl Handler for ResourceRequestReceiver {
 
 #[derive(Clone)]
 pub(crate) struct ResourceRequestPasser {
-	some_static_resources: Option<Arc<[FinalResource]>>,
+	some_static_resources: Option<Arc<HashMap<Arc<str>, FinalResource>>>,
+	some_relaxed_static_matching: Option<Arc<RelaxedStaticMatching>>,
 	some_regex_resources: Option<Arc<[FinalResource]>>,
 	some_wildcard_resource: Option<Arc<FinalResource>>,
 
@@ -636,7 +661,8 @@ pub(crate) struct ResourceRequestPasser {
 
 impl ResourceRequestPasser {
 	pub(crate) fn new(
-		some_static_resources: Option<Arc<[FinalResource]>>,
+		some_static_resources: Option<Arc<HashMap<Arc<str>, FinalResource>>>,
+		some_relaxed_static_matching: Option<Arc<RelaxedStaticMatching>>,
 		some_regex_resources: Option<Arc<[FinalResource]>>,
 		some_wildcard_resource: Option<Arc<FinalResource>>,
 		some_mistargeted_request_handler: Option<ArcHandler>,
@@ -644,6 +670,7 @@ impl ResourceRequestPasser {
 	) -> MaybeBoxed<Self> {
 		let request_passer = Self {
 			some_static_resources,
+			some_relaxed_static_matching,
 			some_regex_resources,
 			some_wildcard_resource,
 			some_mistargeted_request_handler,
@@ -683,55 +710,106 @@ impl Handler for ResourceRequestPasser {
 
 	#[inline]
 	fn handle(&self, mut request_context: RequestContext, args: Args) -> Self::Future {
+		let next_segment_index = request_context.routing_next_segment_index();
+		let mut some_canonical_segment = None;
+
 		let some_next_resource = 'some_next_resource: {
 			let (next_segment, uri_params) = request_context
 				.routing_next_segment_and_uri_params_mut()
 				.expect("request passer shouldn't be called when there is no next path segment");
 
-			if let Some(next_resource) = self.some_static_resources.as_ref().and_then(|resources| {
-				resources.iter().find(
-					// Static patterns keep percent-encoded string. We may match them without
-					// decoding the segment.
-					|resource| {
-						resource
-							.pattern
-							.is_static_match(next_segment)
-							.expect("static_resources must keep only the resources with a static pattern")
-					},
-				)
-			}) {
+			// Static patterns keep percent-encoded string. We may match them without
+			// decoding the segment.
+			if let Some(next_resource) = self
+				.some_static_resources
+				.as_ref()
+				.and_then(|resources| resources.get(next_segment))
+			{
 				break 'some_next_resource Some(next_resource);
 			}
 
+			if let Some(relaxed_static_matching) = self.some_relaxed_static_matching.as_deref() {
+				if let Some(static_pattern) = relaxed_static_matching.registered_pattern(next_segment) {
+					// The request is redirected once the target resource is found, so all
+					// the segments of the path can be replaced in a single redirect.
+					if relaxed_static_matching.static_matching.redirects() {
+						some_canonical_segment = Some((
+							next_segment_index,
+							next_segment.len(),
+							static_pattern.clone(),
+						));
+					}
+
+					break 'some_next_resource self
+						.some_static_resources
+						.as_ref()
+						.and_then(|resources| resources.get(static_pattern));
+				}
+			}
+
 			let Ok(decoded_segment) = percent_decode_str(next_segment).decode_utf8() else {
 				return Box::pin(ready(Ok(StatusCode::BAD_REQUEST.into_response())));
 			};
 
-			#[cfg(feature = "regex")]
 			if let Some(next_resource) = self.some_regex_resources.as_ref().and_then(|resources| {
 				resources.iter().find(|resource| {
 					resource
 						.pattern
-						.is_regex_match(decoded_segment.as_ref(), uri_params)
-						.expect("regex_resources must keep only the resources with a regex pattern")
+						.is_regex_or_constrained_match(decoded_segment.as_ref(), uri_params)
+						.expect(
+							"regex_resources must keep only the resources with a regex or constrained pattern",
+						)
 				})
 			}) {
 				break 'some_next_resource Some(next_resource);
 			}
 
-			let _ = self
+			let Some(wildcard_resource) = self.some_wildcard_resource.as_deref() else {
+				break 'some_next_resource None;
+			};
+
+			// Catch-all patterns are matched against all the remaining path segments below.
+			if wildcard_resource.pattern.is_catch_all() {
+				break 'some_next_resource None;
+			}
+
+			let _ = wildcard_resource
+				.pattern
+				.is_wildcard_match(decoded_segment, uri_params)
+				.expect("wildcard_resource must keep only a resource with a wilcard pattern");
+
+			Some(wildcard_resource)
+		};
+
+		let some_next_resource = match some_next_resource {
+			Some(next_resource) => Some(next_resource),
+			None => match self
 				.some_wildcard_resource
-				.as_ref()
-				.is_some_and(|resource| {
-					resource
-						.pattern
-						.is_wildcard_match(decoded_segment, uri_params)
-						.expect("wildcard_resource must keep only a resource with a wilcard pattern")
-				});
+				.as_deref()
+				.filter(|resource| resource.pattern.is_catch_all())
+			{
+				Some(catch_all_resource) => {
+					let (segments, uri_params) =
+						request_context.routing_catch_all_segments_and_uri_params_mut(next_segment_index);
+
+					let Ok(decoded_segments) = percent_decode_str(segments).decode_utf8() else {
+						return Box::pin(ready(Ok(StatusCode::BAD_REQUEST.into_response())));
+					};
 
-			self.some_wildcard_resource.as_deref()
+					catch_all_resource
+						.pattern
+						.is_catch_all_match(decoded_segments, uri_params)
+						.expect("catch-all resource must have a catch-all pattern")
+						.then_some(catch_all_resource)
+				}
+				None => None,
+			},
 		};
 
+		if let Some(canonical_segment) = some_canonical_segment {
+			request_context.note_canonical_segment(canonical_segment);
+		}
+
 		if let Some(next_resource) = some_next_resource {
 			request_context.clone_valid_properties_from(&next_resource.request_context_properties);
 
@@ -759,6 +837,88 @@ impl Handler for ResourceRequestPasser {
 	}
 }
 
+// -------------------------
+// RelaxedStaticMatching
+
+// Normalized static patterns of the subresources mapped to their registered patterns.
+pub(crate) struct RelaxedStaticMatching {
+	static_matching: StaticMatching,
+	normalized_patterns: HashMap<String, Arc<str>>,
+}
+
+impl RelaxedStaticMatching {
+	pub(crate) fn new<'p, P>(static_matching: StaticMatching, static_patterns: P) -> Self
+	where
+		P: Iterator<Item = &'p Arc<str>>,
+	{
+		let mut normalized_patterns = HashMap::new();
+
+		for static_pattern in static_patterns {
+			let normalized_pattern = static_matching.normalize(static_pattern);
+
+			if let Some(other_static_pattern) =
+				normalized_patterns.insert(normalized_pattern, static_pattern.clone())
+			{
+				panic!(
+					"static patterns '{}' and '{}' are the same after the normalization",
+					other_static_pattern, static_pattern,
+				)
+			}
+		}
+
+		Self {
+			static_matching,
+			normalized_patterns,
+		}
+	}
+
+	#[inline(always)]
+	fn registered_pattern(&self, segment: &str) -> Option<&Arc<str>> {
+		self
+			.normalized_patterns
+			.get(&self.static_matching.normalize(segment))
+	}
+}
+
+// Replaces the noted path segments with their registered static patterns and redirects
+// the request to the new path with a `301 Moved Permanently` if the request's method is
+// GET or HEAD, or with a `308 Permanent Redirect` otherwise.
+fn redirect_to_canonical_path(request_context: &RequestContext) -> Response {
+	let uri = request_context.uri_ref();
+	let path = uri.path();
+
+	let mut canonical_path = String::with_capacity(path.len());
+	let mut copied_length = 0;
+
+	for (segment_index, segment_length, static_pattern) in request_context.noted_canonical_segments()
+	{
+		canonical_path.push_str(&path[copied_length..*segment_index]);
+		canonical_path.push_str(static_pattern);
+		copied_length = segment_index + segment_length;
+	}
+
+	canonical_path.push_str(&path[copied_length..]);
+
+	if let Some(query) = uri.query() {
+		canonical_path.push('?');
+		canonical_path.push_str(query);
+	}
+
+	// Other methods must not be changed to GET by the client.
+	let status_code = match *request_context.method_ref() {
+		Method::GET | Method::HEAD => StatusCode::MOVED_PERMANENTLY,
+		_ => StatusCode::PERMANENT_REDIRECT,
+	};
+
+	let mut response = status_code.into_response();
+	response.headers_mut().insert(
+		LOCATION,
+		HeaderValue::from_str(&canonical_path).expect("path must be a valid header value"),
+	);
+
+	response
+}
+
 // --------------------------------------------------
 // ResourceRequestHandler
 
@@ -900,7 +1060,9 @@ impl NotFoundResourceError {
 	}
 
 	pub(crate) fn new_with_request_context(request_context: RequestContext) -> Self {
-		Self(InnerNotFoundResourceError::RequestContext(request_context))
+		Self(InnerNotFoundResourceError::RequestContext(Box::new(
+			request_context,
+		)))
 	}
 
 	/// Returns a reference to the URI that the error was created with.
@@ -917,7 +1079,7 @@ impl NotFoundResourceError {
 
 	pub(crate) fn try_into_request_context(self) -> Result<RequestContext, Self> {
 		if let InnerNotFoundResourceError::RequestContext(request_context) = self.0 {
-			return Ok(request_context);
+			return Ok(*request_context);
 		}
 
 		Err(self)
@@ -934,7 +1096,7 @@ impl IntoResponse for NotFoundResourceError {
 
 enum InnerNotFoundResourceError {
 	Uri(Uri),
-	RequestContext(RequestContext),
+	RequestContext(Box<RequestContext>),
 }
 
 impl Debug for InnerNotFoundResourceError {
@@ -1315,6 +1477,96 @@ mod test {
 		assert_eq!(body, "Hello from Middleware!");
 	}
 
+	#[tokio::test]
+	async fn resource_from_fn_layer() {
+		use crate::{
+			middleware::{from_fn, HandlerWrapper, Next},
+			request::MistargetedRequest,
+			test_client::TestClient,
+		};
+
+		let trace = |name: &'static str| {
+			from_fn(
+				move |request_context: RequestContext, args: Args<'static>, next: Next| async move {
+					let mut response = next
+						.run(request_context, args)
+						.await
+						.unwrap_or_else(|error| error.into_response());
+					response
+						.headers_mut()
+						.append("x-trace", HeaderValue::from_static(name));
+
+					Ok::<_, BoxedErrorResponse>(response)
+				},
+			)
+		};
+
+		let mut root = Resource::new("/");
+		root.wrap([
+			RequestReceiver.component_in(trace("root_receiver")),
+			RequestPasser.component_in(trace("root_passer")),
+		]);
+
+		let st_0_0 = root.subresource_mut("/st_0_0");
+		st_0_0.wrap(MistargetedRequest.handler_in(trace("st_0_0_mistargeted")));
+
+		let st_1_0 = root.subresource_mut("/st_0_0/st_1_0");
+		st_1_0.set_handler_for(Method::GET.to(|| async { "Hello from Handler!" }));
+		st_1_0.wrap([
+			RequestHandler.component_in(trace("st_1_0_handler")),
+			Method::GET.handler_in(trace("st_1_0_get")),
+		]);
+
+		let st_1_1 = root.subresource_mut("/st_0_0/st_1_1");
+		st_1_1.set_handler_for(Method::GET.to(|| async { "Hello from Handler!" }));
+		st_1_1.wrap(RequestReceiver.component_in(from_fn(
+			|_: RequestContext, _: Args<'static>, _: Next| async { StatusCode::FORBIDDEN },
+		)));
+
+		// ----------
+
+		let client = TestClient::new(root.into_service());
+
+		let response = client.get("/st_0_0/st_1_0").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "Hello from Handler!");
+
+		let traces = response
+			.headers()
+			.get_all("x-trace")
+			.iter()
+			.map(|value| value.to_str().unwrap())
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			traces,
+			[
+				"st_1_0_get",
+				"st_1_0_handler",
+				"root_passer",
+				"root_receiver"
+			],
+		);
+
+		let response = client.get("/st_0_0/unknown").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+		let traces = response
+			.headers()
+			.get_all("x-trace")
+			.iter()
+			.map(|value| value.to_str().unwrap())
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			traces,
+			["st_0_0_mistargeted", "root_passer", "root_receiver"],
+		);
+
+		let response = client.get("/st_0_0/st_1_1").send().await;
+		assert_eq!(response.status(), StatusCode::FORBIDDEN);
+	}
+
 	// --------------------------------------------------
 	// Request extensions test.
 
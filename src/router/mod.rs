@@ -3,22 +3,38 @@
 // ----------
 
 use core::panic;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-	common::{node_properties::NodeProperty, IntoArray, NodeExtension, SCOPE_VALIDITY},
-	host::Host,
+	common::{
+		node_properties::{NodeProperty, StaticMatching},
+		IntoArray, NodeExtension, SCOPE_VALIDITY,
+	},
+	host::{parse_host_pattern, Host, HostPatternError},
 	middleware::targets::LayerTarget,
-	pattern::{split_uri_host_and_path, Pattern, Similarity},
+	pattern::{
+		add_registered_constraint, split_uri_host_and_path, Pattern, RegisteredConstraint, Similarity,
+	},
 	request::RequestContextProperties,
-	resource::{Iteration, Resource},
+	resource::{Iteration, NamedRoutes, PathParamsCheckError, Resource, Route},
+};
+
+#[cfg(feature = "openapi")]
+use bytes::Bytes;
+#[cfg(feature = "openapi")]
+use http::{header::CONTENT_TYPE, HeaderValue};
+
+#[cfg(feature = "openapi")]
+use crate::{
+	handler::HandlerSetter,
+	openapi::{OpenApi, APPLICATION_YAML},
 };
 
 // --------------------------------------------------
 
 mod service;
 
-pub use service::{ArcRouterService, LeakedRouterService, RouterService};
+pub use service::{ArcRouterService, LeakedRouterService, ReloadableRouterService, RouterService};
 
 use self::service::{FinalRouter, RouterRequestPasser};
 
@@ -28,18 +44,27 @@ use self::service::{FinalRouter, RouterRequestPasser};
 /// A type that can contain hosts and a root resource.
 ///
 /// The `Router` passes the request to a host that matches the request's 'Host' or
-/// to a root resource if one exists when there is no matching host. Otherwise, it
-/// responds with `404 Not Found`.
-///
+/// to a root resource if one exists when there is no matching host. If the router has
+/// a default host set, the default host receives the requests that don't match any other
+/// host. Otherwise, it responds with `404 Not Found`.
 ///
+/// The port in the request's 'Host' is ignored when the hosts are matched. A subdomain
+/// can be captured with a host pattern like `{tenant}.example.com`, where the capture
+/// matches a single, non-empty label before the static domain.
 pub struct Router {
 	static_hosts: Vec<Host>,
 	regex_hosts: Vec<Host>,
 	some_root_resource: Option<Box<Resource>>,
+	some_default_host_pattern: Option<Pattern>,
 
 	request_context_properties: RequestContextProperties,
+	some_static_matching: Option<StaticMatching>,
+	segment_constraints: Vec<RegisteredConstraint>,
 	extension: NodeExtension,
 	middleware: Vec<LayerTarget<Self>>,
+
+	#[cfg(feature = "openapi")]
+	some_openapi_document: Option<(String, OpenApi)>,
 }
 
 impl Default for Router {
@@ -55,10 +80,16 @@ impl Router {
 			static_hosts: Vec::new(),
 			regex_hosts: Vec::new(),
 			some_root_resource: None,
+			some_default_host_pattern: None,
 
 			request_context_properties: RequestContextProperties::default(),
+			some_static_matching: None,
+			segment_constraints: Vec::new(),
 			extension: NodeExtension::new(),
 			middleware: Vec::new(),
+
+			#[cfg(feature = "openapi")]
+			some_openapi_document: None,
 		}
 	}
 
@@ -247,21 +278,21 @@ impl Router {
 				.static_hosts
 				.iter_mut()
 				.find(|static_host| static_host.compare_pattern(host_pattern) == Similarity::Same),
-			#[cfg(feature = "regex")]
-			Pattern::Regex(_, _) => self
+			Pattern::Wildcard(_) | Pattern::CatchAll(..) => unreachable!(),
+			// Regex, constrained, and subdomain patterns.
+			_ => self
 				.regex_hosts
 				.iter_mut()
 				.find(|regex_host| regex_host.compare_pattern(host_pattern) == Similarity::Same),
-			Pattern::Wildcard(_) => unreachable!(),
 		}
 	}
 
 	fn add_new_host(&mut self, host_pattern: Pattern, root: Resource) {
 		let host = match host_pattern {
 			Pattern::Static(_) => &mut self.static_hosts,
-			#[cfg(feature = "regex")]
-			Pattern::Regex(_, _) => &mut self.regex_hosts,
-			Pattern::Wildcard(_) => unreachable!(),
+			Pattern::Wildcard(_) | Pattern::CatchAll(..) => unreachable!(),
+			// Regex, constrained, and subdomain patterns.
+			_ => &mut self.regex_hosts,
 		};
 
 		host.push(Host::with_pattern(host_pattern, root));
@@ -333,7 +364,7 @@ impl Router {
 		some_path_pattern_str: Option<&str>,
 		new_resource: Resource,
 	) {
-		let some_host_pattern = some_host_pattern_str.map(Pattern::parse);
+		let some_host_pattern = some_host_pattern_str.map(Pattern::parse_host);
 
 		let new_resource_is_root = new_resource.is("/");
 
@@ -442,7 +473,7 @@ impl Router {
 
 		let resource_is_root = path_pattern_str == "/";
 
-		if let Some(host_pattern) = some_host_pattern_str.map(Pattern::parse) {
+		if let Some(host_pattern) = some_host_pattern_str.map(Pattern::parse_host) {
 			let new_host =
 				match &host_pattern {
 					Pattern::Static(_) => {
@@ -465,8 +496,9 @@ impl Router {
 
 						self.static_hosts.last_mut().expect(SCOPE_VALIDITY)
 					}
-					#[cfg(feature = "regex")]
-					Pattern::Regex(_, _) => {
+					Pattern::Wildcard(_) | Pattern::CatchAll(..) => unreachable!(),
+					// Regex and constrained patterns.
+					_ => {
 						if let Some(position) = self
 							.regex_hosts
 							.iter()
@@ -488,7 +520,6 @@ impl Router {
 
 						self.regex_hosts.last_mut().expect(SCOPE_VALIDITY)
 					}
-					Pattern::Wildcard(_) => unreachable!(),
 				};
 
 			if resource_is_root {
@@ -514,6 +545,46 @@ impl Router {
 		}
 	}
 
+	/// Sets the host that receives the requests which don't match any other host of the
+	/// router, including the requests without a 'Host'.
+	///
+	/// ```
+	/// use argan::{Router, Host, Resource};
+	///
+	/// let mut router = Router::new();
+	/// router.add_host([
+	///   Host::new("example.com", Resource::new("/")),
+	///   Host::new("{tenant}.example.com", Resource::new("/")),
+	/// ]);
+	///
+	/// router.set_default_host("example.com");
+	/// ```
+	///
+	/// # Panics
+	///
+	/// - if the router already has a default host set
+	/// - if the host pattern isn't static
+	///
+	/// When the router is being converted into a service, it also panics if it doesn't
+	/// have a host with the given pattern or if it has a root resource without a host.
+	pub fn set_default_host<P: AsRef<str>>(&mut self, host_pattern: P) {
+		if self.some_default_host_pattern.is_some() {
+			panic!("router already has a default host");
+		}
+
+		let host_pattern = match parse_host_pattern(host_pattern) {
+			Ok(host_pattern) => host_pattern,
+			Err(HostPatternError::Empty) => panic!("empty host pattern"),
+			Err(HostPatternError::Wildcard) => panic!("host pattern cannot be a wildcard"),
+		};
+
+		if !host_pattern.is_static() {
+			panic!("default host pattern must be static")
+		}
+
+		self.some_default_host_pattern = Some(host_pattern);
+	}
+
 	/// Sets the given extension to the `Router`. The extension is available to all middleware
 	/// that wraps the `Router`'s request passer in the [`NodeExtension`] field of the
 	/// [`Args`](crate::handler::Args).
@@ -606,19 +677,26 @@ impl Router {
 	/// // routed or handled.
 	/// router.set_property(NodeCookieKey.to(Key::generate()));
 	/// ```
+	///
+	/// See [`StaticMatching`](crate::common::node_properties::StaticMatching) for the rules
+	/// of matching the request's path segments against the static resources. The rules set
+	/// on the router are applied to the root resources that don't have their own rules.
 	pub fn set_property<C, const N: usize>(&mut self, properties: C)
 	where
 		C: IntoArray<NodeProperty<Self>, N>,
 	{
 		#![allow(unused_imports)]
 
-		// Currently we have only one property.
-		if let Some(property) = properties.into_array().into_iter().next() {
+		for property in properties.into_array() {
 			use NodeProperty::*;
 
 			match property {
 				#[cfg(any(feature = "private-cookies", feature = "signed-cookies"))]
 				CookieKey(cookie_key) => self.request_context_properties.set_cookie_key(cookie_key),
+				StaticMatching(static_matching) => self.some_static_matching = Some(static_matching),
+				SegmentConstraint(name, constraint) => {
+					add_registered_constraint(&mut self.segment_constraints, (name, constraint))
+				}
 				_ => unreachable!("ConfigOption::None should never be used"),
 			}
 		}
@@ -664,20 +742,154 @@ impl Router {
 		}
 	}
 
+	/// Returns the routes of all the resource trees. The routes of the root resource's tree
+	/// are listed first, then the routes of the hosts.
+	///
+	/// See [`Resource::routes()`] for details.
+	///
+	/// ```
+	/// use argan::{Router, Host, Resource, handler::HandlerSetter, http::Method};
+	///
+	/// let mut root = Resource::new("/");
+	/// root.set_handler_for(Method::GET.to(|| async {}));
+	///
+	/// let mut router = Router::new();
+	/// router.add_host(Host::new("example.com", root));
+	///
+	/// let route = router.routes().next().unwrap();
+	/// assert_eq!(route.host(), Some("example.com"));
+	/// assert_eq!(route.to_string(), "//example.com/ [GET] (handles_on_unmatching_slash)");
+	/// ```
+	pub fn routes(&self) -> impl Iterator<Item = Route> {
+		let mut routes = Vec::new();
+
+		if let Some(root) = self.some_root_resource.as_deref() {
+			root.collect_routes(&mut routes);
+		}
+
+		for host in self.static_hosts.iter().chain(self.regex_hosts.iter()) {
+			host.root_ref().collect_routes(&mut routes);
+		}
+
+		routes.into_iter()
+	}
+
+	/// Sets the OpenAPI document to be served at the given path with the `GET` method.
+	///
+	/// The routes of the router are added to the document when the router is being
+	/// converted into a service. The document is served as YAML if the path ends with
+	/// `.yaml` or `.yml`, and as JSON otherwise.
+	///
+	/// ```
+	/// use argan::{Router, handler::HandlerSetter, http::Method, openapi::OpenApi};
+	///
+	/// let mut router = Router::new();
+	/// router.resource_mut("/users").set_handler_for(Method::GET.to(|| async {}));
+	/// router.set_openapi_document("/openapi.json", OpenApi::new("Users", "1.0.0"));
+	///
+	/// let service = router.into_arc_service();
+	/// ```
+	///
+	/// # Panics
+	///
+	/// - if the router already has an OpenAPI document set
+	/// - when the router is being converted into a service, if the routes of different
+	///   hosts have the same path (see [`OpenApi`])
+	#[cfg(feature = "openapi")]
+	pub fn set_openapi_document<P: AsRef<str>>(&mut self, path: P, document: OpenApi) {
+		if self.some_openapi_document.is_some() {
+			panic!("router already has an OpenAPI document");
+		}
+
+		self.some_openapi_document = Some((path.as_ref().to_owned(), document));
+	}
+
+	/// Checks the path params expected by the handlers in all the resource trees against
+	/// the params captured by their resources' routes.
+	///
+	/// See [`Resource::check_path_params()`] for details.
+	pub fn check_path_params(&self) -> Result<(), PathParamsCheckError> {
+		let mut issues = Vec::new();
+
+		if let Some(root) = self.some_root_resource.as_deref() {
+			root.collect_path_params_issues(&mut issues);
+		}
+
+		for host in self.static_hosts.iter().chain(self.regex_hosts.iter()) {
+			host.root_ref().collect_path_params_issues(&mut issues);
+		}
+
+		PathParamsCheckError::from_issues(issues)
+	}
+
+	// Resolves the registered constraints of the patterns in the hosts and resource trees.
+	fn resolve_segment_constraints(&self) -> Result<(), RouterReloadError> {
+		let mut constraints = self.segment_constraints.iter().collect::<Vec<_>>();
+
+		if let Some(root) = self.some_root_resource.as_deref() {
+			root.resolve_segment_constraints(&mut constraints)?;
+		}
+
+		for host in self.static_hosts.iter().chain(self.regex_hosts.iter()) {
+			host.resolve_segment_constraints(&mut constraints)?;
+		}
+
+		Ok(())
+	}
+
 	fn finalize(self) -> FinalRouter {
 		let Router {
 			static_hosts,
 			regex_hosts,
 			some_root_resource,
+			some_default_host_pattern,
 			request_context_properties,
+			some_static_matching: _,
+			segment_constraints: _,
 			extension,
 			middleware,
+			#[cfg(feature = "openapi")]
+				some_openapi_document: _,
 		} = self;
 
+		let some_default_host = some_default_host_pattern.map(|default_host_pattern| {
+			if some_root_resource.is_some() {
+				panic!("router cannot have both a default host and a root resource without a host")
+			}
+
+			if !static_hosts
+				.iter()
+				.any(|static_host| static_host.compare_pattern(&default_host_pattern) == Similarity::Same)
+			{
+				panic!(
+					"router has no host with a default host pattern '{}'",
+					default_host_pattern
+				)
+			}
+
+			default_host_pattern
+				.static_str()
+				.expect("default host pattern must be static")
+				.clone()
+		});
+
 		let some_static_hosts = if static_hosts.is_empty() {
 			None
 		} else {
-			Some(static_hosts.into_iter().map(Host::finalize).collect())
+			let static_hosts = static_hosts
+				.into_iter()
+				.map(|host| {
+					let static_pattern = host
+						.pattern_ref()
+						.static_str()
+						.expect("static_hosts must keep only the hosts with a static pattern")
+						.clone();
+
+					(static_pattern, host.finalize())
+				})
+				.collect::<HashMap<_, _>>();
+
+			Some(Arc::new(static_hosts))
 		};
 
 		let some_regex_hosts = if regex_hosts.is_empty() {
@@ -693,6 +905,7 @@ impl Router {
 			some_static_hosts,
 			some_regex_hosts,
 			some_root_resource,
+			some_default_host,
 			middleware,
 		);
 
@@ -702,9 +915,85 @@ impl Router {
 	// -------------------------
 
 	/// Converts the `Router` into a service.
+	///
+	/// # Panics
+	///
+	/// - if some handler expects a path param that its resource's route doesn't capture
+	///   (see [`check_path_params()`](Self::check_path_params))
+	/// - if two resources in the resource trees have the same name
+	/// - if some pattern uses a segment constraint that isn't registered on the router,
+	///   its resource or the resource's parents
 	#[inline(always)]
 	pub fn into_service(self) -> RouterService {
-		RouterService::new(self.finalize())
+		match self.try_into_service() {
+			Ok(router_service) => router_service,
+			Err(error) => panic!("{}", error),
+		}
+	}
+
+	// Converts the `Router` into a service. Returns an error instead of panicking when
+	// the resource trees fail the checks of `into_service()`.
+	pub(crate) fn try_into_service(mut self) -> Result<RouterService, RouterReloadError> {
+		if let Some(static_matching) = self.some_static_matching {
+			self.for_each_root((), |_, root| {
+				root.inherit_static_matching(static_matching);
+
+				Iteration::Continue
+			});
+		}
+
+		self.resolve_segment_constraints()?;
+
+		if let Err(error) = self.check_path_params() {
+			error
+				.into_mismatches()
+				.map_err(RouterReloadError::PathParamsMismatch)?;
+		}
+
+		#[cfg(feature = "openapi")]
+		if let Some((path, document)) = self.some_openapi_document.take() {
+			let document = document.with_routes(self.routes());
+
+			let (content_type, document) = if path.ends_with(".yaml") || path.ends_with(".yml") {
+				(APPLICATION_YAML, Bytes::from(document.to_yaml()))
+			} else {
+				(
+					mime::APPLICATION_JSON.as_ref(),
+					Bytes::from(document.to_json()),
+				)
+			};
+
+			self
+				.resource_mut(path)
+				.set_handler_for(http::Method::GET.to(move || {
+					let document = document.clone();
+
+					async move {
+						(
+							[(CONTENT_TYPE, HeaderValue::from_static(content_type))],
+							document,
+						)
+					}
+				}));
+		}
+
+		let mut named_routes = NamedRoutes::default();
+
+		if let Some(root) = self.some_root_resource.as_deref() {
+			root.collect_named_routes(&mut named_routes)?;
+		}
+
+		for host in self.static_hosts.iter().chain(self.regex_hosts.iter()) {
+			host.root_ref().collect_named_routes(&mut named_routes)?;
+		}
+
+		if !named_routes.is_empty() {
+			self
+				.request_context_properties
+				.set_named_routes(Arc::new(named_routes));
+		}
+
+		Ok(RouterService::new(self.finalize()))
 	}
 
 	/// Converts the `Router` into a service that uses `Arc` internally.
@@ -718,6 +1007,34 @@ impl Router {
 	pub fn into_leaked_service(self) -> LeakedRouterService {
 		LeakedRouterService::from(self.into_service())
 	}
+
+	/// Converts the `Router` into a service whose router can be replaced with
+	/// [`ReloadableRouterService::reload()`] while the service is being used.
+	#[inline(always)]
+	pub fn into_reloadable_service(self) -> ReloadableRouterService {
+		ReloadableRouterService::from(self.into_service())
+	}
+}
+
+// --------------------------------------------------
+// RouterReloadError
+
+/// An error that's returned by [`ReloadableRouterService::reload()`] when the new router's
+/// resource trees fail the checks of [`Router::into_service()`].
+#[non_exhaustive]
+#[derive(Debug, crate::ImplError)]
+pub enum RouterReloadError {
+	/// Returned when some handler expects a path param that its resource's route doesn't
+	/// capture. Contains only the mismatches.
+	#[error(transparent)]
+	PathParamsMismatch(PathParamsCheckError),
+	/// Returned when two resources in the resource trees have the same name.
+	#[error("duplicate resource name '{0}'")]
+	DuplicateResourceName(Box<str>),
+	/// Returned when some pattern uses a segment constraint that isn't registered on the
+	/// router, its resource or the resource's parents.
+	#[error("unknown segment constraint '{0}'")]
+	UnknownSegmentConstraint(Box<str>),
 }
 
 // --------------------------------------------------------------------------------
@@ -801,7 +1118,7 @@ mod test {
 		{
 			assert_eq!(router.static_hosts.len(), 1);
 			let example_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			assert_eq!(example_com.root_mut().static_resources().len(), 2);
@@ -810,7 +1127,7 @@ mod test {
 		{
 			assert_eq!(router.regex_hosts.len(), 1);
 			let sub_example_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_1.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_1.com"))
 				.unwrap();
 
 			assert_eq!(sub_example_com.root_mut().static_resources().len(), 2);
@@ -833,7 +1150,7 @@ mod test {
 		{
 			assert_eq!(router.static_hosts.len(), 1);
 			let example_0_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			let root = example_0_com.root_mut();
@@ -845,7 +1162,7 @@ mod test {
 		{
 			assert_eq!(router.regex_hosts.len(), 2);
 			let sub_example_1_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_1.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_1.com"))
 				.unwrap();
 
 			let root = sub_example_1_com.root_mut();
@@ -856,7 +1173,7 @@ mod test {
 
 		{
 			let sub_example_2_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_2.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_2.com"))
 				.unwrap();
 
 			let root = sub_example_2_com.root_mut();
@@ -882,7 +1199,7 @@ mod test {
 			router.add_resource(new_root);
 
 			let example_0_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			let root = example_0_com.root_mut();
@@ -1033,7 +1350,7 @@ mod test {
 		{
 			assert_eq!(router.static_hosts.len(), 1);
 			let example_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			assert_eq!(example_com.root_mut().static_resources().len(), 2);
@@ -1042,7 +1359,7 @@ mod test {
 		{
 			assert_eq!(router.regex_hosts.len(), 1);
 			let sub_example_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_1.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_1.com"))
 				.unwrap();
 
 			assert_eq!(sub_example_com.root_mut().static_resources().len(), 2);
@@ -1073,7 +1390,7 @@ mod test {
 		{
 			assert_eq!(router.static_hosts.len(), 1);
 			let example_0_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			let root = example_0_com.root_mut();
@@ -1085,7 +1402,7 @@ mod test {
 		{
 			assert_eq!(router.regex_hosts.len(), 2);
 			let sub_example_1_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_1.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_1.com"))
 				.unwrap();
 
 			let root = sub_example_1_com.root_mut();
@@ -1096,7 +1413,7 @@ mod test {
 
 		{
 			let sub_example_2_com = router
-				.existing_host_mut(&Pattern::parse("{sub}.example_2.com"))
+				.existing_host_mut(&Pattern::parse_host("{sub}.example_2.com"))
 				.unwrap();
 
 			let root = sub_example_2_com.root_mut();
@@ -1122,7 +1439,7 @@ mod test {
 			router.add_resource(new_root);
 
 			let example_0_com = router
-				.existing_host_mut(&Pattern::parse("example_0.com"))
+				.existing_host_mut(&Pattern::parse_host("example_0.com"))
 				.unwrap();
 
 			let root = example_0_com.root_mut();
@@ -1344,6 +1661,89 @@ mod test {
 				.has_some_effect());
 		}
 	}
+
+	#[test]
+	fn router_check_path_params() {
+		use serde::Deserialize;
+
+		use crate::{request::PathParams, resource::PathParamsIssue};
+
+		#[derive(Deserialize)]
+		struct Params {
+			sub: String,
+			id: u32,
+		}
+
+		let mut router = Router::new();
+		router
+			.resource_mut("http://{sub}.example.com/items/{id}")
+			.set_handler_for(Method::GET.to(|_: PathParams<Params>| async {}));
+
+		router.check_path_params().unwrap();
+
+		router
+			.resource_mut("/items/{id}")
+			.set_handler_for(Method::GET.to(|_: PathParams<Params>| async {}));
+
+		let error = router.check_path_params().unwrap_err();
+		assert_eq!(
+			error.issues(),
+			[PathParamsIssue::Mismatch {
+				route: "/items/{id}".to_string(),
+				handler: "GET".to_string(),
+				param: "sub".to_string(),
+			}]
+		);
+	}
+
+	#[tokio::test]
+	async fn router_static_matching() {
+		use http::{header::HOST, StatusCode};
+
+		use crate::{
+			common::node_properties::{NodeStaticMatching, StaticMatching},
+			test_client::TestClient,
+		};
+
+		let mut router = Router::new();
+		router.set_property(NodeStaticMatching.to(StaticMatching::exact().case_insensitive()));
+
+		router
+			.resource_mut("/items")
+			.set_handler_for(Method::GET.to(|| async { "items" }));
+
+		router
+			.resource_mut("http://example.com/items")
+			.set_handler_for(Method::GET.to(|| async { "host items" }));
+
+		// The root resource's own rules take precedence.
+		router
+			.resource_mut("http://exact.example.com/")
+			.set_property(NodeStaticMatching.to(StaticMatching::exact()));
+
+		router
+			.resource_mut("http://exact.example.com/items")
+			.set_handler_for(Method::GET.to(|| async { "exact items" }));
+
+		let client = TestClient::new(router.into_service());
+
+		let cases = [
+			(None, StatusCode::OK, "items"),
+			(Some("example.com"), StatusCode::OK, "host items"),
+			(Some("exact.example.com"), StatusCode::NOT_FOUND, ""),
+		];
+
+		for (some_host, status_code, body) in cases {
+			let mut request = client.get("/Items");
+			if let Some(host) = some_host {
+				request = request.header(HOST, host);
+			}
+
+			let response = request.send().await;
+			assert_eq!(response.status(), status_code, "{:?}", some_host);
+			assert_eq!(response.text(), body, "{:?}", some_host);
+		}
+	}
 }
 
 // --------------------------------------------------------------------------------
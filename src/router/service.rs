@@ -1,4 +1,10 @@
-use std::{convert::Infallible, future::ready, net::SocketAddr, sync::Arc};
+use std::{
+	collections::HashMap,
+	convert::Infallible,
+	future::ready,
+	net::SocketAddr,
+	sync::{Arc, RwLock},
+};
 
 use argan_core::{
 	body::{Body, HttpBody},
@@ -26,7 +32,7 @@ use crate::{
 #[cfg(feature = "peer-addr")]
 use crate::common::SCOPE_VALIDITY;
 
-use super::Router;
+use super::{Router, RouterReloadError};
 
 // --------------------------------------------------------------------------------
 // --------------------------------------------------------------------------------
@@ -317,27 +323,154 @@ impl CloneWithPeerAddr for LeakedRouterService {
 
 impl Sealed for LeakedRouterService {}
 
+// --------------------------------------------------
+// ReloadableRouterService
+
+/// A router service whose router can be replaced while the service is being used.
+///
+/// Created by calling [`Router::into_reloadable_service()`] on a `Router`. All the clones
+/// of the service share the current router, so the router replaced via any clone is used
+/// to handle the subsequent requests. The requests that are already being handled finish
+/// on the previous router.
+///
+/// ```
+/// use argan::{Router, Resource, handler::HandlerSetter, http::Method};
+///
+/// let mut router = Router::new();
+/// router.add_resource(Resource::new("/"));
+///
+/// let service = router.into_reloadable_service();
+///
+/// // ...
+///
+/// let mut new_router = Router::new();
+/// new_router
+///   .resource_mut("/tenants/{tenant}")
+///   .set_handler_for(Method::GET.to(|| async {}));
+///
+/// service.reload(new_router).expect("the new router should be valid");
+/// ```
+#[derive(Clone)]
+pub struct ReloadableRouterService {
+	current_router: Arc<RwLock<Arc<FinalRouter>>>,
+
+	#[cfg(feature = "peer-addr")]
+	peer_addr: SocketAddr,
+}
+
+impl ReloadableRouterService {
+	/// Converts the given `Router` into the service's current router. The requests that
+	/// are already being handled aren't affected.
+	///
+	/// If the `router` fails the checks of [`Router::into_service()`], an error is returned,
+	/// and the current router stays in place.
+	///
+	/// # Panics
+	///
+	/// - if the `router` has both a default host and a root resource without a host, or
+	///   doesn't have a host with the default host pattern
+	/// - if the `router`'s OpenAPI document has colliding paths (with the `openapi` feature)
+	pub fn reload(&self, router: Router) -> Result<(), RouterReloadError> {
+		let RouterService(InnerRouterService { router, .. }) = router.try_into_service()?;
+
+		*self
+			.current_router
+			.write()
+			.unwrap_or_else(|error| error.into_inner()) = Arc::new(router);
+
+		Ok(())
+	}
+
+	#[inline(always)]
+	fn current_router(&self) -> Arc<FinalRouter> {
+		Arc::clone(
+			&self
+				.current_router
+				.read()
+				.unwrap_or_else(|error| error.into_inner()),
+		)
+	}
+}
+
+impl From<RouterService> for ReloadableRouterService {
+	#[inline(always)]
+	fn from(router_service: RouterService) -> Self {
+		let RouterService(InnerRouterService {
+			router,
+
+			#[cfg(feature = "peer-addr")]
+			peer_addr,
+		}) = router_service;
+
+		Self {
+			current_router: Arc::new(RwLock::new(Arc::new(router))),
+
+			#[cfg(feature = "peer-addr")]
+			peer_addr,
+		}
+	}
+}
+
+impl<B> Service<Request<B>> for ReloadableRouterService
+where
+	B: HttpBody<Data = Bytes> + Send + Sync + 'static,
+	B::Error: Into<BoxedError>,
+{
+	type Response = Response;
+	type Error = Infallible;
+	type Future = InfallibleResponseFuture;
+
+	#[inline(always)]
+	fn call(&self, request: Request<B>) -> Self::Future {
+		// The returned future doesn't borrow the router, so the current router may be
+		// replaced while the request is being handled.
+		let inner_router_service = InnerRouterService {
+			router: self.current_router(),
+
+			#[cfg(feature = "peer-addr")]
+			peer_addr: self.peer_addr,
+		};
+
+		inner_router_service.call(request)
+	}
+}
+
+impl CloneWithPeerAddr for ReloadableRouterService {
+	fn clone_with_peer_addr(&self, _addr: SocketAddr) -> Self {
+		Self {
+			current_router: Arc::clone(&self.current_router),
+			#[cfg(feature = "peer-addr")]
+			peer_addr: _addr,
+		}
+	}
+}
+
+impl Sealed for ReloadableRouterService {}
+
 // --------------------------------------------------
 // RequestPasser
 
 #[derive(Clone)]
 pub(super) struct RouterRequestPasser {
-	some_static_hosts: Option<Arc<[FinalHost]>>,
+	some_static_hosts: Option<Arc<HashMap<Arc<str>, FinalHost>>>,
 	some_regex_hosts: Option<Arc<[FinalHost]>>,
 	some_root_resource: Option<Arc<FinalResource>>,
+	some_default_host: Option<Arc<str>>,
 }
 
 impl RouterRequestPasser {
 	pub(super) fn new(
-		some_static_hosts: Option<Arc<[FinalHost]>>,
+		some_static_hosts: Option<Arc<HashMap<Arc<str>, FinalHost>>>,
 		some_regex_hosts: Option<Arc<[FinalHost]>>,
 		some_root_resource: Option<Arc<FinalResource>>,
+		some_default_host: Option<Arc<str>>,
 		middleware: Vec<LayerTarget<Router>>,
 	) -> MaybeBoxed<Self> {
 		let request_passer = Self {
 			some_static_hosts,
 			some_regex_hosts,
 			some_root_resource,
+			some_default_host,
 		};
 
 		let mut maybe_boxed_request_passer = MaybeBoxed::Unboxed(request_passer);
@@ -380,22 +513,19 @@ where
 		#[allow(unused_variables)]
 		match request_context.routing_host_and_uri_params_mut() {
 			Ok((uri_host, uri_params)) => {
-				if let Some(host) = self.some_static_hosts.as_ref().and_then(|hosts| {
-					hosts.iter().find(|host| {
-						host
-							.is_static_match(uri_host)
-							.expect("static_hosts must keep only the hosts with a static pattern")
-					})
-				}) {
+				if let Some(host) = self
+					.some_static_hosts
+					.as_ref()
+					.and_then(|hosts| hosts.get(uri_host))
+				{
 					return host.handle(request_context, args);
 				}
 
-				#[cfg(feature = "regex")]
 				if let Some(host) = self.some_regex_hosts.as_ref().and_then(|hosts| {
 					hosts.iter().find(|host| {
 						host
-							.is_regex_match(uri_host, uri_params)
-							.expect("regex_hosts must keep only the hosts with a static pattern")
+							.is_regex_or_constrained_match(uri_host, uri_params)
+							.expect("regex_hosts must keep only the hosts with a regex or constrained pattern")
 					})
 				}) {
 					return host.handle(request_context, args);
@@ -409,6 +539,16 @@ where
 			return root_resource.handle(request_context, args);
 		}
 
+		// The default host is always one of the static hosts.
+		if let Some(default_host) = self.some_default_host.as_ref().and_then(|default_host| {
+			self
+				.some_static_hosts
+				.as_ref()
+				.and_then(|hosts| hosts.get(default_host))
+		}) {
+			return default_host.handle(request_context, args);
+		}
+
 		let request = request_context.into_request();
 		let (head, _) = request.into_parts();
 
@@ -894,4 +1034,207 @@ mod test {
 			location.to_str().unwrap()
 		);
 	}
+
+	#[tokio::test]
+	async fn router_default_host_and_subdomain() {
+		use crate::{request::PathParams, test_client::TestClient};
+
+		let mut router = Router::new();
+		router
+			.resource_mut("http://example.com/")
+			.set_handler_for(Method::GET.to(|| async { "example.com" }));
+
+		router
+			.resource_mut("http://{tenant}.example.com:8080/")
+			.set_handler_for(
+				Method::GET.to(|PathParams(tenant): PathParams<String>| async move { tenant }),
+			);
+
+		router.set_default_host("example.com");
+
+		let client = TestClient::new(router.into_service());
+
+		let response = client
+			.get("/")
+			.header(HOST, "acme.example.com")
+			.send()
+			.await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "acme");
+
+		let response = client
+			.get("/")
+			.header(HOST, "acme.example.com:3000")
+			.send()
+			.await;
+
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "acme");
+
+		for host in [
+			"example.com:8080",
+			"a.b.example.com",
+			"example.org",
+			"[::1]",
+			"[::1]:8080",
+		] {
+			let response = client.get("/").header(HOST, host).send().await;
+			assert_eq!(response.status(), StatusCode::OK, "{}", host);
+			assert_eq!(response.text(), "example.com", "{}", host);
+		}
+
+		let response = client.get("/").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "example.com");
+	}
+
+	#[test]
+	#[should_panic(expected = "router has no host with a default host pattern")]
+	fn router_default_host_missing() {
+		let mut router = Router::new();
+		let _ = router.resource_mut("http://{tenant}.example.com/");
+		router.set_default_host("example.com");
+
+		let _ = router.into_service();
+	}
+
+	#[test]
+	#[should_panic(expected = "router cannot have both a default host and a root resource")]
+	fn router_default_host_with_root() {
+		let mut router = Router::new();
+		let _ = router.resource_mut("http://example.com/");
+		let _ = router.resource_mut("/");
+		router.set_default_host("example.com");
+
+		let _ = router.into_service();
+	}
+
+	#[tokio::test]
+	async fn router_reloadable_service() {
+		use tokio::sync::oneshot;
+
+		use crate::test_client::TestClient;
+
+		let (started_tx, started_rx) = oneshot::channel::<()>();
+		let (release_tx, release_rx) = oneshot::channel::<()>();
+		let channels = Arc::new(std::sync::Mutex::new(Some((started_tx, release_rx))));
+
+		let mut router = Router::new();
+		router
+			.resource_mut("/old")
+			.set_handler_for(Method::GET.to(|| async { "old" }));
+
+		router
+			.resource_mut("/slow")
+			.set_handler_for(Method::GET.to(move || {
+				let some_channels = channels.lock().unwrap().take();
+
+				async move {
+					if let Some((started_tx, release_rx)) = some_channels {
+						started_tx.send(()).unwrap();
+						let _ = release_rx.await;
+					}
+
+					"slow"
+				}
+			}));
+
+		let service = router.into_reloadable_service();
+		let client = TestClient::new(service.clone());
+
+		let response = client.get("/old").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "old");
+
+		let slow_response = tokio::spawn({
+			let client = TestClient::new(service.clone());
+
+			async move { client.get("/slow").send().await }
+		});
+
+		// The slow request is being handled by the old router.
+		started_rx.await.unwrap();
+
+		// ----------
+
+		let mut new_router = Router::new();
+		new_router
+			.resource_mut("/new")
+			.set_handler_for(Method::GET.to(|| async { "new" }));
+
+		service.reload(new_router).unwrap();
+
+		let response = client.get("/old").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+		let response = client.get("/new").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "new");
+
+		// The request that was being handled finishes on the previous router.
+		release_tx.send(()).unwrap();
+
+		let response = slow_response.await.unwrap();
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "slow");
+	}
+	#[tokio::test]
+	async fn router_reloadable_service_invalid_router() {
+		use serde::Deserialize;
+
+		use crate::{request::PathParams, test_client::TestClient};
+
+		#[derive(Deserialize)]
+		struct Params {
+			tenant: String,
+		}
+
+		let mut router = Router::new();
+		router
+			.resource_mut("/old")
+			.set_handler_for(Method::GET.to(|| async { "old" }));
+
+		let service = router.into_reloadable_service();
+		let client = TestClient::new(service.clone());
+
+		// The handler expects the 'tenant' param that the route doesn't capture.
+		let mut new_router = Router::new();
+		new_router
+			.resource_mut("/tenants/{id}")
+			.set_handler_for(Method::GET.to(|_: PathParams<Params>| async {}));
+
+		let error = service.reload(new_router).unwrap_err();
+		assert!(matches!(error, RouterReloadError::PathParamsMismatch(_)));
+
+		// The current router stays in place.
+		let response = client.get("/old").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.text(), "old");
+
+		// -------------------------
+
+		let mut new_router = Router::new();
+		new_router.resource_mut("/a").set_name("tenant");
+		new_router.resource_mut("/b").set_name("tenant");
+
+		let error = service.reload(new_router).unwrap_err();
+		assert!(matches!(
+			error,
+			RouterReloadError::DuplicateResourceName(name) if name.as_ref() == "tenant",
+		));
+
+		let mut new_router = Router::new();
+		new_router
+			.resource_mut("/tenants/{id:@unknown}")
+			.set_handler_for(Method::GET.to(|| async {}));
+
+		let error = service.reload(new_router).unwrap_err();
+		assert!(matches!(
+			error,
+			RouterReloadError::UnknownSegmentConstraint(name) if name.as_ref() == "unknown",
+		));
+
+		let response = client.get("/old").send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+	}
 }
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use hyper_util::{
+	rt::{TokioExecutor, TokioTimer},
+	server::conn::auto::Builder,
+};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// The minimum buffer size that hyper accepts for HTTP/1 connections.
+const MIN_HTTP1_BUFFER_SIZE: usize = 8192;
+
+// --------------------------------------------------
+// ServerConfig
+
+/// A configuration of the HTTP protocols that a [`Server`] serves.
+///
+/// By default, both HTTP/1 and HTTP/2 are served. Over TLS, the protocol is negotiated
+/// with ALPN, and on plaintext connections HTTP/2 is served to the clients that start
+/// the connection with the HTTP/2 preface (prior-knowledge h2c).
+///
+/// ```
+/// use std::time::Duration;
+/// use argan::{Server, server::ServerConfig};
+///
+/// let server = Server::with_config(
+///   ServerConfig::new()
+///     .with_h2c_prior_knowledge(false)
+///     .with_max_header_size(16 * 1024)
+///     .with_http2_max_concurrent_streams(100)
+///     .with_http2_keep_alive(Duration::from_secs(20), Duration::from_secs(10)),
+/// );
+/// ```
+///
+/// [`Server`]: crate::Server
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+	protocols: HttpProtocols,
+	h2c_prior_knowledge: bool,
+	http1_keep_alive: bool,
+	some_max_header_size: Option<usize>,
+	some_http2_max_concurrent_streams: Option<u32>,
+	some_http2_keep_alive: Option<(Duration, Duration)>,
+}
+
+// The HTTP protocols that the server serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HttpProtocols {
+	Http1AndHttp2,
+	Http1,
+	Http2,
+}
+
+impl ServerConfig {
+	/// Creates a new configuration that serves both HTTP/1 and HTTP/2.
+	pub fn new() -> Self {
+		Self {
+			protocols: HttpProtocols::Http1AndHttp2,
+			h2c_prior_knowledge: true,
+			http1_keep_alive: true,
+			some_max_header_size: None,
+			some_http2_max_concurrent_streams: None,
+			some_http2_keep_alive: None,
+		}
+	}
+
+	/// Configures the server to serve only HTTP/1.
+	pub fn http1_only(mut self) -> Self {
+		self.protocols = HttpProtocols::Http1;
+
+		self
+	}
+
+	/// Configures the server to serve only HTTP/2.
+	///
+	/// On plaintext connections, the clients must use prior-knowledge h2c.
+	pub fn http2_only(mut self) -> Self {
+		self.protocols = HttpProtocols::Http2;
+
+		self
+	}
+
+	/// Sets whether the plaintext connections that start with the HTTP/2 preface are
+	/// served with HTTP/2 when both protocols are served. By default, it's enabled.
+	///
+	/// When disabled, plaintext connections are served only with HTTP/1.
+	pub fn with_h2c_prior_knowledge(mut self, enabled: bool) -> Self {
+		self.h2c_prior_knowledge = enabled;
+
+		self
+	}
+
+	/// Sets whether the HTTP/1 connections are kept alive between the requests.
+	/// By default, it's enabled.
+	pub fn with_http1_keep_alive(mut self, enabled: bool) -> Self {
+		self.http1_keep_alive = enabled;
+
+		self
+	}
+
+	/// Sets the maximum size of the request headers in bytes.
+	///
+	/// For HTTP/1, the size limits the connection's read buffer, which can't be smaller
+	/// than 8192 bytes.
+	pub fn with_max_header_size(mut self, size: usize) -> Self {
+		self.some_max_header_size = Some(size);
+
+		self
+	}
+
+	/// Sets the maximum number of concurrent streams of the HTTP/2 connections.
+	pub fn with_http2_max_concurrent_streams(mut self, max_streams: u32) -> Self {
+		self.some_http2_max_concurrent_streams = Some(max_streams);
+
+		self
+	}
+
+	/// Sets the interval of the HTTP/2 keep-alive pings and the timeout to receive the
+	/// acknowledgement. The connection is closed if the acknowledgement is not received
+	/// in time.
+	pub fn with_http2_keep_alive(mut self, interval: Duration, timeout: Duration) -> Self {
+		self.some_http2_keep_alive = Some((interval, timeout));
+
+		self
+	}
+
+	// Returns the protocols that the plaintext and the TLS connections are served with.
+	pub(super) fn protocols(&self) -> (HttpProtocols, HttpProtocols) {
+		if self.protocols == HttpProtocols::Http1AndHttp2 && !self.h2c_prior_knowledge {
+			return (HttpProtocols::Http1, HttpProtocols::Http1AndHttp2);
+		}
+
+		(self.protocols, self.protocols)
+	}
+
+	// Returns the ALPN protocols that match the served HTTP protocols.
+	#[cfg(feature = "tls")]
+	pub(super) fn alpn_protocols(&self) -> Vec<Vec<u8>> {
+		match self.protocols {
+			HttpProtocols::Http1AndHttp2 => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+			HttpProtocols::Http1 => vec![b"http/1.1".to_vec()],
+			HttpProtocols::Http2 => vec![b"h2".to_vec()],
+		}
+	}
+
+	pub(super) fn connection_builder(&self) -> Builder<TokioExecutor> {
+		let mut connection_builder = Builder::new(TokioExecutor::new());
+
+		{
+			let mut http1_builder = connection_builder.http1();
+			http1_builder.keep_alive(self.http1_keep_alive);
+
+			if let Some(max_header_size) = self.some_max_header_size {
+				http1_builder.max_buf_size(max_header_size.max(MIN_HTTP1_BUFFER_SIZE));
+			}
+		}
+
+		{
+			let mut http2_builder = connection_builder.http2();
+			http2_builder.timer(TokioTimer::new());
+
+			if let Some(max_header_size) = self.some_max_header_size {
+				http2_builder.max_header_list_size(u32::try_from(max_header_size).unwrap_or(u32::MAX));
+			}
+
+			if let Some(max_streams) = self.some_http2_max_concurrent_streams {
+				http2_builder.max_concurrent_streams(max_streams);
+			}
+
+			if let Some((interval, timeout)) = self.some_http2_keep_alive {
+				http2_builder
+					.keep_alive_interval(interval)
+					.keep_alive_timeout(timeout);
+			}
+		}
+
+		// The protocol restrictions are enforced by the server before the connection is
+		// passed to the builder, as the builder ignores them when serving the connections
+		// with upgrades.
+		connection_builder
+	}
+}
+
+impl Default for ServerConfig {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// --------------------------------------------------------------------------------
@@ -0,0 +1,2027 @@
+#![doc = include_str!("../../docs/server.md")]
+
+// ----------
+
+use std::{
+	future::{pending, Future},
+	io::{Error as IoError, ErrorKind, IoSlice},
+	net::{SocketAddr, ToSocketAddrs},
+	pin::{pin, Pin},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	task::{ready, Context, Poll},
+	time::Duration,
+};
+
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+
+use argan_core::{
+	body::{Body, Frame, HttpBody, SizeHint},
+	request::Request,
+	response::Response,
+	BoxedError, BoxedFuture,
+};
+use bytes::Bytes;
+use futures_util::{
+	stream::{self, select_all, BoxStream},
+	task::AtomicWaker,
+	StreamExt,
+};
+use hyper::{body::Incoming, service::Service};
+use hyper_util::{
+	rt::{TokioExecutor, TokioIo, TokioTimer},
+	server::{
+		conn::auto::Builder,
+		graceful::{GracefulShutdown, Watcher},
+	},
+};
+use pin_project::pin_project;
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+	net::{TcpListener, TcpStream},
+	sync::{watch, OwnedSemaphorePermit, Semaphore},
+	time::Sleep,
+};
+
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+#[cfg(feature = "tls")]
+use tokio_rustls::{rustls::ServerConfig as TlsServerConfig, TlsAcceptor};
+
+use crate::common::{CloneWithPeerAddr, SCOPE_VALIDITY};
+
+// ----------
+
+mod config;
+use config::HttpProtocols;
+pub use config::ServerConfig;
+
+#[cfg(feature = "tls")]
+mod tls;
+
+#[cfg(feature = "tls")]
+pub use tls::*;
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+/// A high-level server type.
+///
+/// For examples, see the module [`doc`](crate::server).
+pub struct Server {
+	connection_builder: Builder<TokioExecutor>,
+	some_shutdown_duration: Option<Duration>,
+	shutdown_handle: ShutdownHandle,
+	some_shutdown_signal: Mutex<Option<BoxedFuture<()>>>,
+	built_in_signals: bool,
+	some_max_connections: Option<usize>,
+	some_tls_handshake_timeout: Option<Duration>,
+	some_idle_timeout: Option<Duration>,
+	some_header_read_timeout: Option<Duration>,
+	plaintext_protocols: HttpProtocols,
+	#[cfg(feature = "tls")]
+	tls_protocols: HttpProtocols,
+	#[cfg(feature = "tls")]
+	some_alpn_protocols: Option<Vec<Vec<u8>>>,
+}
+
+impl Server {
+	/// Creates a new `Server` with the provided [`Builder`].
+	///
+	/// [`Builder`]: https://docs.rs/hyper-util/latest/hyper_util/server/conn/auto/struct.Builder.html
+	pub fn new(connection_builder: Builder<TokioExecutor>) -> Self {
+		Self {
+			connection_builder,
+			some_shutdown_duration: None,
+			shutdown_handle: ShutdownHandle::new(),
+			some_shutdown_signal: Mutex::new(None),
+			built_in_signals: true,
+			some_max_connections: None,
+			some_tls_handshake_timeout: None,
+			some_idle_timeout: None,
+			some_header_read_timeout: None,
+			plaintext_protocols: HttpProtocols::Http1AndHttp2,
+			#[cfg(feature = "tls")]
+			tls_protocols: HttpProtocols::Http1AndHttp2,
+			#[cfg(feature = "tls")]
+			some_alpn_protocols: None,
+		}
+	}
+
+	/// Creates a new `Server` with the given [`ServerConfig`].
+	///
+	/// Unlike with [`Self::new()`], the TLS connections advertise the ALPN protocols that
+	/// match the configured HTTP protocols.
+	pub fn with_config(config: ServerConfig) -> Self {
+		let mut server = Self::new(config.connection_builder());
+
+		#[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+		let (plaintext_protocols, tls_protocols) = config.protocols();
+		server.plaintext_protocols = plaintext_protocols;
+
+		#[cfg(feature = "tls")]
+		{
+			server.tls_protocols = tls_protocols;
+			server.some_alpn_protocols = Some(config.alpn_protocols());
+		}
+
+		server
+	}
+
+	/// Sets the graceful shutdown duration. By default, the server shuts down immediately.
+	pub fn with_graceful_shutdown_duration(mut self, duration: Duration) -> Self {
+		self.some_shutdown_duration = Some(duration);
+
+		self
+	}
+
+	/// Sets the maximum number of concurrent connections. By default, the number of
+	/// connections is not limited.
+	///
+	/// When the limit is reached, the server stops accepting new connections until some
+	/// of the open connections close.
+	pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+		self.some_max_connections = Some(max_connections);
+
+		self
+	}
+
+	/// Sets the TLS handshake timeout. By default, there is no timeout.
+	///
+	/// Connections that don't complete the handshake in time are closed.
+	///
+	/// If no header read timeout is set, this timeout is also used while reading the
+	/// beginning of each connection that determines the client's protocol, on both TLS and
+	/// plaintext listeners. See [`Self::with_header_read_timeout()`].
+	#[cfg(feature = "tls")]
+	pub fn with_tls_handshake_timeout(mut self, timeout: Duration) -> Self {
+		self.some_tls_handshake_timeout = Some(timeout);
+
+		self
+	}
+
+	/// Sets the idle timeout. By default, there is no timeout.
+	///
+	/// Connections that have no requests in flight, including the keep-alive connections
+	/// between requests, are closed when no bytes are received or sent within the timeout.
+	/// The timer doesn't run while a request is being handled or its response is being sent.
+	pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+		self.some_idle_timeout = Some(timeout);
+
+		self
+	}
+
+	/// Sets the timeout to read the request headers of the HTTP/1 connections.
+	///
+	/// The connection is closed if the client doesn't send the complete headers in time.
+	/// The timeout also applies to reading the beginning of the connection that determines
+	/// the client's protocol, whichever protocols are served. If this timeout isn't set,
+	/// the TLS handshake timeout is used for that read instead. Without either of them,
+	/// the server waits for the beginning of the connection without a time limit.
+	pub fn with_header_read_timeout(mut self, timeout: Duration) -> Self {
+		self.some_header_read_timeout = Some(timeout);
+
+		self
+			.connection_builder
+			.http1()
+			.timer(TokioTimer::new())
+			.header_read_timeout(timeout);
+
+		self
+	}
+
+	/// Sets a future that signals the server to shut down when it completes.
+	///
+	/// The signal is awaited by the first call that serves connections. It's awaited
+	/// along with the built-in signals and the [`ShutdownHandle`], whichever comes first.
+	pub fn with_shutdown_signal<F>(self, signal: F) -> Self
+	where
+		F: Future<Output = ()> + Send + 'static,
+	{
+		*self
+			.some_shutdown_signal
+			.lock()
+			.expect("the lock should never be poisoned") = Some(Box::pin(signal));
+
+		self
+	}
+
+	/// Turns off the built-in Ctrl-C and, on Unix systems, `SIGTERM` signal listeners.
+	///
+	/// The server then can only be stopped with a [`ShutdownHandle`] or a signal set
+	/// with [`Self::with_shutdown_signal()`].
+	pub fn without_built_in_signals(mut self) -> Self {
+		self.built_in_signals = false;
+
+		self
+	}
+
+	/// Returns a handle that can be used to shut down the server.
+	pub fn shutdown_handle(&self) -> ShutdownHandle {
+		self.shutdown_handle.clone()
+	}
+
+	/// Serves HTTP connections with the `service` on the first successfully
+	/// bound listener address.
+	///
+	/// Returns [`ServerError::Bind`] if there is no valid address with an unused port
+	/// to bind `TcpListener`.
+	pub async fn serve<S, A>(
+		&self,
+		service: S,
+		listener_addresses: A,
+	) -> Result<ShutdownReport, ServerError>
+	where
+		S: Service<Request<Incoming>, Response = Response<Body>>
+			+ CloneWithPeerAddr
+			+ Clone
+			+ Send
+			+ 'static,
+		S::Future: Send + 'static,
+		S::Error: Into<BoxedError>,
+		A: ToSocketAddrs,
+	{
+		self
+			.serve_on(service, [ListenerConfig::tcp(listener_addresses)])
+			.await
+	}
+
+	/// Serves HTTPS connections with the `service` on the first successfully
+	/// bound listener address.
+	///
+	/// TLS can be configured with [`TlsServerConfig`] (`TlsServerConfig` is an alias
+	/// for [`rustls`]'s [`ServerConfig`]).
+	///
+	/// Returns [`ServerError::Bind`] if there is no valid address with an unused port
+	/// to bind `TcpListener`.
+	///
+	/// [`rustls`]: https://docs.rs/rustls/latest/rustls/
+	/// [`TlsServerConfig`]: https://docs.rs/rustls/latest/rustls/server/struct.ServerConfig.html
+	/// [`ServerConfig`]: https://docs.rs/rustls/latest/rustls/server/struct.ServerConfig.html
+	#[cfg(feature = "tls")]
+	pub async fn serve_with_tls<S, A>(
+		&self,
+		service: S,
+		listener_addresses: A,
+		tls_server_config: TlsServerConfig,
+	) -> Result<ShutdownReport, ServerError>
+	where
+		S: Service<Request<Incoming>, Response = Response<Body>>
+			+ CloneWithPeerAddr
+			+ Clone
+			+ Send
+			+ 'static,
+		S::Future: Send + 'static,
+		S::Error: Into<BoxedError>,
+		A: ToSocketAddrs,
+	{
+		self
+			.serve_on(
+				service,
+				[ListenerConfig::tcp(listener_addresses).with_tls(tls_server_config)],
+			)
+			.await
+	}
+
+	/// Serves connections with the `service` on all the given listeners at once.
+	///
+	/// All the listeners are bound before the server starts accepting connections. If any
+	/// of them fails to bind, [`ServerError::Bind`] is returned. The listeners share the
+	/// graceful shutdown.
+	///
+	/// For an example, see the module [`doc`](crate::server).
+	pub async fn serve_on<S, L>(
+		&self,
+		service: S,
+		listeners: L,
+	) -> Result<ShutdownReport, ServerError>
+	where
+		S: Service<Request<Incoming>, Response = Response<Body>>
+			+ CloneWithPeerAddr
+			+ Clone
+			+ Send
+			+ 'static,
+		S::Future: Send + 'static,
+		S::Error: Into<BoxedError>,
+		L: IntoIterator<Item = ListenerConfig>,
+	{
+		let mut bound_listeners = Vec::new();
+		for listener_config in listeners {
+			bound_listeners.push(listener_config.bind().await?);
+		}
+
+		serve(service, bound_listeners, self).await
+	}
+}
+
+// --------------------------------------------------
+// ShutdownHandle
+
+/// A handle to shut down the [`Server`] from code.
+///
+/// Once the shutdown is requested, all the current and future calls of the server
+/// that serve connections stop accepting new connections and return.
+#[derive(Clone)]
+pub struct ShutdownHandle(Arc<watch::Sender<bool>>);
+
+impl ShutdownHandle {
+	fn new() -> Self {
+		let (sender, _) = watch::channel(false);
+
+		Self(Arc::new(sender))
+	}
+
+	/// Requests the server to shut down.
+	pub fn shutdown(&self) {
+		self.0.send_replace(true);
+	}
+
+	async fn requested(&self) {
+		let mut receiver = self.0.subscribe();
+
+		// The sender lives as long as the handle, so the only possible outcome is
+		// the requested shutdown.
+		let _ = receiver.wait_for(|requested| *requested).await;
+	}
+}
+
+// ----------
+
+/// A report of the [`Server`]'s shutdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutdownReport {
+	open_connections: usize,
+}
+
+impl ShutdownReport {
+	/// Returns the number of connections that were still open when the server returned.
+	///
+	/// When the graceful shutdown duration is set, these are the connections that didn't
+	/// close before the deadline.
+	#[inline(always)]
+	pub fn open_connections(&self) -> usize {
+		self.open_connections
+	}
+}
+
+// ----------
+
+// Counts the open connections.
+#[derive(Clone, Default)]
+struct ConnectionCounter(Arc<AtomicUsize>);
+
+impl ConnectionCounter {
+	fn track(&self, some_permit: Option<OwnedSemaphorePermit>) -> ConnectionGuard {
+		self.0.fetch_add(1, Ordering::Relaxed);
+
+		ConnectionGuard(self.0.clone(), some_permit)
+	}
+
+	fn count(&self) -> usize {
+		self.0.load(Ordering::Relaxed)
+	}
+}
+
+// Decrements the count and releases the connection limiter's permit when the
+// connection closes.
+struct ConnectionGuard(Arc<AtomicUsize>, Option<OwnedSemaphorePermit>);
+
+impl Drop for ConnectionGuard {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::Relaxed);
+	}
+}
+
+// --------------------------------------------------
+// Listener
+
+/// A source of connections that a [`Server`] accepts.
+///
+/// `Listener` is implemented for `tokio`'s `TcpListener` and, on Unix systems,
+/// `UnixListener`. Custom implementations can be used to serve, for example, the
+/// streams that decode the PROXY protocol header or in-memory streams in tests.
+///
+/// The peer address that the listener reports is passed to the service and can be
+/// extracted with the `PeerAddr` extractor when the `peer-addr` feature flag is enabled.
+pub trait Listener: Send + 'static {
+	/// The I/O stream of the accepted connection.
+	type Io: AsyncRead + AsyncWrite + Send + Unpin + 'static;
+
+	/// Accepts a new connection, returning its I/O stream and the address of the peer.
+	fn accept(&mut self) -> impl Future<Output = Result<(Self::Io, SocketAddr), IoError>> + Send;
+}
+
+impl Listener for TcpListener {
+	type Io = TcpStream;
+
+	fn accept(&mut self) -> impl Future<Output = Result<(Self::Io, SocketAddr), IoError>> + Send {
+		TcpListener::accept(self)
+	}
+}
+
+#[cfg(unix)]
+impl Listener for UnixListener {
+	type Io = UnixStream;
+
+	async fn accept(&mut self) -> Result<(Self::Io, SocketAddr), IoError> {
+		// Unix domain socket peers don't have an IP address.
+		UnixListener::accept(self)
+			.await
+			.map(|(stream, _)| (stream, SocketAddr::from(([0, 0, 0, 0], 0))))
+	}
+}
+
+// ----------
+
+// A listener bound to a Unix domain socket file that removes the file when dropped.
+#[cfg(unix)]
+struct UnixSocketFileListener(UnixListener, PathBuf);
+
+#[cfg(unix)]
+impl Listener for UnixSocketFileListener {
+	type Io = UnixStream;
+
+	fn accept(&mut self) -> impl Future<Output = Result<(Self::Io, SocketAddr), IoError>> + Send {
+		Listener::accept(&mut self.0)
+	}
+}
+
+#[cfg(unix)]
+impl Drop for UnixSocketFileListener {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(&self.1);
+	}
+}
+
+// ----------
+
+trait Io: AsyncRead + AsyncWrite + Send + Unpin + 'static {}
+
+impl<I: AsyncRead + AsyncWrite + Send + Unpin + 'static> Io for I {}
+
+type BoxedIo = Box<dyn Io>;
+
+type AcceptedConnections = BoxStream<'static, Result<(BoxedIo, SocketAddr), IoError>>;
+
+fn accepted_connections<L: Listener>(listener: L) -> AcceptedConnections {
+	stream::unfold(listener, |mut listener| async move {
+		let connection = listener
+			.accept()
+			.await
+			.map(|(io, peer_address)| (Box::new(io) as BoxedIo, peer_address));
+
+		Some((connection, listener))
+	})
+	.boxed()
+}
+
+// --------------------------------------------------
+// ListenerConfig
+
+/// A configuration of the listener that a [`Server`] accepts connections on.
+///
+/// The listener can be a TCP, a Unix domain socket (on Unix systems), or any custom
+/// [`Listener`]. Each listener can have its own TLS configuration when the `tls`
+/// feature flag is enabled.
+pub struct ListenerConfig {
+	kind: ListenerKind,
+	#[cfg(feature = "tls")]
+	some_tls_config_source: Option<TlsConfigSource>,
+}
+
+enum ListenerKind {
+	TcpAddresses(Result<Vec<SocketAddr>, IoError>, String),
+	#[cfg(unix)]
+	UnixPath(PathBuf),
+	Bound(AcceptedConnections),
+}
+
+impl ListenerConfig {
+	fn with_kind(kind: ListenerKind) -> Self {
+		Self {
+			kind,
+			#[cfg(feature = "tls")]
+			some_tls_config_source: None,
+		}
+	}
+
+	/// Creates a configuration of the given `listener`.
+	pub fn new<L: Listener>(listener: L) -> Self {
+		Self::with_kind(ListenerKind::Bound(accepted_connections(listener)))
+	}
+
+	/// Creates a TCP listener configuration. The listener is bound to the first address
+	/// with an unused port.
+	pub fn tcp<A: ToSocketAddrs>(addresses: A) -> Self {
+		let addresses_result = addresses
+			.to_socket_addrs()
+			.map(|addresses| addresses.collect::<Vec<_>>());
+
+		let addresses_string = match addresses_result.as_ref() {
+			Ok(addresses) => addresses
+				.iter()
+				.map(ToString::to_string)
+				.collect::<Vec<_>>()
+				.join(", "),
+			Err(_) => "invalid address".to_string(),
+		};
+
+		Self::with_kind(ListenerKind::TcpAddresses(
+			addresses_result,
+			addresses_string,
+		))
+	}
+
+	/// Creates a Unix domain socket listener configuration with the socket file `path`.
+	///
+	/// The socket file is removed when the server stops.
+	#[cfg(unix)]
+	pub fn unix<P: AsRef<Path>>(path: P) -> Self {
+		Self::with_kind(ListenerKind::UnixPath(path.as_ref().to_owned()))
+	}
+
+	/// Sets the TLS configuration of the listener.
+	///
+	/// TLS can be configured with [`TlsServerConfig`] (`TlsServerConfig` is an alias
+	/// for [`rustls`]'s [`ServerConfig`]).
+	///
+	/// [`rustls`]: https://docs.rs/rustls/latest/rustls/
+	/// [`TlsServerConfig`]: https://docs.rs/rustls/latest/rustls/server/struct.ServerConfig.html
+	/// [`ServerConfig`]: https://docs.rs/rustls/latest/rustls/server/struct.ServerConfig.html
+	#[cfg(feature = "tls")]
+	pub fn with_tls(self, tls_server_config: TlsServerConfig) -> Self {
+		self.with_tls_source(TlsConfigSource::new(tls_server_config))
+	}
+
+	/// Sets the TLS configuration source of the listener.
+	///
+	/// Each TLS handshake uses the current configuration of the source, so the
+	/// configuration can be replaced or reloaded while the server is running.
+	#[cfg(feature = "tls")]
+	pub fn with_tls_source(mut self, tls_config_source: TlsConfigSource) -> Self {
+		self.some_tls_config_source = Some(tls_config_source);
+
+		self
+	}
+
+	async fn bind(self) -> Result<BoundListener, ServerError> {
+		let ListenerConfig {
+			kind,
+			#[cfg(feature = "tls")]
+			some_tls_config_source,
+		} = self;
+
+		let connections = match kind {
+			ListenerKind::TcpAddresses(addresses_result, addresses_string) => {
+				let bind_error = |source| ServerError::Bind {
+					address: addresses_string.clone(),
+					source,
+				};
+
+				let addresses = addresses_result.map_err(bind_error)?;
+
+				let mut some_last_error = None;
+				let mut some_listener = None;
+				for address in addresses {
+					match TcpListener::bind(address).await {
+						Ok(listener) => {
+							some_listener = Some(listener);
+
+							break;
+						}
+						Err(error) => some_last_error = Some(error),
+					}
+				}
+
+				match some_listener {
+					Some(listener) => accepted_connections(listener),
+					None => {
+						let error = some_last_error.unwrap_or_else(|| {
+							IoError::new(std::io::ErrorKind::InvalidInput, "no address is given")
+						});
+
+						return Err(bind_error(error));
+					}
+				}
+			}
+			#[cfg(unix)]
+			ListenerKind::UnixPath(path) => match UnixListener::bind(&path) {
+				Ok(listener) => accepted_connections(UnixSocketFileListener(listener, path)),
+				Err(source) => {
+					return Err(ServerError::Bind {
+						address: path.display().to_string(),
+						source,
+					})
+				}
+			},
+			ListenerKind::Bound(connections) => connections,
+		};
+
+		Ok(BoundListener {
+			connections,
+			#[cfg(feature = "tls")]
+			some_tls_config_source,
+		})
+	}
+}
+
+impl From<TcpListener> for ListenerConfig {
+	fn from(listener: TcpListener) -> Self {
+		Self::new(listener)
+	}
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for ListenerConfig {
+	fn from(listener: UnixListener) -> Self {
+		Self::new(listener)
+	}
+}
+
+// --------------------------------------------------
+// BoundListener
+
+struct BoundListener {
+	connections: AcceptedConnections,
+	#[cfg(feature = "tls")]
+	some_tls_config_source: Option<TlsConfigSource>,
+}
+
+impl BoundListener {
+	fn into_incoming_connections(self) -> BoxStream<'static, IncomingConnection> {
+		let BoundListener {
+			connections,
+			#[cfg(feature = "tls")]
+			some_tls_config_source,
+		} = self;
+
+		connections
+			.map(move |result| IncomingConnection {
+				result,
+				#[cfg(feature = "tls")]
+				some_tls_config_source: some_tls_config_source.clone(),
+			})
+			.boxed()
+	}
+}
+
+struct IncomingConnection {
+	result: Result<(BoxedIo, SocketAddr), IoError>,
+	#[cfg(feature = "tls")]
+	some_tls_config_source: Option<TlsConfigSource>,
+}
+
+// --------------------------------------------------
+
+async fn serve<S>(
+	service: S,
+	bound_listeners: Vec<BoundListener>,
+	server: &Server,
+) -> Result<ShutdownReport, ServerError>
+where
+	S: Service<Request<Incoming>, Response = Response<Body>>
+		+ CloneWithPeerAddr
+		+ Clone
+		+ Send
+		+ 'static,
+	S::Future: Send + 'static,
+	S::Error: Into<BoxedError>,
+{
+	let mut incoming_connections = select_all(
+		bound_listeners
+			.into_iter()
+			.map(BoundListener::into_incoming_connections),
+	);
+
+	let Server {
+		connection_builder,
+		some_shutdown_duration,
+		shutdown_handle,
+		some_shutdown_signal,
+		built_in_signals,
+		some_max_connections,
+		some_tls_handshake_timeout,
+		some_idle_timeout,
+		some_header_read_timeout,
+		plaintext_protocols,
+		#[cfg(feature = "tls")]
+		tls_protocols,
+		#[cfg(feature = "tls")]
+		some_alpn_protocols,
+	} = server;
+
+	let some_shutdown_signal = some_shutdown_signal
+		.lock()
+		.expect("the lock should never be poisoned")
+		.take();
+
+	#[cfg(unix)]
+	let some_terminate_signal = if *built_in_signals {
+		Some(tokio::signal::unix::signal(
+			tokio::signal::unix::SignalKind::terminate(),
+		)?)
+	} else {
+		None
+	};
+
+	let built_in_signal = async {
+		if !*built_in_signals {
+			return pending().await;
+		}
+
+		#[cfg(unix)]
+		if let Some(mut terminate_signal) = some_terminate_signal {
+			tokio::select! {
+				_ = tokio::signal::ctrl_c() => {},
+				_ = terminate_signal.recv() => {},
+			}
+
+			return;
+		}
+
+		let _ = tokio::signal::ctrl_c().await;
+	};
+
+	let shutdown_signal = async {
+		match some_shutdown_signal {
+			Some(shutdown_signal) => shutdown_signal.await,
+			None => pending().await,
+		}
+	};
+
+	let mut pinned_shutdown = pin!(async {
+		tokio::select! {
+			_ = built_in_signal => {},
+			_ = shutdown_signal => {},
+			_ = shutdown_handle.requested() => {},
+		}
+	});
+
+	let connection_context = ConnectionContext {
+		connection_builder: Arc::new(connection_builder.clone()),
+		plaintext_protocols: *plaintext_protocols,
+		#[cfg(feature = "tls")]
+		tls_protocols: *tls_protocols,
+		#[cfg(feature = "tls")]
+		some_alpn_protocols: some_alpn_protocols.clone(),
+		some_tls_handshake_timeout: *some_tls_handshake_timeout,
+		some_idle_timeout: *some_idle_timeout,
+		some_header_read_timeout: *some_header_read_timeout,
+		connection_counter: ConnectionCounter::default(),
+	};
+
+	let some_connection_limiter =
+		some_max_connections.map(|max_connections| Arc::new(Semaphore::new(max_connections)));
+
+	let mut accept_error_count = 0;
+	let graceful_shutdown_watcher = GracefulShutdown::new();
+
+	loop {
+		// When the connection limit is reached, we stop accepting new connections
+		// until some of the open connections close.
+		let some_permit = match some_connection_limiter.as_ref() {
+			Some(connection_limiter) => tokio::select! {
+				permit = connection_limiter.clone().acquire_owned() => {
+					Some(permit.expect("the connection limiter should never be closed"))
+				},
+				_ = pinned_shutdown.as_mut() => break,
+			},
+			None => None,
+		};
+
+		tokio::select! {
+			Some(incoming_connection) = incoming_connections.next() => {
+				match incoming_connection.result {
+					Ok((io, peer_address)) => {
+						accept_error_count = 0;
+
+						serve_connection(
+							io,
+							#[cfg(feature = "tls")]
+							incoming_connection.some_tls_config_source,
+							peer_address,
+							service.clone(),
+							&connection_context,
+							graceful_shutdown_watcher.watcher(),
+							some_permit,
+						);
+					},
+					Err(error) => {
+						// Errors of the accepted connections don't affect the listener.
+						if is_connection_error(&error) {
+							continue;
+						}
+
+						// Other errors, like reaching the limit of open files, may be temporary.
+						tokio::time::sleep(Duration::from_secs(1)).await;
+
+						if accept_error_count < 3 {
+							accept_error_count += 1;
+
+							continue;
+						}
+
+						return Err(ServerError::from(error));
+					}
+				};
+			},
+			_ = pinned_shutdown.as_mut() => break,
+		}
+	}
+
+	let open_connections = match some_shutdown_duration {
+		Some(duration) => tokio::select! {
+			_ = graceful_shutdown_watcher.shutdown() => 0,
+			_ = tokio::time::sleep(*duration) => connection_context.connection_counter.count(),
+		},
+		None => connection_context.connection_counter.count(),
+	};
+
+	Ok(ShutdownReport { open_connections })
+}
+
+// The state shared by the connections that are served by the same server call.
+struct ConnectionContext {
+	connection_builder: Arc<Builder<TokioExecutor>>,
+	plaintext_protocols: HttpProtocols,
+	#[cfg(feature = "tls")]
+	tls_protocols: HttpProtocols,
+	#[cfg(feature = "tls")]
+	some_alpn_protocols: Option<Vec<Vec<u8>>>,
+	some_tls_handshake_timeout: Option<Duration>,
+	some_idle_timeout: Option<Duration>,
+	some_header_read_timeout: Option<Duration>,
+	connection_counter: ConnectionCounter,
+}
+
+fn serve_connection<S>(
+	io: BoxedIo,
+	#[cfg(feature = "tls")] some_tls_config_source: Option<TlsConfigSource>,
+	_peer_address: SocketAddr,
+	service: S,
+	connection_context: &ConnectionContext,
+	graceful_shutdown_watcher: Watcher,
+	some_permit: Option<OwnedSemaphorePermit>,
+) where
+	S: Service<Request<Incoming>, Response = Response<Body>>
+		+ CloneWithPeerAddr
+		+ Clone
+		+ Send
+		+ 'static,
+	S::Future: Send + 'static,
+	S::Error: Into<BoxedError>,
+{
+	let connection_builder = connection_context.connection_builder.clone();
+	let plaintext_protocols = connection_context.plaintext_protocols;
+	#[cfg(feature = "tls")]
+	let tls_protocols = connection_context.tls_protocols;
+	#[cfg(feature = "tls")]
+	let some_tls_server_config = some_tls_config_source.map(|tls_config_source| {
+		match connection_context.some_alpn_protocols.as_ref() {
+			Some(alpn_protocols) => tls_config_source.current_with_alpn_protocols(alpn_protocols),
+			None => tls_config_source.current(),
+		}
+	});
+
+	let some_tls_handshake_timeout = connection_context.some_tls_handshake_timeout;
+	let some_header_read_timeout = connection_context.some_header_read_timeout;
+	let connection_guard = connection_context.connection_counter.track(some_permit);
+
+	let some_in_flight_counter = connection_context
+		.some_idle_timeout
+		.map(|_| InFlightCounter::default());
+
+	let io = match connection_context.some_idle_timeout {
+		Some(idle_timeout) => Box::new(IdleTimeoutIo::new(
+			io,
+			idle_timeout,
+			some_in_flight_counter.clone().expect(SCOPE_VALIDITY),
+		)),
+		None => io,
+	};
+
+	tokio::spawn(async move {
+		#[cfg(feature = "tls")]
+		let (io, protocols) = match some_tls_server_config {
+			// The `tls` feature flag is enabled, and the listener is configured
+			// with TlsServerConfig.
+			Some(tls_server_config) => {
+				let handshake = TlsAcceptor::from(tls_server_config).accept(io);
+
+				let handshake_result = match some_tls_handshake_timeout {
+					Some(timeout) => tokio::time::timeout(timeout, handshake)
+						.await
+						.unwrap_or_else(|_| Err(IoError::from(ErrorKind::TimedOut))),
+					None => handshake.await,
+				};
+
+				// A failed handshake only closes its own connection.
+				match handshake_result {
+					Ok(tls_stream) => (Box::new(tls_stream) as BoxedIo, tls_protocols),
+					Err(_) => return,
+				}
+			}
+			None => (io, plaintext_protocols),
+		};
+
+		#[cfg(not(feature = "tls"))]
+		let protocols = plaintext_protocols;
+
+		// A client that never sends anything mustn't hold the connection forever. The header
+		// read timeout takes precedence; the TLS handshake timeout is the fallback for both
+		// TLS and plaintext connections.
+		let some_preface_timeout = some_header_read_timeout.or(some_tls_handshake_timeout);
+
+		let Some(io) = check_http_version(io, protocols, some_preface_timeout).await else {
+			return;
+		};
+
+		#[cfg(feature = "peer-addr")]
+		let service = service.clone_with_peer_addr(_peer_address);
+
+		let connection = connection_builder.serve_connection_with_upgrades(
+			TokioIo::new(io),
+			InFlightTrackingService {
+				service,
+				some_in_flight_counter,
+			},
+		);
+
+		let _ = graceful_shutdown_watcher.watch(connection).await;
+
+		drop(connection_guard);
+	});
+}
+
+// Reads the beginning of the connection to check whether the client uses one of the
+// allowed protocols. Returns the connection with the read bytes put back, or `None` if
+// the client doesn't send the beginning within the timeout.
+async fn check_http_version(
+	mut io: BoxedIo,
+	protocols: HttpProtocols,
+	some_timeout: Option<Duration>,
+) -> Option<BoxedIo> {
+	const HTTP2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+	// Hyper detects the protocol itself, but it doesn't limit the time it waits for
+	// the client. So we read the beginning ourselves when there is a timeout.
+	if protocols == HttpProtocols::Http1AndHttp2 && some_timeout.is_none() {
+		return Some(io);
+	}
+
+	let mut prefix = Vec::with_capacity(HTTP2_PREFACE.len());
+
+	let read_prefix = async {
+		let mut buffer = [0; HTTP2_PREFACE.len()];
+
+		while prefix.len() < HTTP2_PREFACE.len() && HTTP2_PREFACE.starts_with(&prefix) {
+			let size = io
+				.read(&mut buffer[..HTTP2_PREFACE.len() - prefix.len()])
+				.await
+				.ok()?;
+
+			if size == 0 {
+				return None;
+			}
+
+			prefix.extend_from_slice(&buffer[..size]);
+		}
+
+		Some(())
+	};
+
+	match some_timeout {
+		Some(timeout) => tokio::time::timeout(timeout, read_prefix).await.ok()??,
+		None => read_prefix.await?,
+	}
+
+	let is_http2 = prefix == HTTP2_PREFACE;
+
+	match protocols {
+		HttpProtocols::Http1 if is_http2 => None,
+		HttpProtocols::Http2 if !is_http2 => None,
+		_ => Some(Box::new(PrefixedIo {
+			prefix: Bytes::from(prefix),
+			io,
+		})),
+	}
+}
+
+// ----------
+
+// Returns the prefix before reading from the inner I/O.
+struct PrefixedIo {
+	prefix: Bytes,
+	io: BoxedIo,
+}
+
+impl AsyncRead for PrefixedIo {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<Result<(), IoError>> {
+		let this = self.get_mut();
+
+		if !this.prefix.is_empty() {
+			let size = this.prefix.len().min(buf.remaining());
+			buf.put_slice(&this.prefix.split_to(size));
+
+			return Poll::Ready(Ok(()));
+		}
+
+		Pin::new(&mut this.io).poll_read(cx, buf)
+	}
+}
+
+impl AsyncWrite for PrefixedIo {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<Result<usize, IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+	}
+
+	fn poll_write_vectored(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		bufs: &[IoSlice<'_>],
+	) -> Poll<Result<usize, IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		self.io.is_write_vectored()
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+	}
+}
+
+// ----------
+
+fn is_connection_error(error: &IoError) -> bool {
+	matches!(
+		error.kind(),
+		ErrorKind::ConnectionAborted | ErrorKind::ConnectionRefused | ErrorKind::ConnectionReset
+	)
+}
+
+// ----------
+
+// Counts the requests of the connection that are being handled or whose responses
+// are being sent.
+#[derive(Clone, Default)]
+struct InFlightCounter(Arc<InFlightState>);
+
+#[derive(Default)]
+struct InFlightState {
+	count: AtomicUsize,
+	// Wakes the connection's reader to start the idle timer when the last request
+	// in flight completes.
+	idle_waker: AtomicWaker,
+}
+
+impl InFlightCounter {
+	fn track(&self) -> InFlightGuard {
+		self.0.count.fetch_add(1, Ordering::AcqRel);
+
+		InFlightGuard(self.0.clone())
+	}
+
+	fn is_idle(&self, cx: &mut Context<'_>) -> bool {
+		self.0.idle_waker.register(cx.waker());
+
+		self.0.count.load(Ordering::Acquire) == 0
+	}
+}
+
+// Decrements the count when the request's response is sent or dropped.
+struct InFlightGuard(Arc<InFlightState>);
+
+impl Drop for InFlightGuard {
+	fn drop(&mut self) {
+		if self.0.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+			self.0.idle_waker.wake();
+		}
+	}
+}
+
+// ----------
+
+// Tracks the requests in flight when the connection has an idle timeout.
+#[derive(Clone)]
+struct InFlightTrackingService<S> {
+	service: S,
+	some_in_flight_counter: Option<InFlightCounter>,
+}
+
+impl<S> Service<Request<Incoming>> for InFlightTrackingService<S>
+where
+	S: Service<Request<Incoming>, Response = Response<Body>>,
+{
+	type Response = Response<Body>;
+	type Error = S::Error;
+	type Future = InFlightTrackingFuture<S::Future>;
+
+	fn call(&self, request: Request<Incoming>) -> Self::Future {
+		InFlightTrackingFuture {
+			inner: self.service.call(request),
+			some_in_flight_guard: self
+				.some_in_flight_counter
+				.as_ref()
+				.map(InFlightCounter::track),
+		}
+	}
+}
+
+#[pin_project]
+struct InFlightTrackingFuture<F> {
+	#[pin]
+	inner: F,
+	some_in_flight_guard: Option<InFlightGuard>,
+}
+
+impl<F, E> Future for InFlightTrackingFuture<F>
+where
+	F: Future<Output = Result<Response<Body>, E>>,
+{
+	type Output = Result<Response<Body>, E>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.project();
+
+		let response = ready!(this.inner.poll(cx))?;
+
+		// The request stays in flight until its response body is sent or dropped.
+		let response = match this.some_in_flight_guard.take() {
+			Some(in_flight_guard) => response.map(|body| {
+				Body::new(InFlightBody {
+					body,
+					_in_flight_guard: in_flight_guard,
+				})
+			}),
+			None => response,
+		};
+
+		Poll::Ready(Ok(response))
+	}
+}
+
+#[pin_project]
+struct InFlightBody {
+	#[pin]
+	body: Body,
+	_in_flight_guard: InFlightGuard,
+}
+
+impl HttpBody for InFlightBody {
+	type Data = Bytes;
+	type Error = BoxedError;
+
+	fn poll_frame(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+	) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+		self.project().body.poll_frame(cx)
+	}
+
+	fn is_end_stream(&self) -> bool {
+		self.body.is_end_stream()
+	}
+
+	fn size_hint(&self) -> SizeHint {
+		self.body.size_hint()
+	}
+}
+
+// ----------
+
+// Closes the connection when no bytes are received or sent within the timeout while
+// the connection has no requests in flight and is waiting for data.
+struct IdleTimeoutIo {
+	io: BoxedIo,
+	timeout: Duration,
+	pinned_sleep: Pin<Box<Sleep>>,
+	in_flight_counter: InFlightCounter,
+}
+
+impl IdleTimeoutIo {
+	fn new(io: BoxedIo, timeout: Duration, in_flight_counter: InFlightCounter) -> Self {
+		Self {
+			io,
+			timeout,
+			pinned_sleep: Box::pin(tokio::time::sleep(timeout)),
+			in_flight_counter,
+		}
+	}
+
+	fn reset(&mut self) {
+		self
+			.pinned_sleep
+			.as_mut()
+			.reset(tokio::time::Instant::now() + self.timeout);
+	}
+}
+
+impl AsyncRead for IdleTimeoutIo {
+	fn poll_read(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &mut ReadBuf<'_>,
+	) -> Poll<Result<(), IoError>> {
+		let this = self.get_mut();
+		let filled_len = buf.filled().len();
+
+		match Pin::new(&mut this.io).poll_read(cx, buf) {
+			Poll::Ready(Ok(())) => {
+				if buf.filled().len() > filled_len {
+					this.reset();
+				}
+
+				Poll::Ready(Ok(()))
+			}
+			Poll::Pending => {
+				// HTTP/2 connections and HTTP/1 connections waiting for the EOF keep reading
+				// while the requests are being handled. The timer starts over once the
+				// connection becomes idle.
+				if !this.in_flight_counter.is_idle(cx) {
+					this.reset();
+
+					return Poll::Pending;
+				}
+
+				if this.pinned_sleep.as_mut().poll(cx).is_ready() {
+					return Poll::Ready(Err(IoError::new(ErrorKind::TimedOut, "idle timeout")));
+				}
+
+				Poll::Pending
+			}
+			result => result,
+		}
+	}
+}
+
+impl AsyncWrite for IdleTimeoutIo {
+	fn poll_write(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		buf: &[u8],
+	) -> Poll<Result<usize, IoError>> {
+		let this = self.get_mut();
+
+		let result = Pin::new(&mut this.io).poll_write(cx, buf);
+		if let Poll::Ready(Ok(size)) = result {
+			if size > 0 {
+				this.reset();
+			}
+		}
+
+		result
+	}
+
+	fn poll_write_vectored(
+		self: Pin<&mut Self>,
+		cx: &mut Context<'_>,
+		bufs: &[IoSlice<'_>],
+	) -> Poll<Result<usize, IoError>> {
+		let this = self.get_mut();
+
+		let result = Pin::new(&mut this.io).poll_write_vectored(cx, bufs);
+		if let Poll::Ready(Ok(size)) = result {
+			if size > 0 {
+				this.reset();
+			}
+		}
+
+		result
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		self.io.is_write_vectored()
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+		Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+	}
+}
+
+// --------------------------------------------------
+
+/// An error type of server failures.
+///
+/// Up to `0.1.2`, this type was the tuple struct `ServerError(IoError)`. I/O failures
+/// other than bind failures are now returned as [`ServerError::Io`].
+#[non_exhaustive]
+#[derive(Debug, crate::ImplError)]
+pub enum ServerError {
+	/// Returned when a listener couldn't be bound to its address.
+	#[error("couldn't bind the listener to {address}: {source}")]
+	Bind { address: String, source: IoError },
+	/// Returned on other I/O failures.
+	#[error(transparent)]
+	Io(#[from] IoError),
+}
+
+// --------------------------------------------------
+
+#[cfg(test)]
+mod test {
+	use hyper_util::{rt::TokioExecutor, server::conn::auto::Builder};
+	use tokio::{
+		io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream},
+		sync::mpsc::{channel, Receiver},
+	};
+
+	use crate::{handler::HandlerSetter, http::Method, Resource};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	struct InMemoryListener(Receiver<(DuplexStream, SocketAddr)>);
+
+	impl Listener for InMemoryListener {
+		type Io = DuplexStream;
+
+		async fn accept(&mut self) -> Result<(Self::Io, SocketAddr), IoError> {
+			self.0.recv().await.ok_or(IoError::other("closed"))
+		}
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn bind_failure() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let taken_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+		let taken_address = taken_listener.local_addr().unwrap();
+
+		let server = Server::new(Builder::new(TokioExecutor::new()));
+
+		let error = server
+			.serve_on(
+				root.into_arc_service(),
+				[
+					ListenerConfig::tcp("127.0.0.1:0"),
+					ListenerConfig::tcp(taken_address),
+				],
+			)
+			.await
+			.unwrap_err();
+
+		let ServerError::Bind { address, .. } = error else {
+			panic!("unexpected error: {}", error);
+		};
+
+		assert_eq!(address, taken_address.to_string());
+
+		// ----------
+
+		#[cfg(unix)]
+		{
+			let mut root = Resource::new("/");
+			root.set_handler_for(Method::GET.to(|| async {}));
+
+			let error = server
+				.serve_on(
+					root.into_arc_service(),
+					[ListenerConfig::unix("/nonexistent-argan-dir/argan.sock")],
+				)
+				.await
+				.unwrap_err();
+
+			assert!(matches!(error, ServerError::Bind { .. }));
+		}
+	}
+
+	// --------------------------------------------------
+
+	#[cfg(feature = "peer-addr")]
+	#[tokio::test]
+	async fn custom_listener() {
+		use crate::request::PeerAddr;
+
+		let mut root = Resource::new("/");
+		root.set_handler_for(
+			Method::GET.to(|PeerAddr(peer_address): PeerAddr| async move { peer_address.to_string() }),
+		);
+
+		let service = root.into_arc_service();
+		let (sender, receiver) = channel(1);
+
+		let server_task = tokio::spawn(async move {
+			Server::new(Builder::new(TokioExecutor::new()))
+				.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+				.await
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "10.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\nconnection: close\r\n\r\n")
+			.await
+			.unwrap();
+
+		let mut response = String::new();
+		client_io.read_to_string(&mut response).await.unwrap();
+
+		assert!(response.starts_with("HTTP/1.1 200 OK"));
+		assert!(response.contains("\r\n10.0.0.1:4321\r\n"));
+
+		server_task.abort();
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn shutdown() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+		root
+			.subresource_mut("/pending")
+			.set_handler_for(Method::GET.to(pending::<()>));
+
+		let service = root.into_arc_service();
+
+		// ----------
+		// ShutdownHandle
+
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_graceful_shutdown_duration(Duration::from_millis(100))
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn({
+			let service = service.clone();
+
+			async move {
+				server
+					.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+					.await
+			}
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET /pending HTTP/1.1\r\nhost: example.com\r\n\r\n")
+			.await
+			.unwrap();
+
+		// Gives the server time to start handling the request.
+		tokio::time::sleep(Duration::from_millis(50)).await;
+
+		shutdown_handle.shutdown();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 1);
+
+		// ----------
+		// Shutdown signal
+
+		let (signal_sender, signal_receiver) = tokio::sync::oneshot::channel::<()>();
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_graceful_shutdown_duration(Duration::from_secs(1))
+			.with_shutdown_signal(async {
+				let _ = signal_receiver.await;
+			})
+			.without_built_in_signals();
+
+		let server_task = tokio::spawn(async move {
+			server
+				.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+				.await
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n")
+			.await
+			.unwrap();
+
+		let mut buffer = [0; 16];
+		client_io.read_exact(&mut buffer).await.unwrap();
+		assert!(buffer.starts_with(b"HTTP/1.1 200 OK"));
+
+		signal_sender.send(()).unwrap();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 0);
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn connection_limit_and_idle_timeout() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_arc_service();
+
+		// ----------
+		// Max connections
+
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_max_connections(1)
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn({
+			let service = service.clone();
+
+			async move {
+				server
+					.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+					.await
+			}
+		});
+
+		let (first_client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		let (mut second_client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4322".parse().unwrap()))
+			.await
+			.unwrap();
+
+		second_client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n")
+			.await
+			.unwrap();
+
+		let mut buffer = [0; 16];
+
+		// The second connection must wait until the first one closes.
+		assert!(tokio::time::timeout(
+			Duration::from_millis(100),
+			second_client_io.read_exact(&mut buffer),
+		)
+		.await
+		.is_err());
+
+		drop(first_client_io);
+
+		second_client_io.read_exact(&mut buffer).await.unwrap();
+		assert!(buffer.starts_with(b"HTTP/1.1 200 OK"));
+
+		shutdown_handle.shutdown();
+		server_task.await.unwrap().unwrap();
+
+		// ----------
+		// Idle timeout
+
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_idle_timeout(Duration::from_millis(50))
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn(async move {
+			server
+				.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+				.await
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n")
+			.await
+			.unwrap();
+
+		// The connection is kept alive after the response and then closed when it's idle.
+		let mut response = Vec::new();
+		tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+			.await
+			.unwrap()
+			.unwrap();
+
+		assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+
+		shutdown_handle.shutdown();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 0);
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn idle_timeout_with_slow_handler() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {
+			tokio::time::sleep(Duration::from_millis(200)).await;
+
+			"slow"
+		}));
+
+		let service = root.into_arc_service();
+
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_idle_timeout(Duration::from_millis(50))
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn(async move {
+			server
+				.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+				.await
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n")
+			.await
+			.unwrap();
+
+		// The handler takes longer than the idle timeout, but the timer doesn't run while
+		// the request is in flight. The connection is closed after the response is sent.
+		let mut response = Vec::new();
+		tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+			.await
+			.unwrap()
+			.unwrap();
+
+		assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+		assert!(response.ends_with(b"\r\nslow\r\n0\r\n\r\n"));
+
+		shutdown_handle.shutdown();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 0);
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn header_read_timeout() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_arc_service();
+
+		for config in [ServerConfig::new(), ServerConfig::new().http1_only()] {
+			let (sender, receiver) = channel(1);
+
+			let server = Server::with_config(config)
+				.with_header_read_timeout(Duration::from_millis(50))
+				.without_built_in_signals();
+
+			let shutdown_handle = server.shutdown_handle();
+
+			let server_task = tokio::spawn({
+				let service = service.clone();
+
+				async move {
+					server
+						.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+						.await
+				}
+			});
+
+			// Incomplete headers.
+			let (mut client_io, server_io) = duplex(1024);
+			sender
+				.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+				.await
+				.unwrap();
+
+			client_io
+				.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\n")
+				.await
+				.unwrap();
+
+			let mut response = Vec::new();
+			tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+				.await
+				.unwrap()
+				.unwrap();
+
+			assert!(!response.starts_with(b"HTTP/1.1 200 OK"));
+
+			// No bytes at all. The server waits for the beginning of the connection to
+			// detect the protocol no longer than the timeout.
+			let (mut client_io, server_io) = duplex(1024);
+			sender
+				.send((server_io, "127.0.0.1:4322".parse().unwrap()))
+				.await
+				.unwrap();
+
+			let mut response = Vec::new();
+			tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+				.await
+				.unwrap()
+				.unwrap();
+
+			assert!(!response.starts_with(b"HTTP/1.1 200 OK"));
+
+			shutdown_handle.shutdown();
+
+			let report = server_task.await.unwrap().unwrap();
+			assert_eq!(report.open_connections(), 0);
+		}
+	}
+
+	// --------------------------------------------------
+
+	#[cfg(feature = "tls")]
+	#[tokio::test]
+	async fn tls_handshake_timeout() {
+		use tokio_rustls::rustls::crypto::aws_lc_rs;
+
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_arc_service();
+
+		let tls_server_config =
+			TlsServerConfig::builder_with_provider(Arc::new(aws_lc_rs::default_provider()))
+				.with_safe_default_protocol_versions()
+				.unwrap()
+				.with_no_client_auth()
+				.with_cert_resolver(Arc::new(SniCertResolver::new()));
+
+		let (sender, receiver) = channel(1);
+
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_tls_handshake_timeout(Duration::from_millis(50))
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn(async move {
+			server
+				.serve_on(
+					service,
+					[ListenerConfig::new(InMemoryListener(receiver)).with_tls(tls_server_config)],
+				)
+				.await
+		});
+
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		// The client never starts the handshake, so the connection is closed when
+		// the timeout expires.
+		let mut response = Vec::new();
+		tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+			.await
+			.unwrap()
+			.unwrap();
+
+		assert!(response.is_empty());
+
+		shutdown_handle.shutdown();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 0);
+	}
+
+	// --------------------------------------------------
+
+	#[cfg(feature = "tls")]
+	#[tokio::test]
+	async fn preface_timeout_falls_back_to_tls_handshake_timeout() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_arc_service();
+
+		let (sender, receiver) = channel(1);
+
+		// The server serves both HTTP/1 and HTTP/2 on a plaintext listener and has only
+		// the TLS handshake timeout.
+		let server = Server::new(Builder::new(TokioExecutor::new()))
+			.with_tls_handshake_timeout(Duration::from_millis(50))
+			.without_built_in_signals();
+
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn(async move {
+			server
+				.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+				.await
+		});
+
+		// A client that sends its request in time is served.
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+			.await
+			.unwrap();
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\nconnection: close\r\n\r\n")
+			.await
+			.unwrap();
+
+		let mut response = Vec::new();
+		tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+			.await
+			.unwrap()
+			.unwrap();
+
+		assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+
+		// A client that never sends anything is disconnected when the timeout expires.
+		let (mut client_io, server_io) = duplex(1024);
+		sender
+			.send((server_io, "127.0.0.1:4322".parse().unwrap()))
+			.await
+			.unwrap();
+
+		let mut response = Vec::new();
+		tokio::time::timeout(Duration::from_secs(1), client_io.read_to_end(&mut response))
+			.await
+			.unwrap()
+			.unwrap();
+
+		assert!(response.is_empty());
+
+		shutdown_handle.shutdown();
+
+		let report = server_task.await.unwrap().unwrap();
+		assert_eq!(report.open_connections(), 0);
+	}
+
+	// --------------------------------------------------
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn unix_socket() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async { "unix" }));
+
+		let service = root.into_arc_service();
+
+		let socket_path = std::env::temp_dir().join(format!("argan-{}.sock", std::process::id()));
+
+		let server = Server::new(Builder::new(TokioExecutor::new())).without_built_in_signals();
+		let shutdown_handle = server.shutdown_handle();
+
+		let server_task = tokio::spawn({
+			let socket_path = socket_path.clone();
+
+			async move {
+				server
+					.serve_on(service, [ListenerConfig::unix(socket_path)])
+					.await
+			}
+		});
+
+		let mut client_io = loop {
+			match UnixStream::connect(&socket_path).await {
+				Ok(stream) => break stream,
+				Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+			}
+		};
+
+		client_io
+			.write_all(b"GET / HTTP/1.1\r\nhost: example.com\r\nconnection: close\r\n\r\n")
+			.await
+			.unwrap();
+
+		let mut response = Vec::new();
+		client_io.read_to_end(&mut response).await.unwrap();
+
+		assert!(response.starts_with(b"HTTP/1.1 200 OK"));
+		assert!(response.ends_with(b"\r\nunix\r\n0\r\n\r\n"));
+
+		shutdown_handle.shutdown();
+		server_task.await.unwrap().unwrap();
+
+		// The socket file is removed when the server stops.
+		assert!(!socket_path.exists());
+	}
+
+	// --------------------------------------------------
+
+	#[tokio::test]
+	async fn server_config() {
+		let mut root = Resource::new("/");
+		root.set_handler_for(Method::GET.to(|| async {}));
+
+		let service = root.into_arc_service();
+
+		let http2_preface: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+		let http1_request: &[u8] = b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n";
+
+		for (config, serves_http1, serves_http2) in [
+			(ServerConfig::new(), true, true),
+			(
+				ServerConfig::new().with_h2c_prior_knowledge(false),
+				true,
+				false,
+			),
+			(ServerConfig::new().http1_only(), true, false),
+			(ServerConfig::new().http2_only(), false, true),
+		] {
+			let (sender, receiver) = channel(1);
+
+			let server = Server::with_config(config).without_built_in_signals();
+			let shutdown_handle = server.shutdown_handle();
+
+			let server_task = tokio::spawn({
+				let service = service.clone();
+
+				async move {
+					server
+						.serve_on(service, [ListenerConfig::new(InMemoryListener(receiver))])
+						.await
+				}
+			});
+
+			for (request, is_served) in [(http1_request, serves_http1), (http2_preface, serves_http2)] {
+				let (mut client_io, server_io) = duplex(1024);
+				sender
+					.send((server_io, "127.0.0.1:4321".parse().unwrap()))
+					.await
+					.unwrap();
+
+				client_io.write_all(request).await.unwrap();
+
+				let mut buffer = [0; 9];
+				let result = client_io.read_exact(&mut buffer).await;
+
+				if !is_served {
+					// The connection is closed without a response.
+					assert!(result.is_err());
+
+					continue;
+				}
+
+				result.unwrap();
+
+				if request == http1_request {
+					assert!(buffer.starts_with(b"HTTP/1.1 "));
+				} else {
+					// An HTTP/2 server starts with the SETTINGS frame (type 0x4).
+					assert_eq!(buffer[3], 0x4);
+				}
+			}
+
+			shutdown_handle.shutdown();
+			server_task.await.unwrap().unwrap();
+		}
+	}
+}
@@ -0,0 +1,575 @@
+use std::{
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex, RwLock},
+	time::{Duration, SystemTime},
+};
+
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::{
+	crypto::CryptoProvider,
+	pki_types::{
+		pem::{Error as PemError, PemObject},
+		CertificateDer, PrivateKeyDer,
+	},
+	server::{ClientHello, ResolvesServerCert},
+	sign::CertifiedKey,
+	Error as RustlsError, ServerConfig as TlsServerConfig,
+};
+
+use crate::{
+	host::{parse_host_pattern, HostPatternError},
+	pattern::{ParamsList, Pattern, Similarity},
+};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// TlsConfigSource
+
+/// A source of the TLS configuration that can be swapped while the server is running.
+///
+/// Each TLS handshake uses the current configuration of the source. Clones of the
+/// source share the configuration, so one of them can be given to the [`Server`] and
+/// another one can be used to replace or reload the configuration.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use argan::{
+///   Resource,
+///   Server,
+///   http::Method,
+///   handler::HandlerSetter,
+///   server::{ListenerConfig, TlsConfigSource},
+/// };
+/// use hyper_util::{server::conn::auto::Builder, rt::TokioExecutor};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut root = Resource::new("/");
+/// root.set_handler_for(Method::GET.to(|| async { "Hello, World!" }));
+///
+/// let tls_config_source = TlsConfigSource::from_pem_files("cert.pem", "privkey.pem")?;
+///
+/// // Renewed certificates are picked up within a minute.
+/// let _ = tls_config_source.reload_on_change(Duration::from_secs(60))?;
+///
+/// let _ = Server::new(Builder::new(TokioExecutor::new()))
+///   .serve_on(
+///     root.into_arc_service(),
+///     [ListenerConfig::tcp("localhost:8443").with_tls_source(tls_config_source)],
+///   )
+///   .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Server`]: crate::Server
+#[derive(Clone)]
+pub struct TlsConfigSource {
+	current: Arc<RwLock<Arc<TlsServerConfig>>>,
+	some_pem_files: Option<Arc<PemFiles>>,
+	alpn_cache: Arc<Mutex<Option<AlpnConfig>>>,
+}
+
+// The current configuration with the ALPN protocols set by the server.
+struct AlpnConfig {
+	original: Arc<TlsServerConfig>,
+	with_alpn_protocols: Arc<TlsServerConfig>,
+}
+
+impl TlsConfigSource {
+	/// Creates a new source with the given configuration.
+	pub fn new(tls_server_config: TlsServerConfig) -> Self {
+		Self {
+			current: Arc::new(RwLock::new(Arc::new(tls_server_config))),
+			some_pem_files: None,
+			alpn_cache: Arc::new(Mutex::new(None)),
+		}
+	}
+
+	/// Creates a new source with the certificate chain and the private key loaded from
+	/// the PEM files.
+	///
+	/// The configuration is created with the process-level default [`CryptoProvider`].
+	/// It can be reloaded from the same files with [`Self::reload()`].
+	///
+	/// [`CryptoProvider`]: https://docs.rs/rustls/latest/rustls/crypto/struct.CryptoProvider.html
+	pub fn from_pem_files<C, K>(
+		cert_chain_path: C,
+		private_key_path: K,
+	) -> Result<Self, TlsConfigError>
+	where
+		C: AsRef<Path>,
+		K: AsRef<Path>,
+	{
+		let pem_files = PemFiles {
+			cert_chain_path: cert_chain_path.as_ref().to_owned(),
+			private_key_path: private_key_path.as_ref().to_owned(),
+		};
+
+		let tls_server_config = pem_files.load_tls_server_config()?;
+
+		Ok(Self {
+			current: Arc::new(RwLock::new(Arc::new(tls_server_config))),
+			some_pem_files: Some(Arc::new(pem_files)),
+			alpn_cache: Arc::new(Mutex::new(None)),
+		})
+	}
+
+	/// Returns the current configuration.
+	pub fn current(&self) -> Arc<TlsServerConfig> {
+		self
+			.current
+			.read()
+			.expect("the lock should never be poisoned")
+			.clone()
+	}
+
+	// Returns the current configuration with the given ALPN protocols. The modified
+	// configuration is cached until the current configuration is replaced.
+	pub(super) fn current_with_alpn_protocols(
+		&self,
+		alpn_protocols: &[Vec<u8>],
+	) -> Arc<TlsServerConfig> {
+		let current = self.current();
+
+		let mut alpn_cache = self
+			.alpn_cache
+			.lock()
+			.expect("the lock should never be poisoned");
+
+		if let Some(alpn_config) = alpn_cache.as_ref() {
+			if Arc::ptr_eq(&alpn_config.original, &current)
+				&& alpn_config.with_alpn_protocols.alpn_protocols == alpn_protocols
+			{
+				return alpn_config.with_alpn_protocols.clone();
+			}
+		}
+
+		let mut tls_server_config = TlsServerConfig::clone(&current);
+		tls_server_config.alpn_protocols = alpn_protocols.to_vec();
+
+		let with_alpn_protocols = Arc::new(tls_server_config);
+		*alpn_cache = Some(AlpnConfig {
+			original: current,
+			with_alpn_protocols: with_alpn_protocols.clone(),
+		});
+
+		with_alpn_protocols
+	}
+
+	/// Replaces the current configuration. New TLS handshakes use the given configuration.
+	pub fn replace(&self, tls_server_config: TlsServerConfig) {
+		*self
+			.current
+			.write()
+			.expect("the lock should never be poisoned") = Arc::new(tls_server_config);
+	}
+
+	/// Reloads the configuration from the PEM files that the source was created with.
+	///
+	/// On failure, the current configuration is kept.
+	pub fn reload(&self) -> Result<(), TlsConfigError> {
+		let Some(pem_files) = self.some_pem_files.as_ref() else {
+			return Err(TlsConfigError::NoPemFiles);
+		};
+
+		self.replace(pem_files.load_tls_server_config()?);
+
+		Ok(())
+	}
+
+	/// Spawns a task that checks the modification time of the PEM files at the given
+	/// `interval` and reloads the configuration when they change.
+	///
+	/// Failed reloads keep the current configuration and are retried on the next change.
+	/// The task can be stopped with the returned handle.
+	///
+	/// Returns [`TlsConfigError::NoPemFiles`] if the source wasn't created from PEM files.
+	pub fn reload_on_change(&self, interval: Duration) -> Result<JoinHandle<()>, TlsConfigError> {
+		let Some(pem_files) = self.some_pem_files.clone() else {
+			return Err(TlsConfigError::NoPemFiles);
+		};
+
+		let tls_config_source = self.clone();
+
+		Ok(tokio::spawn(async move {
+			let mut some_last_modified = pem_files.last_modified();
+
+			loop {
+				tokio::time::sleep(interval).await;
+
+				let some_modified = pem_files.last_modified();
+				if some_modified == some_last_modified {
+					continue;
+				}
+
+				if let Ok(tls_server_config) = pem_files.load_tls_server_config() {
+					tls_config_source.replace(tls_server_config);
+					some_last_modified = some_modified;
+				}
+			}
+		}))
+	}
+}
+
+impl From<TlsServerConfig> for TlsConfigSource {
+	fn from(tls_server_config: TlsServerConfig) -> Self {
+		Self::new(tls_server_config)
+	}
+}
+
+// ----------
+
+struct PemFiles {
+	cert_chain_path: PathBuf,
+	private_key_path: PathBuf,
+}
+
+impl PemFiles {
+	fn load_tls_server_config(&self) -> Result<TlsServerConfig, TlsConfigError> {
+		let crypto_provider = CryptoProvider::get_default().ok_or(TlsConfigError::NoCryptoProvider)?;
+
+		let (cert_chain, private_key) = load_pem_files(&self.cert_chain_path, &self.private_key_path)?;
+
+		TlsServerConfig::builder_with_provider(crypto_provider.clone())
+			.with_safe_default_protocol_versions()?
+			.with_no_client_auth()
+			.with_single_cert(cert_chain, private_key)
+			.map_err(TlsConfigError::from)
+	}
+
+	// Returns the latest modification time of the files.
+	fn last_modified(&self) -> Option<SystemTime> {
+		[&self.cert_chain_path, &self.private_key_path]
+			.into_iter()
+			.filter_map(|path| {
+				std::fs::metadata(path)
+					.and_then(|metadata| metadata.modified())
+					.ok()
+			})
+			.max()
+	}
+}
+
+// ----------
+
+/// Loads the certificate chain and the private key from the PEM files and creates
+/// a [`CertifiedKey`] with the process-level default [`CryptoProvider`].
+///
+/// [`CertifiedKey`]: https://docs.rs/rustls/latest/rustls/sign/struct.CertifiedKey.html
+/// [`CryptoProvider`]: https://docs.rs/rustls/latest/rustls/crypto/struct.CryptoProvider.html
+pub fn certified_key_from_pem_files<C, K>(
+	cert_chain_path: C,
+	private_key_path: K,
+) -> Result<CertifiedKey, TlsConfigError>
+where
+	C: AsRef<Path>,
+	K: AsRef<Path>,
+{
+	let crypto_provider = CryptoProvider::get_default().ok_or(TlsConfigError::NoCryptoProvider)?;
+
+	let (cert_chain, private_key) =
+		load_pem_files(cert_chain_path.as_ref(), private_key_path.as_ref())?;
+
+	let signing_key = crypto_provider.key_provider.load_private_key(private_key)?;
+
+	Ok(CertifiedKey::new(cert_chain, signing_key))
+}
+
+fn load_pem_files(
+	cert_chain_path: &Path,
+	private_key_path: &Path,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), TlsConfigError> {
+	let pem_error = |path: &Path| {
+		let path = path.display().to_string();
+
+		move |source| TlsConfigError::Pem { path, source }
+	};
+
+	let cert_chain = CertificateDer::pem_file_iter(cert_chain_path)
+		.and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+		.map_err(pem_error(cert_chain_path))?;
+
+	let private_key =
+		PrivateKeyDer::from_pem_file(private_key_path).map_err(pem_error(private_key_path))?;
+
+	Ok((cert_chain, private_key))
+}
+
+// --------------------------------------------------
+// SniCertResolver
+
+/// A certificate resolver that selects the certificate by the server name the client
+/// sends in the TLS handshake (SNI).
+///
+/// Server names are matched against the same host patterns that [`Host`] uses. Static
+/// patterns are checked before the regex patterns.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use argan::server::{certified_key_from_pem_files, SniCertResolver, TlsConfigSource};
+/// use rustls::ServerConfig as TlsServerConfig;
+///
+/// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut sni_cert_resolver = SniCertResolver::new();
+/// sni_cert_resolver.add_cert(
+///   "example.com",
+///   certified_key_from_pem_files("example.pem", "example.key.pem")?,
+/// );
+///
+/// sni_cert_resolver.add_cert(
+///   "{sub}.example.com",
+///   certified_key_from_pem_files("wildcard.pem", "wildcard.key.pem")?,
+/// );
+///
+/// let tls_server_config = TlsServerConfig::builder()
+///   .with_no_client_auth()
+///   .with_cert_resolver(Arc::new(sni_cert_resolver));
+///
+/// let tls_config_source = TlsConfigSource::new(tls_server_config);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Host`]: crate::Host
+#[derive(Debug, Default)]
+pub struct SniCertResolver {
+	static_hosts: Vec<(Pattern, Arc<CertifiedKey>)>,
+	regex_hosts: Vec<(Pattern, Arc<CertifiedKey>)>,
+	some_default_cert: Option<Arc<CertifiedKey>>,
+}
+
+impl SniCertResolver {
+	/// Creates a new, empty resolver.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds the certificate for the server names that match the `host_pattern`.
+	///
+	/// # Panics
+	/// - if the pattern is empty or a wildcard
+	/// - if a certificate was already added for the same pattern
+	pub fn add_cert<P: AsRef<str>>(&mut self, host_pattern: P, certified_key: CertifiedKey) {
+		let host_pattern = match parse_host_pattern(host_pattern) {
+			Ok(host_pattern) => host_pattern,
+			Err(HostPatternError::Empty) => panic!("empty host pattern"),
+			Err(HostPatternError::Wildcard) => panic!("host pattern cannot be a wildcard"),
+		};
+
+		let hosts = if host_pattern.is_static() {
+			&mut self.static_hosts
+		} else {
+			&mut self.regex_hosts
+		};
+
+		if hosts
+			.iter()
+			.any(|(existing_pattern, _)| existing_pattern.compare(&host_pattern) == Similarity::Same)
+		{
+			panic!("{} host already has a certificate", host_pattern);
+		}
+
+		hosts.push((host_pattern, Arc::new(certified_key)));
+	}
+
+	/// Sets the certificate that's used when the client doesn't send a server name, or
+	/// when the server name doesn't match any of the host patterns.
+	pub fn set_default_cert(&mut self, certified_key: CertifiedKey) {
+		self.some_default_cert = Some(Arc::new(certified_key));
+	}
+
+	fn resolve_server_name(&self, some_server_name: Option<&str>) -> Option<Arc<CertifiedKey>> {
+		if let Some(server_name) = some_server_name {
+			if let Some((_, certified_key)) = self
+				.static_hosts
+				.iter()
+				.find(|(pattern, _)| pattern.is_static_match(server_name) == Some(true))
+			{
+				return Some(certified_key.clone());
+			}
+
+			if let Some((_, certified_key)) = self.regex_hosts.iter().find(|(pattern, _)| {
+				pattern.is_regex_or_constrained_match(server_name, &mut ParamsList::new()) == Some(true)
+			}) {
+				return Some(certified_key.clone());
+			}
+		}
+
+		self.some_default_cert.clone()
+	}
+}
+
+impl ResolvesServerCert for SniCertResolver {
+	fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+		self.resolve_server_name(client_hello.server_name())
+	}
+}
+
+// --------------------------------------------------
+// TlsConfigError
+
+/// An error type of TLS configuration failures.
+#[non_exhaustive]
+#[derive(Debug, crate::ImplError)]
+pub enum TlsConfigError {
+	/// Returned when a PEM file couldn't be read or parsed.
+	#[error("couldn't load {path}: {source}")]
+	Pem { path: String, source: PemError },
+	/// Returned when the certificate or the key is rejected.
+	#[error(transparent)]
+	Rustls(#[from] RustlsError),
+	/// Returned when there is no process-level default crypto provider.
+	#[error("no process-level default crypto provider")]
+	NoCryptoProvider,
+	/// Returned when the source that wasn't created from PEM files is asked to reload
+	/// or to watch the files for changes.
+	#[error("no PEM files to reload from")]
+	NoPemFiles,
+}
+
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use tokio_rustls::rustls::{
+		sign::{Signer, SigningKey},
+		SignatureAlgorithm, SignatureScheme,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Debug)]
+	struct DummySigningKey;
+
+	impl SigningKey for DummySigningKey {
+		fn choose_scheme(&self, _offered: &[SignatureScheme]) -> Option<Box<dyn Signer>> {
+			None
+		}
+
+		fn algorithm(&self) -> SignatureAlgorithm {
+			SignatureAlgorithm::ED25519
+		}
+	}
+
+	fn certified_key(cert: &'static [u8]) -> CertifiedKey {
+		CertifiedKey::new(vec![CertificateDer::from(cert)], Arc::new(DummySigningKey))
+	}
+
+	fn resolved_cert(resolver: &SniCertResolver, server_name: Option<&str>) -> Option<Vec<u8>> {
+		resolver
+			.resolve_server_name(server_name)
+			.map(|certified_key| certified_key.cert[0].to_vec())
+	}
+
+	// --------------------------------------------------
+
+	#[test]
+	fn sni_cert_resolver() {
+		let mut resolver = SniCertResolver::new();
+		resolver.add_cert("http://example.com", certified_key(b"example"));
+		resolver.add_cert("{sub}.example.com", certified_key(b"sub"));
+		resolver.add_cert("https://abc.example.com/", certified_key(b"abc"));
+
+		assert_eq!(
+			resolved_cert(&resolver, Some("example.com")).unwrap(),
+			b"example"
+		);
+
+		assert_eq!(
+			resolved_cert(&resolver, Some("abc.example.com")).unwrap(),
+			b"abc"
+		);
+		assert_eq!(
+			resolved_cert(&resolver, Some("xyz.example.com")).unwrap(),
+			b"sub"
+		);
+		assert!(resolved_cert(&resolver, Some("example.org")).is_none());
+		assert!(resolved_cert(&resolver, None).is_none());
+
+		resolver.set_default_cert(certified_key(b"default"));
+
+		assert_eq!(
+			resolved_cert(&resolver, Some("example.org")).unwrap(),
+			b"default"
+		);
+
+		assert_eq!(resolved_cert(&resolver, None).unwrap(), b"default");
+	}
+
+	#[test]
+	#[should_panic(expected = "already has a certificate")]
+	fn sni_cert_resolver_duplicate() {
+		let mut resolver = SniCertResolver::new();
+		resolver.add_cert("{sub}.example.com", certified_key(b"sub"));
+		resolver.add_cert("http://{sub}.example.com", certified_key(b"sub"));
+	}
+
+	#[test]
+	fn tls_config_source() {
+		let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+		let error = TlsConfigSource::from_pem_files("nonexistent.pem", "nonexistent.key.pem")
+			.err()
+			.unwrap();
+
+		let TlsConfigError::Pem { path, .. } = error else {
+			panic!("unexpected error: {}", error);
+		};
+
+		assert_eq!(path, "nonexistent.pem");
+
+		// ----------
+
+		let tls_server_config = || {
+			TlsServerConfig::builder()
+				.with_no_client_auth()
+				.with_cert_resolver(Arc::new(SniCertResolver::new()))
+		};
+
+		let tls_config_source = TlsConfigSource::new(tls_server_config());
+		let shared_tls_config_source = tls_config_source.clone();
+
+		let old_tls_server_config = shared_tls_config_source.current();
+		tls_config_source.replace(tls_server_config());
+
+		assert!(!Arc::ptr_eq(
+			&old_tls_server_config,
+			&shared_tls_config_source.current()
+		));
+
+		assert!(matches!(
+			tls_config_source.reload(),
+			Err(TlsConfigError::NoPemFiles)
+		));
+
+		assert!(matches!(
+			tls_config_source.reload_on_change(Duration::from_secs(1)),
+			Err(TlsConfigError::NoPemFiles)
+		));
+
+		// ----------
+
+		let alpn_protocols = [b"h2".to_vec(), b"http/1.1".to_vec()];
+
+		let with_alpn_protocols = tls_config_source.current_with_alpn_protocols(&alpn_protocols);
+		assert_eq!(with_alpn_protocols.alpn_protocols, alpn_protocols);
+		assert!(tls_config_source.current().alpn_protocols.is_empty());
+
+		assert!(Arc::ptr_eq(
+			&with_alpn_protocols,
+			&tls_config_source.current_with_alpn_protocols(&alpn_protocols)
+		));
+
+		tls_config_source.replace(tls_server_config());
+
+		assert!(!Arc::ptr_eq(
+			&with_alpn_protocols,
+			&tls_config_source.current_with_alpn_protocols(&alpn_protocols)
+		));
+	}
+}
@@ -0,0 +1,489 @@
+//! An in-process client to test services.
+
+// ----------
+
+use std::{
+	convert::Infallible,
+	net::SocketAddr,
+	sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use http::{
+	header::{CONTENT_TYPE, COOKIE, SET_COOKIE},
+	request::Builder as RequestBuilder,
+	HeaderMap, HeaderName, HeaderValue, Method, StatusCode,
+};
+use http_body_util::{BodyExt, Full};
+use hyper::service::Service;
+
+use crate::{common::CloneWithPeerAddr, request::Request, response::Response};
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+// --------------------------------------------------
+// TestClient
+
+/// A client that calls the service in the same process, without a server.
+///
+/// The client can wrap any of the router, host and resource services. It keeps the
+/// cookies that the service sets and sends them with the following requests.
+///
+/// ```
+/// use argan::{Resource, handler::HandlerSetter, http::Method, test_client::TestClient};
+///
+/// # async fn run() {
+/// let mut root = Resource::new("/");
+/// root
+///   .subresource_mut("/greeting")
+///   .set_handler_for(Method::GET.to(|| async { "Hello, World!" }));
+///
+/// let client = TestClient::new(root.into_arc_service());
+///
+/// let response = client.get("/greeting").send().await;
+/// assert_eq!(response.status(), 200);
+/// assert_eq!(response.text(), "Hello, World!");
+/// # }
+/// ```
+pub struct TestClient<S> {
+	service: S,
+	peer_addr: SocketAddr,
+	cookie_jar: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl<S> TestClient<S>
+where
+	S: Service<Request<Full<Bytes>>, Response = Response, Error = Infallible> + CloneWithPeerAddr,
+{
+	/// Creates a new client of the `service`.
+	pub fn new(service: S) -> Self {
+		Self {
+			service,
+			peer_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+			cookie_jar: Arc::new(Mutex::new(Vec::new())),
+		}
+	}
+
+	/// Sets the peer address that the service receives with the requests.
+	///
+	/// The address can be extracted with the `PeerAddr` extractor when the `peer-addr`
+	/// feature flag is enabled. By default, it's `127.0.0.1:0`.
+	pub fn with_peer_addr(mut self, peer_addr: SocketAddr) -> Self {
+		self.peer_addr = peer_addr;
+
+		self
+	}
+
+	/// Starts a request with the given method and URI.
+	pub fn request<U: AsRef<str>>(&self, method: Method, uri: U) -> TestRequest<'_, S> {
+		TestRequest {
+			client: self,
+			builder: RequestBuilder::new().method(method).uri(uri.as_ref()),
+			body: Bytes::new(),
+		}
+	}
+
+	/// Starts a `GET` request.
+	pub fn get<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::GET, uri)
+	}
+
+	/// Starts a `POST` request.
+	pub fn post<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::POST, uri)
+	}
+
+	/// Starts a `PUT` request.
+	pub fn put<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::PUT, uri)
+	}
+
+	/// Starts a `PATCH` request.
+	pub fn patch<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::PATCH, uri)
+	}
+
+	/// Starts a `DELETE` request.
+	pub fn delete<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::DELETE, uri)
+	}
+
+	/// Starts a `HEAD` request.
+	pub fn head<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::HEAD, uri)
+	}
+
+	/// Starts an `OPTIONS` request.
+	pub fn options<U: AsRef<str>>(&self, uri: U) -> TestRequest<'_, S> {
+		self.request(Method::OPTIONS, uri)
+	}
+
+	/// Returns the value of the cookie with the given `name` from the client's cookie jar.
+	pub fn cookie(&self, name: &str) -> Option<String> {
+		self
+			.cookie_jar
+			.lock()
+			.expect("the lock should never be poisoned")
+			.iter()
+			.find_map(|(cookie_name, value)| (cookie_name == name).then(|| value.clone()))
+	}
+
+	/// Adds the cookie to the client's cookie jar, replacing the existing cookie with
+	/// the same name.
+	pub fn set_cookie<N, V>(&self, name: N, value: V)
+	where
+		N: Into<String>,
+		V: Into<String>,
+	{
+		let mut cookie_jar = self
+			.cookie_jar
+			.lock()
+			.expect("the lock should never be poisoned");
+
+		let name = name.into();
+		cookie_jar.retain(|(cookie_name, _)| cookie_name != &name);
+		cookie_jar.push((name, value.into()));
+	}
+
+	/// Removes all the cookies from the client's cookie jar.
+	pub fn clear_cookies(&self) {
+		self
+			.cookie_jar
+			.lock()
+			.expect("the lock should never be poisoned")
+			.clear();
+	}
+
+	fn cookie_header_value(&self) -> Option<HeaderValue> {
+		let cookie_jar = self
+			.cookie_jar
+			.lock()
+			.expect("the lock should never be poisoned");
+
+		if cookie_jar.is_empty() {
+			return None;
+		}
+
+		let cookies = cookie_jar
+			.iter()
+			.map(|(name, value)| format!("{}={}", name, value))
+			.collect::<Vec<_>>()
+			.join("; ");
+
+		HeaderValue::try_from(cookies).ok()
+	}
+
+	fn update_cookies(&self, headers: &HeaderMap) {
+		for set_cookie in headers.get_all(SET_COOKIE) {
+			let Ok(set_cookie) = set_cookie.to_str() else {
+				continue;
+			};
+
+			let mut parts = set_cookie.split(';');
+
+			let Some((name, value)) = parts.next().and_then(|pair| pair.split_once('=')) else {
+				continue;
+			};
+
+			let name = name.trim();
+			let value = value.trim();
+
+			let expired = parts.any(|attribute| {
+				attribute
+					.split_once('=')
+					.filter(|(key, _)| key.trim().eq_ignore_ascii_case("max-age"))
+					.and_then(|(_, max_age)| max_age.trim().parse::<i64>().ok())
+					.is_some_and(|max_age| max_age <= 0)
+			});
+
+			if expired {
+				self
+					.cookie_jar
+					.lock()
+					.expect("the lock should never be poisoned")
+					.retain(|(cookie_name, _)| cookie_name != name);
+			} else {
+				self.set_cookie(name, value);
+			}
+		}
+	}
+}
+
+// --------------------------------------------------
+// TestRequest
+
+/// A request builder of the [`TestClient`].
+pub struct TestRequest<'c, S> {
+	client: &'c TestClient<S>,
+	builder: RequestBuilder,
+	body: Bytes,
+}
+
+impl<'c, S> TestRequest<'c, S>
+where
+	S: Service<Request<Full<Bytes>>, Response = Response, Error = Infallible> + CloneWithPeerAddr,
+{
+	/// Adds a header to the request.
+	///
+	/// # Panics
+	/// - if the name or the value is not valid
+	pub fn header<N, V>(mut self, name: N, value: V) -> Self
+	where
+		HeaderName: TryFrom<N>,
+		<HeaderName as TryFrom<N>>::Error: Into<http::Error>,
+		HeaderValue: TryFrom<V>,
+		<HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+	{
+		self.builder = self.builder.header(name, value);
+
+		self
+	}
+
+	/// Sets the body of the request.
+	pub fn body<B: Into<Bytes>>(mut self, body: B) -> Self {
+		self.body = body.into();
+
+		self
+	}
+
+	/// Sets the text body of the request with the `text/plain` content type.
+	pub fn text<T: Into<String>>(self, text: T) -> Self {
+		self
+			.header(CONTENT_TYPE, mime::TEXT_PLAIN_UTF_8.as_ref())
+			.body(text.into())
+	}
+
+	/// Sets the JSON body of the request with the `application/json` content type.
+	///
+	/// # Panics
+	/// - if the value fails to serialize
+	#[cfg(feature = "json")]
+	pub fn json<T: serde::Serialize>(self, value: &T) -> Self {
+		let json = serde_json::to_vec(value).expect("value should serialize to JSON");
+
+		self
+			.header(CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+			.body(json)
+	}
+
+	/// Sets the form body of the request with the `application/x-www-form-urlencoded`
+	/// content type.
+	///
+	/// # Panics
+	/// - if the value fails to serialize
+	#[cfg(feature = "form")]
+	pub fn form<T: serde::Serialize>(self, value: &T) -> Self {
+		let form = serde_urlencoded::to_string(value).expect("value should serialize to a form");
+
+		self
+			.header(CONTENT_TYPE, mime::APPLICATION_WWW_FORM_URLENCODED.as_ref())
+			.body(form)
+	}
+
+	/// Sends the request to the service and returns its response.
+	///
+	/// The cookies of the client's cookie jar are sent with the request, and the cookies
+	/// that the service sets are stored in the jar.
+	///
+	/// # Panics
+	/// - if the request is not valid
+	/// - if the response body fails to be collected
+	pub async fn send(self) -> TestResponse {
+		let TestRequest {
+			client,
+			mut builder,
+			body,
+		} = self;
+
+		if let Some(cookie_header_value) = client.cookie_header_value() {
+			let has_cookie_header = builder
+				.headers_ref()
+				.is_some_and(|headers| headers.contains_key(COOKIE));
+
+			if !has_cookie_header {
+				builder = builder.header(COOKIE, cookie_header_value);
+			}
+		}
+
+		let request = builder
+			.body(Full::new(body))
+			.expect("request should be valid");
+
+		let service = client.service.clone_with_peer_addr(client.peer_addr);
+		let response = match service.call(request).await {
+			Ok(response) => response,
+			Err(infallible) => match infallible {},
+		};
+
+		let (head, body) = response.into_parts();
+
+		client.update_cookies(&head.headers);
+
+		let body = body
+			.collect()
+			.await
+			.expect("response body should be collected")
+			.to_bytes();
+
+		TestResponse {
+			status: head.status,
+			headers: head.headers,
+			body,
+		}
+	}
+}
+
+// --------------------------------------------------
+// TestResponse
+
+/// A response returned by the [`TestClient`].
+#[derive(Debug)]
+pub struct TestResponse {
+	status: StatusCode,
+	headers: HeaderMap,
+	body: Bytes,
+}
+
+impl TestResponse {
+	/// Returns the status code of the response.
+	#[inline(always)]
+	pub fn status(&self) -> StatusCode {
+		self.status
+	}
+
+	/// Returns the headers of the response.
+	#[inline(always)]
+	pub fn headers(&self) -> &HeaderMap {
+		&self.headers
+	}
+
+	/// Returns the value of the header with the given `name` if it exists and is a
+	/// visible ASCII string.
+	pub fn header<N: AsRef<str>>(&self, name: N) -> Option<&str> {
+		self
+			.headers
+			.get(name.as_ref())
+			.and_then(|value| value.to_str().ok())
+	}
+
+	/// Returns the body of the response.
+	#[inline(always)]
+	pub fn bytes(&self) -> &Bytes {
+		&self.body
+	}
+
+	/// Returns the body of the response as text.
+	///
+	/// # Panics
+	/// - if the body is not valid UTF-8
+	pub fn text(&self) -> &str {
+		std::str::from_utf8(&self.body).expect("response body should be valid UTF-8")
+	}
+
+	/// Deserializes the JSON body of the response.
+	///
+	/// # Panics
+	/// - if the body is not valid JSON of the type `T`
+	#[cfg(feature = "json")]
+	pub fn json<T: serde::de::DeserializeOwned>(&self) -> T {
+		serde_json::from_slice(&self.body).expect("response body should be valid JSON of the type")
+	}
+}
+
+// --------------------------------------------------------------------------------
+// --------------------------------------------------------------------------------
+
+#[cfg(all(test, feature = "full"))]
+mod test {
+	use serde::{Deserialize, Serialize};
+
+	use crate::{
+		data::json::Json,
+		handler::HandlerSetter,
+		request::{PeerAddr, RequestHead},
+		Router,
+	};
+
+	use super::*;
+
+	// --------------------------------------------------------------------------------
+	// --------------------------------------------------------------------------------
+
+	#[derive(Debug, Serialize, Deserialize, PartialEq)]
+	struct Data {
+		name: String,
+		count: u32,
+	}
+
+	#[tokio::test]
+	async fn test_client() {
+		let mut router = Router::new();
+
+		router
+			.resource_mut("/echo")
+			.set_handler_for(Method::POST.to(|Json(data): Json<Data>| async move { Json(data) }));
+
+		router.resource_mut("/peer").set_handler_for(
+			Method::GET.to(|PeerAddr(peer_addr): PeerAddr| async move { peer_addr.to_string() }),
+		);
+
+		router.resource_mut("/login").set_handler_for(
+			Method::POST.to(|| async { [(SET_COOKIE, "session=abc; Path=/; HttpOnly")] }),
+		);
+
+		router
+			.resource_mut("/logout")
+			.set_handler_for(Method::POST.to(|| async { [(SET_COOKIE, "session=; Max-Age=0")] }));
+
+		router
+			.resource_mut("/session")
+			.set_handler_for(Method::GET.to(|head: RequestHead| async move {
+				head
+					.headers_ref()
+					.get(COOKIE)
+					.map(|value| value.to_str().unwrap().to_owned())
+					.unwrap_or_default()
+			}));
+
+		let client = TestClient::new(router.into_arc_service())
+			.with_peer_addr(SocketAddr::from(([10, 0, 0, 1], 4321)));
+
+		// ----------
+
+		let data = Data {
+			name: "test".to_owned(),
+			count: 3,
+		};
+
+		let response = client.post("/echo").json(&data).send().await;
+		assert_eq!(response.status(), StatusCode::OK);
+		assert_eq!(response.header("content-type"), Some("application/json"));
+		assert_eq!(response.json::<Data>(), data);
+
+		let response = client.get("/peer").send().await;
+		assert_eq!(response.text(), "10.0.0.1:4321");
+
+		let response = client.get("/unknown").send().await;
+		assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+		// ----------
+
+		let response = client.get("/session").send().await;
+		assert_eq!(response.text(), "");
+
+		client.post("/login").send().await;
+		assert_eq!(client.cookie("session").as_deref(), Some("abc"));
+
+		let response = client.get("/session").send().await;
+		assert_eq!(response.text(), "session=abc");
+
+		client.post("/logout").send().await;
+		assert_eq!(client.cookie("session"), None);
+
+		client.set_cookie("key", "value");
+
+		let response = client.get("/session").send().await;
+		assert_eq!(response.text(), "key=value");
+	}
+}